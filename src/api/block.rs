@@ -2,7 +2,8 @@ use crate::color::Color;
 use crate::parser::logic::Block as ParserBlock;
 use crate::parser::logic::BlockContainer;
 use ritelinked::LinkedHashMap;
-use std::fmt::Debug;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt::{Debug, Display, Formatter};
 use std::num::ParseIntError;
 use std::str::FromStr;
 use thiserror::Error;
@@ -149,6 +150,187 @@ impl Blocks {
         Self(vec![])
     }
 
+    /// Retrieves a block using its path, returns None if the path doesn't lead to any block
+    pub fn get(&self, path: &BlockPath) -> Option<&Block> {
+        let mut segments = path.0.iter();
+        let mut block = match segments.next()? {
+            BlockPathSegment::Index(index) => self.0.get(*index)?,
+            _ => return None,
+        };
+
+        while let Some(segment) = segments.next() {
+            block = match segment {
+                BlockPathSegment::Argument(index) => {
+                    block.content.get_args().get(*index)?.block()?
+                }
+                BlockPathSegment::SubStack1 | BlockPathSegment::SubStack2 => {
+                    let stack = if *segment == BlockPathSegment::SubStack1 {
+                        block.sub_stack1.as_ref()?
+                    } else {
+                        block.sub_stack2.as_ref()?
+                    };
+
+                    match segments.next()? {
+                        BlockPathSegment::Index(index) => stack.0.get(*index)?,
+                        _ => return None,
+                    }
+                }
+                BlockPathSegment::Index(_) => return None,
+            };
+        }
+
+        Some(block)
+    }
+
+    /// Retrieves a mutable reference of a block using its path, returns None if the path doesn't
+    /// lead to any block
+    pub fn get_mut(&mut self, path: &BlockPath) -> Option<&mut Block> {
+        let mut segments = path.0.iter();
+        let mut block = match segments.next()? {
+            BlockPathSegment::Index(index) => self.0.get_mut(*index)?,
+            _ => return None,
+        };
+
+        while let Some(segment) = segments.next() {
+            block = match segment {
+                BlockPathSegment::Argument(index) => block
+                    .content
+                    .get_args_mut()
+                    .into_iter()
+                    .nth(*index)?
+                    .block_mut()?,
+                BlockPathSegment::SubStack1 | BlockPathSegment::SubStack2 => {
+                    let stack = if *segment == BlockPathSegment::SubStack1 {
+                        block.sub_stack1.as_mut()?
+                    } else {
+                        block.sub_stack2.as_mut()?
+                    };
+
+                    match segments.next()? {
+                        BlockPathSegment::Index(index) => stack.0.get_mut(*index)?,
+                        _ => return None,
+                    }
+                }
+                BlockPathSegment::Index(_) => return None,
+            };
+        }
+
+        Some(block)
+    }
+
+    /// Visits every block recursively (including argument blocks and blocks inside substacks)
+    /// along with their paths. Blocks are visited in the same order as they are in a
+    /// [`BlockContainer`]: the block itself, its argument blocks, its substacks, then the next
+    /// block
+    pub fn visit<F>(&self, mut visitor: F)
+    where
+        F: FnMut(&BlockPath, &Block),
+    {
+        fn visit_stack<F: FnMut(&BlockPath, &Block)>(
+            blocks: &Blocks,
+            path: &mut BlockPath,
+            visitor: &mut F,
+        ) {
+            for (index, block) in blocks.0.iter().enumerate() {
+                path.0.push(BlockPathSegment::Index(index));
+                visit_block(block, path, visitor);
+                path.0.pop();
+            }
+        }
+
+        fn visit_block<F: FnMut(&BlockPath, &Block)>(
+            block: &Block,
+            path: &mut BlockPath,
+            visitor: &mut F,
+        ) {
+            visitor(path, block);
+
+            for (index, arg) in block.content.get_args().into_iter().enumerate() {
+                if let Some(arg_block) = arg.block() {
+                    path.0.push(BlockPathSegment::Argument(index));
+                    visit_block(arg_block, path, visitor);
+                    path.0.pop();
+                }
+            }
+
+            if let Some(stack) = &block.sub_stack1 {
+                path.0.push(BlockPathSegment::SubStack1);
+                visit_stack(stack, path, visitor);
+                path.0.pop();
+            }
+
+            if let Some(stack) = &block.sub_stack2 {
+                path.0.push(BlockPathSegment::SubStack2);
+                visit_stack(stack, path, visitor);
+                path.0.pop();
+            }
+        }
+
+        visit_stack(self, &mut BlockPath::default(), &mut visitor);
+    }
+
+    /// Inserts a block into a stack at the given path, the path must end with an index that is at
+    /// most the length of its stack. A substack is created when a nesting block doesn't have one.
+    /// Returns false when the path doesn't lead to a position in a stack.
+    pub fn insert(&mut self, path: &BlockPath, block: Block) -> bool {
+        let (index, stack) = match path.0.split_last() {
+            Some((BlockPathSegment::Index(index), stack)) => (*index, stack),
+            _ => return false,
+        };
+
+        match self.stack_mut(stack, true) {
+            Some(stack) if index <= stack.0.len() => {
+                stack.0.insert(index, block);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Removes a block (along with its arguments and substacks) from its stack, returns None if
+    /// the path doesn't lead to a block in a stack
+    pub fn remove(&mut self, path: &BlockPath) -> Option<Block> {
+        let (index, stack) = match path.0.split_last()? {
+            (BlockPathSegment::Index(index), stack) => (*index, stack),
+            _ => return None,
+        };
+
+        let stack = self.stack_mut(stack, false)?;
+        if index < stack.0.len() {
+            Some(stack.0.remove(index))
+        } else {
+            None
+        }
+    }
+
+    /// Retrieves a stack of blocks from a path that either is empty (this stack) or ends with a
+    /// substack segment
+    fn stack_mut(&mut self, path: &[BlockPathSegment], create: bool) -> Option<&mut Blocks> {
+        let (segment, block) = match path.split_last() {
+            None => return Some(self),
+            Some((segment, block)) => (segment, BlockPath(block.to_vec())),
+        };
+
+        let block = self.get_mut(&block)?;
+        let nests = match block.block_type {
+            BlockType::Control(BlockControl::OneNest) => 1,
+            BlockType::Control(BlockControl::TwoNest) => 2,
+            _ => 0,
+        };
+
+        let stack = match segment {
+            BlockPathSegment::SubStack1 if nests >= 1 => &mut block.sub_stack1,
+            BlockPathSegment::SubStack2 if nests >= 2 => &mut block.sub_stack2,
+            _ => return None,
+        };
+
+        if create {
+            Some(stack.get_or_insert_with(Blocks::new))
+        } else {
+            stack.as_mut()
+        }
+    }
+
     fn to_block_container(self, starts_with: u32) -> BlockContainer {
         let mut result = Vec::new();
         let mut id_counter = starts_with - 1;
@@ -187,6 +369,12 @@ impl Blocks {
                 .sub_stack1
                 .map(|ss1| {
                     let mut ss1_blocks = ss1.to_block_container(*id_counter + 1);
+                    // the substack pointer points to the first block of the substack
+                    let ss1_first_id = ss1_blocks
+                        .0
+                        .first()
+                        .map(|b| b.id.parse::<i32>().unwrap())
+                        .unwrap_or_else(|| -1);
                     let ss1_last_id = ss1_blocks
                         .0
                         .last()
@@ -198,7 +386,7 @@ impl Blocks {
                     if ss1_last_id != -1 {
                         *id_counter = ss1_last_id as u32;
                     }
-                    ss1_last_id
+                    ss1_first_id
                 })
                 .unwrap_or_else(|| -1);
 
@@ -206,6 +394,12 @@ impl Blocks {
                 .sub_stack2
                 .map(|ss2| {
                     let mut ss2_blocks = ss2.to_block_container(*id_counter + 1);
+                    // the substack pointer points to the first block of the substack
+                    let ss2_first_id = ss2_blocks
+                        .0
+                        .first()
+                        .map(|b| b.id.parse::<i32>().unwrap())
+                        .unwrap_or_else(|| -1);
                     let ss2_last_id = ss2_blocks
                         .0
                         .last()
@@ -217,7 +411,7 @@ impl Blocks {
                     if ss2_last_id != -1 {
                         *id_counter = ss2_last_id as u32;
                     }
-                    ss2_last_id
+                    ss2_first_id
                 })
                 .unwrap_or_else(|| -1);
 
@@ -271,8 +465,9 @@ impl Blocks {
                     Argument::String { value, .. } => match value {
                         ArgValue::Value(val) => val,
                         ArgValue::Block(block) => {
-                            convert_block(&mut block_args, block, &mut id_counter, true);
-                            format!("@{}", id_counter)
+                            let block_id =
+                                convert_block(&mut block_args, block, &mut id_counter, true);
+                            format!("@{}", block_id)
                         }
                         ArgValue::BlockPlaceholder { block_id } => panic!(
                             "tries to convert argument to params but encountered an \
@@ -284,8 +479,9 @@ impl Blocks {
                     Argument::Number { value, .. } => match value {
                         ArgValue::Value(val) => val.to_string(),
                         ArgValue::Block(block) => {
-                            convert_block(&mut block_args, block, &mut id_counter, true);
-                            format!("@{}", id_counter)
+                            let block_id =
+                                convert_block(&mut block_args, block, &mut id_counter, true);
+                            format!("@{}", block_id)
                         }
                         ArgValue::BlockPlaceholder { block_id } => panic!(
                             "tries to convert argument to params but encountered an \
@@ -306,12 +502,14 @@ impl Blocks {
                                     BlockType::Argument(ArgumentBlockReturnType::Boolean),
                                 );
 
-                                convert_block(&mut block_args, block, &mut id_counter, true);
-                                format!("@{}", id_counter)
+                                let block_id =
+                                    convert_block(&mut block_args, block, &mut id_counter, true);
+                                format!("@{}", block_id)
                             }
                             ArgValue::Block(block) => {
-                                convert_block(&mut block_args, block, &mut id_counter, true);
-                                format!("@{}", id_counter)
+                                let block_id =
+                                    convert_block(&mut block_args, block, &mut id_counter, true);
+                                format!("@{}", block_id)
                             }
                             ArgValue::BlockPlaceholder { block_id } => panic!(
                                 "tries to convert argument to params but encountered an \
@@ -324,8 +522,9 @@ impl Blocks {
                     Argument::Menu { value, .. } => match value {
                         ArgValue::Value(val) => val.to_string(),
                         ArgValue::Block(block) => {
-                            convert_block(&mut block_args, block, &mut id_counter, true);
-                            format!("@{}", id_counter)
+                            let block_id =
+                                convert_block(&mut block_args, block, &mut id_counter, true);
+                            format!("@{}", block_id)
                         }
                         ArgValue::BlockPlaceholder { block_id } => panic!(
                             "tries to convert argument to params but encountered an \
@@ -365,6 +564,103 @@ impl IntoIterator for Blocks {
     }
 }
 
+/// A path that leads to a block inside of [`Blocks`], starting from the index of a block in the
+/// top-level stack. For instance, the path `1/ss1/0/arg0` points to the block put in the first
+/// argument of the first block inside the first substack of the second block.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct BlockPath(pub Vec<BlockPathSegment>);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BlockPathSegment {
+    /// The nth block of a stack
+    Index(usize),
+
+    /// The block that is put on the nth argument of a block
+    Argument(usize),
+
+    /// The first substack of a block, followed by an [`BlockPathSegment::Index`]
+    SubStack1,
+
+    /// The second substack of a block, followed by an [`BlockPathSegment::Index`]
+    SubStack2,
+}
+
+impl BlockPath {
+    /// Creates a new path that has the given segment appended to this path
+    pub fn join(&self, segment: BlockPathSegment) -> BlockPath {
+        let mut path = self.clone();
+        path.0.push(segment);
+        path
+    }
+}
+
+impl Display for BlockPath {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (index, segment) in self.0.iter().enumerate() {
+            if index != 0 {
+                f.write_str("/")?;
+            }
+
+            match segment {
+                BlockPathSegment::Index(index) => write!(f, "{}", index)?,
+                BlockPathSegment::Argument(index) => write!(f, "arg{}", index)?,
+                BlockPathSegment::SubStack1 => f.write_str("ss1")?,
+                BlockPathSegment::SubStack2 => f.write_str("ss2")?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for BlockPath {
+    type Err = InvalidBlockPath;
+
+    /// Parses a path in the same format as its [`Display`] implementation
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.is_empty() {
+            return Ok(BlockPath::default());
+        }
+
+        s.split('/')
+            .map(|segment| {
+                Ok(match segment {
+                    "ss1" => BlockPathSegment::SubStack1,
+                    "ss2" => BlockPathSegment::SubStack2,
+                    _ => match segment.strip_prefix("arg") {
+                        Some(index) => BlockPathSegment::Argument(index.parse()?),
+                        None => BlockPathSegment::Index(segment.parse()?),
+                    },
+                })
+            })
+            .collect::<Result<_, ParseIntError>>()
+            .map(BlockPath)
+            .map_err(|_| InvalidBlockPath {
+                path: s.to_string(),
+            })
+    }
+}
+
+impl Serialize for BlockPath {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for BlockPath {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+#[derive(Error, Debug)]
+#[error("`{path}` is not a valid block path")]
+pub struct InvalidBlockPath {
+    pub path: String,
+}
+
 /// A model that represents a block
 #[derive(Debug, Clone, PartialEq)]
 pub struct Block {
@@ -1173,6 +1469,54 @@ pub enum Argument {
     },
 }
 
+impl Argument {
+    /// Retrieves the block that is put on this argument (if any)
+    pub fn block(&self) -> Option<&Block> {
+        match self {
+            Argument::String {
+                value: ArgValue::Block(block),
+                ..
+            }
+            | Argument::Number {
+                value: ArgValue::Block(block),
+                ..
+            }
+            | Argument::Boolean {
+                value: ArgValue::Block(block),
+                ..
+            }
+            | Argument::Menu {
+                value: ArgValue::Block(block),
+                ..
+            } => Some(block),
+            _ => None,
+        }
+    }
+
+    /// Retrieves a mutable reference of the block that is put on this argument (if any)
+    pub fn block_mut(&mut self) -> Option<&mut Block> {
+        match self {
+            Argument::String {
+                value: ArgValue::Block(block),
+                ..
+            }
+            | Argument::Number {
+                value: ArgValue::Block(block),
+                ..
+            }
+            | Argument::Boolean {
+                value: ArgValue::Block(block),
+                ..
+            }
+            | Argument::Menu {
+                value: ArgValue::Block(block),
+                ..
+            } => Some(block),
+            _ => None,
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub enum ArgValue<T: Debug + Clone + PartialEq> {
     Value(T),
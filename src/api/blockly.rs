@@ -0,0 +1,920 @@
+//! Conversion between blocks and [Google Blockly](https://developers.google.com/blockly)'s xml
+//! format, along with blockly block definitions generated from the specs of the blocks.
+//!
+//! Every block is exported as a `<block>` with its id, its arguments are exported as `<value>`s
+//! (or `<field>`s for menus) named `ARG0`, `ARG1`, ..., its substacks as `<statement>`s named
+//! `SUBSTACK1` and `SUBSTACK2`, and the block after it as a `<next>`. Literal values are put in
+//! `text`, `math_number` and `logic_boolean` shadow blocks, which are built into blockly.
+//!
+//! Blockly drops any extra information we put inside of a block, so the spec, color and type of
+//! the blocks are stored in [`BlockDefinitions`] instead, which is also needed to import blocks
+//! back from blockly.
+
+use crate::api::block::BlockConversionError;
+use crate::api::block::{Argument, BlockContent, BlockContentParseError, Blocks, SpecItem};
+use crate::color::Color;
+use crate::parser::logic::{Block as ParserBlock, BlockContainer};
+use crate::util::escape_xml;
+use crate::LinkedHashMap;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+use thiserror::Error;
+
+/// Opcodes whose spec changes depending on what they refer to (a variable, a moreblock, etc), the
+/// blockly type of these blocks contain their spec
+const DYNAMIC_SPEC_OPCODES: [&str; 3] = ["getVar", "getArg", "definedFunc"];
+
+/// Retrieves the blockly block type of a block. It is the opcode of the block itself, or
+/// `opcode:spec` for blocks whose spec isn't always the same (like `getVar` or `definedFunc`)
+pub fn blockly_type(op_code: &str, spec: &str) -> String {
+    if DYNAMIC_SPEC_OPCODES.contains(&op_code) {
+        format!("{}:{}", op_code, spec)
+    } else {
+        op_code.to_string()
+    }
+}
+
+/// The sketchware side of a blockly block definition
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockDefinition {
+    pub op_code: String,
+    pub spec: String,
+    pub r#type: String,
+    pub type_name: String,
+    pub color: Color,
+}
+
+impl BlockDefinition {
+    /// Generates a blockly json block definition of this block
+    pub fn to_blockly_json(&self) -> Result<Value, BlockContentParseError> {
+        let content = BlockContent::parse_wo_params(&self.spec)?;
+
+        let mut message = String::new();
+        let mut args = Vec::new();
+
+        for item in content.items {
+            if !message.is_empty() {
+                message.push(' ');
+            }
+
+            match item {
+                SpecItem::Text(text) => message.push_str(&text.replace('%', "%%")),
+                SpecItem::Parameter(arg) => {
+                    let name = format!("ARG{}", args.len());
+                    args.push(match arg {
+                        Argument::String { .. } => {
+                            json!({ "type": "input_value", "name": name, "check": "String" })
+                        }
+                        Argument::Number { .. } => {
+                            json!({ "type": "input_value", "name": name, "check": "Number" })
+                        }
+                        Argument::Boolean { .. } => {
+                            json!({ "type": "input_value", "name": name, "check": "Boolean" })
+                        }
+                        Argument::Menu { .. } => {
+                            json!({ "type": "field_input", "name": name, "text": "" })
+                        }
+                    });
+
+                    let _ = write!(message, "%{}", args.len());
+                }
+            }
+        }
+
+        let (r, g, b) = self.color.rgb();
+        let mut definition = json!({
+            "type": blockly_type(&self.op_code, &self.spec),
+            "message0": message,
+            "args0": args,
+            "inputsInline": true,
+            "colour": format!("#{:02x}{:02x}{:02x}", r, g, b),
+            "sketchware": self,
+        });
+
+        let object = definition.as_object_mut().unwrap();
+        let mut statement = |index: u8, label: &str, name: &str| {
+            object.insert(format!("message{}", index), json!(label));
+            object.insert(
+                format!("args{}", index),
+                json!([{ "type": "input_statement", "name": name }]),
+            );
+        };
+
+        match self.r#type.as_str() {
+            "c" => statement(1, "%1", "SUBSTACK1"),
+            "e" => {
+                statement(1, "%1", "SUBSTACK1");
+                statement(2, "else %1", "SUBSTACK2");
+            }
+            _ => (),
+        }
+
+        let output = match self.r#type.as_str() {
+            "b" => Some("Boolean"),
+            "s" => Some("String"),
+            "d" => Some("Number"),
+            "v" => Some("View"),
+            "p" => Some("Component"),
+            "l" => Some("List"),
+            _ => None,
+        };
+
+        if let Some(output) = output {
+            object.insert("output".to_string(), json!(output));
+        } else {
+            object.insert("previousStatement".to_string(), Value::Null);
+
+            // blocks like `break` or `finish activity` can't have any blocks after them
+            if self.r#type != "f" {
+                object.insert("nextStatement".to_string(), Value::Null);
+            }
+        }
+
+        Ok(definition)
+    }
+}
+
+impl From<&ParserBlock> for BlockDefinition {
+    fn from(block: &ParserBlock) -> Self {
+        BlockDefinition {
+            op_code: block.op_code.to_owned(),
+            spec: block.spec.to_owned(),
+            r#type: block.r#type.to_owned(),
+            type_name: block.type_name.to_owned(),
+            color: block.color,
+        }
+    }
+}
+
+/// A collection of block definitions, keyed by their blockly type (see [`blockly_type`])
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct BlockDefinitions(pub LinkedHashMap<String, BlockDefinition>);
+
+impl BlockDefinitions {
+    pub fn new() -> Self {
+        Self(LinkedHashMap::new())
+    }
+
+    /// Collects the definitions of every block inside the given block container
+    pub fn from_container(container: &BlockContainer) -> Self {
+        let mut definitions = Self::new();
+        definitions.add_container(container);
+        definitions
+    }
+
+    /// Collects the definitions of every block inside the given blocks
+    pub fn from_blocks(blocks: &Blocks) -> Self {
+        Self::from_container(&blocks.clone().into())
+    }
+
+    /// Adds the definitions of every block inside the given block container, blocks that
+    /// are already defined are skipped
+    pub fn add_container(&mut self, container: &BlockContainer) {
+        for block in &container.0 {
+            let r#type = blockly_type(&block.op_code, &block.spec);
+
+            if !self.0.contains_key(&r#type) {
+                self.0.insert(r#type, BlockDefinition::from(block));
+            }
+        }
+    }
+
+    /// Retrieves a block definition by its blockly type
+    pub fn get(&self, blockly_type: &str) -> Option<&BlockDefinition> {
+        self.0.get(blockly_type)
+    }
+
+    /// Generates a json array of blockly block definitions, can be loaded using
+    /// `Blockly.defineBlocksWithJsonArray`
+    pub fn to_json(&self) -> Result<String, BlockContentParseError> {
+        let definitions = self
+            .0
+            .values()
+            .map(BlockDefinition::to_blockly_json)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Value::Array(definitions).to_string())
+    }
+
+    /// Parses block definitions from a json that was generated by [`BlockDefinitions::to_json`]
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        #[derive(Deserialize)]
+        struct Entry {
+            r#type: String,
+            sketchware: BlockDefinition,
+        }
+
+        let entries: Vec<Entry> = serde_json::from_str(json)?;
+
+        Ok(Self(
+            entries
+                .into_iter()
+                .map(|entry| (entry.r#type, entry.sketchware))
+                .collect(),
+        ))
+    }
+}
+
+/// Converts blocks into a blockly xml, the ids of the blocks are the ones they would get on
+/// their conversion into a [`BlockContainer`]
+pub fn blocks_to_xml(blocks: &Blocks) -> Result<String, BlocklyExportError> {
+    to_xml(&blocks.clone().into())
+}
+
+/// Converts a block container into a blockly xml, keeps the ids of the blocks
+pub fn to_xml(container: &BlockContainer) -> Result<String, BlocklyExportError> {
+    let blocks = container
+        .0
+        .iter()
+        .map(|block| (block.id.as_str(), block))
+        .collect::<HashMap<_, _>>();
+
+    let mut result = String::from("<xml xmlns=\"https://developers.google.com/blockly/xml\">\n");
+
+    if let Some(first) = container.0.first() {
+        let mut visited = HashSet::new();
+        write_block(&mut result, first, &blocks, &mut visited, 1, true)?;
+    }
+
+    result.push_str("</xml>\n");
+    Ok(result)
+}
+
+fn write_block<'a>(
+    out: &mut String,
+    block: &'a ParserBlock,
+    blocks: &HashMap<&str, &'a ParserBlock>,
+    visited: &mut HashSet<&'a str>,
+    depth: usize,
+    top_level: bool,
+) -> Result<(), BlocklyExportError> {
+    if !visited.insert(block.id.as_str()) {
+        Err(BlocklyExportError::CyclicReference {
+            id: block.id.to_owned(),
+        })?
+    }
+
+    let indent = "  ".repeat(depth);
+    let get_block = |id: &str| {
+        blocks
+            .get(id)
+            .copied()
+            .ok_or_else(|| BlocklyExportError::BlockNotFound { id: id.to_string() })
+    };
+
+    let _ = write!(
+        out,
+        "{}<block type=\"{}\" id=\"{}\"",
+        indent,
+        escape_xml(&blockly_type(&block.op_code, &block.spec)),
+        escape_xml(&block.id)
+    );
+    if top_level {
+        out.push_str(" x=\"0\" y=\"0\"");
+    }
+    out.push_str(">\n");
+
+    let content = BlockContent::parse_wo_params(&block.spec).map_err(|source| {
+        BlocklyExportError::SpecParseError {
+            id: block.id.to_owned(),
+            source,
+        }
+    })?;
+    let args = content.get_args();
+
+    if args.len() != block.parameters.len() {
+        Err(BlocklyExportError::ArgumentCountMismatch {
+            id: block.id.to_owned(),
+            expected: args.len(),
+            found: block.parameters.len(),
+        })?
+    }
+
+    for (index, (arg, param)) in args.into_iter().zip(&block.parameters).enumerate() {
+        let name = format!("ARG{}", index);
+
+        if let Some(id) = param.strip_prefix('@') {
+            let _ = writeln!(out, "{}  <value name=\"{}\">", indent, name);
+            write_block(out, get_block(id)?, blocks, visited, depth + 2, false)?;
+            let _ = writeln!(out, "{}  </value>", indent);
+            continue;
+        }
+
+        let shadow = match arg {
+            Argument::String { .. } => ("text", "TEXT", param.to_owned()),
+            Argument::Number { .. } => ("math_number", "NUM", param.to_owned()),
+            Argument::Boolean { .. } if param.is_empty() => {
+                let _ = writeln!(out, "{}  <value name=\"{}\"></value>", indent, name);
+                continue;
+            }
+            Argument::Boolean { .. } => ("logic_boolean", "BOOL", param.to_uppercase()),
+            Argument::Menu { .. } => {
+                let _ = writeln!(
+                    out,
+                    "{}  <field name=\"{}\">{}</field>",
+                    indent,
+                    name,
+                    escape_xml(param)
+                );
+                continue;
+            }
+        };
+
+        let _ = writeln!(
+            out,
+            "{}  <value name=\"{}\"><shadow type=\"{}\"><field name=\"{}\">{}</field></shadow></value>",
+            indent,
+            name,
+            shadow.0,
+            shadow.1,
+            escape_xml(&shadow.2)
+        );
+    }
+
+    for (name, pointer) in [
+        ("SUBSTACK1", block.sub_stack1),
+        ("SUBSTACK2", block.sub_stack2),
+    ] {
+        if pointer.is_negative() {
+            continue;
+        }
+
+        let _ = writeln!(out, "{}  <statement name=\"{}\">", indent, name);
+        write_stack(out, &pointer.to_string(), blocks, visited, depth + 2)?;
+        let _ = writeln!(out, "{}  </statement>", indent);
+    }
+
+    if !block.next_block.is_negative() {
+        let _ = writeln!(out, "{}  <next>", indent);
+        write_block(
+            out,
+            get_block(&block.next_block.to_string())?,
+            blocks,
+            visited,
+            depth + 2,
+            false,
+        )?;
+        let _ = writeln!(out, "{}  </next>", indent);
+    }
+
+    let _ = writeln!(out, "{}</block>", indent);
+    Ok(())
+}
+
+fn write_stack<'a>(
+    out: &mut String,
+    first_id: &str,
+    blocks: &HashMap<&str, &'a ParserBlock>,
+    visited: &mut HashSet<&'a str>,
+    depth: usize,
+) -> Result<(), BlocklyExportError> {
+    let block = blocks
+        .get(first_id)
+        .copied()
+        .ok_or_else(|| BlocklyExportError::BlockNotFound {
+            id: first_id.to_string(),
+        })?;
+
+    write_block(out, block, blocks, visited, depth, false)
+}
+
+#[derive(Error, Debug)]
+pub enum BlocklyExportError {
+    #[error("block with id {id} not found")]
+    BlockNotFound { id: String },
+
+    #[error("block with id {id} is referenced more than once")]
+    CyclicReference { id: String },
+
+    #[error("failed to parse the spec of block with id {id}")]
+    SpecParseError {
+        id: String,
+        source: BlockContentParseError,
+    },
+
+    #[error("block with id {id} has {found} parameters, but its spec has {expected} arguments")]
+    ArgumentCountMismatch {
+        id: String,
+        expected: usize,
+        found: usize,
+    },
+}
+
+/// Converts a blockly xml back into blocks, see [`from_xml`]
+pub fn blocks_from_xml(
+    xml: &str,
+    definitions: &BlockDefinitions,
+) -> Result<Blocks, BlocklyImportError> {
+    Blocks::try_from(from_xml(xml, definitions)?)
+        .map_err(|source| BlocklyImportError::BlockConversionError { source })
+}
+
+/// Converts a blockly xml back into a block container, the block definitions are used to
+/// retrieve the spec, color and type of the blocks.
+///
+/// Numeric ids are kept, while blocks that were created inside blockly (with its random ids) are
+/// given new ids after the biggest id.
+pub fn from_xml(
+    xml: &str,
+    definitions: &BlockDefinitions,
+) -> Result<BlockContainer, BlocklyImportError> {
+    let root = xml::parse(xml).map_err(|source| BlocklyImportError::MalformedXml { source })?;
+
+    let top_level = if root.name == "block" {
+        vec![&root]
+    } else {
+        root.elements("block").collect()
+    };
+
+    let first = match top_level.as_slice() {
+        [] => return Ok(BlockContainer(vec![])),
+        [first] => *first,
+        _ => Err(BlocklyImportError::MultipleTopLevelBlocks {
+            count: top_level.len(),
+        })?,
+    };
+
+    // assign ids to every block first, so we know which ids are taken
+    let mut elements = Vec::new();
+    collect_blocks(first, &mut elements);
+
+    let mut used_ids = HashSet::new();
+    let mut ids = Vec::with_capacity(elements.len());
+
+    for element in &elements {
+        let id = element
+            .attribute("id")
+            .and_then(|id| id.parse::<u32>().ok())
+            .filter(|id| used_ids.insert(*id));
+
+        ids.push(id);
+    }
+
+    let mut next_id = used_ids.iter().max().map(|id| id + 1).unwrap_or(10);
+    let ids = ids
+        .into_iter()
+        .map(|id| {
+            id.unwrap_or_else(|| {
+                next_id += 1;
+                next_id - 1
+            })
+        })
+        .collect::<Vec<_>>();
+
+    let ids = elements
+        .into_iter()
+        .zip(ids)
+        .map(|(element, id)| (element as *const xml::Element, id))
+        .collect::<HashMap<_, _>>();
+
+    // every block elements has been collected, this wouldn't panic
+    let id_of = |element: &xml::Element| ids[&(element as *const xml::Element)];
+
+    let mut result = Vec::new();
+    import_stack(first, definitions, &id_of, &mut result)?;
+
+    Ok(BlockContainer(result))
+}
+
+/// Collects every block elements in the order they are going to be in the block container
+fn collect_blocks<'a>(element: &'a xml::Element, result: &mut Vec<&'a xml::Element>) {
+    let mut current = Some(element);
+
+    while let Some(block) = current {
+        result.push(block);
+
+        for value in block.elements("value") {
+            if let Some(arg) = value.element("block") {
+                collect_blocks(arg, result);
+            }
+        }
+
+        for statement in block.elements("statement") {
+            if let Some(first) = statement.element("block") {
+                collect_blocks(first, result);
+            }
+        }
+
+        current = block.element("next").and_then(|next| next.element("block"));
+    }
+}
+
+fn import_stack<F>(
+    element: &xml::Element,
+    definitions: &BlockDefinitions,
+    id_of: &F,
+    result: &mut Vec<ParserBlock>,
+) -> Result<(), BlocklyImportError>
+where
+    F: Fn(&xml::Element) -> u32,
+{
+    let mut current = Some(element);
+
+    while let Some(element) = current {
+        let next = element
+            .element("next")
+            .and_then(|next| next.element("block"));
+        import_block(element, next.map(id_of), definitions, id_of, result)?;
+        current = next;
+    }
+
+    Ok(())
+}
+
+fn import_block<F>(
+    element: &xml::Element,
+    next_id: Option<u32>,
+    definitions: &BlockDefinitions,
+    id_of: &F,
+    result: &mut Vec<ParserBlock>,
+) -> Result<(), BlocklyImportError>
+where
+    F: Fn(&xml::Element) -> u32,
+{
+    let id = id_of(element);
+    let r#type = element.attribute("type").unwrap_or_default();
+    let definition =
+        definitions
+            .get(r#type)
+            .ok_or_else(|| BlocklyImportError::UnknownBlockType {
+                id,
+                r#type: r#type.to_string(),
+            })?;
+
+    let content = BlockContent::parse_wo_params(&definition.spec).map_err(|source| {
+        BlocklyImportError::SpecParseError {
+            r#type: r#type.to_string(),
+            source,
+        }
+    })?;
+
+    // the block is placed first, followed by its arguments and its substacks
+    let index = result.len();
+    result.push(ParserBlock {
+        color: definition.color,
+        id: id.to_string(),
+        next_block: next_id.map(|id| id as i32).unwrap_or(-1),
+        op_code: definition.op_code.to_owned(),
+        parameters: vec![],
+        spec: definition.spec.to_owned(),
+        sub_stack1: -1,
+        sub_stack2: -1,
+        r#type: definition.r#type.to_owned(),
+        type_name: definition.type_name.to_owned(),
+    });
+
+    let mut parameters = Vec::new();
+
+    for (arg_index, arg) in content.get_args().into_iter().enumerate() {
+        let name = format!("ARG{}", arg_index);
+
+        let value = element
+            .elements("value")
+            .find(|value| value.attribute("name") == Some(name.as_str()));
+
+        if let Some(arg_block) = value.and_then(|value| value.element("block")) {
+            parameters.push(format!("@{}", id_of(arg_block)));
+            import_block(arg_block, None, definitions, id_of, result)?;
+            continue;
+        }
+
+        let literal = value
+            .and_then(|value| value.element("shadow"))
+            .and_then(|shadow| shadow.element("field"))
+            .or_else(|| {
+                element
+                    .elements("field")
+                    .find(|field| field.attribute("name") == Some(name.as_str()))
+            })
+            .map(|field| field.text())
+            .unwrap_or_default();
+
+        parameters.push(match arg {
+            Argument::Boolean { .. } => literal.to_lowercase(),
+            _ => literal,
+        });
+    }
+
+    result[index].parameters = parameters;
+
+    for (name, is_first) in [("SUBSTACK1", true), ("SUBSTACK2", false)] {
+        let first = element
+            .elements("statement")
+            .find(|statement| statement.attribute("name") == Some(name))
+            .and_then(|statement| statement.element("block"));
+
+        if let Some(first) = first {
+            let pointer = id_of(first) as i32;
+
+            if is_first {
+                result[index].sub_stack1 = pointer;
+            } else {
+                result[index].sub_stack2 = pointer;
+            }
+
+            import_stack(first, definitions, id_of, result)?;
+        }
+    }
+
+    Ok(())
+}
+
+#[derive(Error, Debug)]
+pub enum BlocklyImportError {
+    #[error("malformed xml")]
+    MalformedXml { source: xml::XmlParseError },
+
+    #[error("expected only one stack of blocks, found {count}")]
+    MultipleTopLevelBlocks { count: usize },
+
+    #[error("block with id {id} has an unknown type `{r#type}`")]
+    UnknownBlockType { id: u32, r#type: String },
+
+    #[error("failed to parse the spec of the block definition `{r#type}`")]
+    SpecParseError {
+        r#type: String,
+        source: BlockContentParseError,
+    },
+
+    #[error("failed to convert the imported blocks")]
+    BlockConversionError { source: BlockConversionError },
+}
+
+/// A tiny xml parser, only supports what blockly outputs: elements, attributes, texts, comments
+/// and the xml declaration
+pub mod xml {
+    use thiserror::Error;
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct Element {
+        pub name: String,
+        pub attributes: Vec<(String, String)>,
+        pub children: Vec<Node>,
+    }
+
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum Node {
+        Element(Element),
+        Text(String),
+    }
+
+    impl Element {
+        pub fn attribute(&self, name: &str) -> Option<&str> {
+            self.attributes
+                .iter()
+                .find(|(key, _)| key == name)
+                .map(|(_, value)| value.as_str())
+        }
+
+        /// Retrieves the child elements with the given name
+        pub fn elements<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Element> {
+            self.children.iter().filter_map(move |node| match node {
+                Node::Element(element) if element.name == name => Some(element),
+                _ => None,
+            })
+        }
+
+        /// Retrieves the first child element with the given name
+        pub fn element<'a>(&'a self, name: &'a str) -> Option<&'a Element> {
+            self.elements(name).next()
+        }
+
+        /// Retrieves the text content of this element
+        pub fn text(&self) -> String {
+            self.children
+                .iter()
+                .filter_map(|node| match node {
+                    Node::Text(text) => Some(text.as_str()),
+                    _ => None,
+                })
+                .collect()
+        }
+    }
+
+    /// Parses an xml document and returns its root element
+    pub fn parse(input: &str) -> Result<Element, XmlParseError> {
+        let mut parser = Parser { input, position: 0 };
+
+        parser.skip_misc()?;
+        let root = parser.element()?;
+        parser.skip_misc()?;
+
+        if parser.position != input.len() {
+            Err(parser.error("unexpected content after the root element"))?
+        }
+
+        Ok(root)
+    }
+
+    #[derive(Error, Debug)]
+    #[error("{reason} at byte {position}")]
+    pub struct XmlParseError {
+        pub position: usize,
+        pub reason: &'static str,
+    }
+
+    struct Parser<'a> {
+        input: &'a str,
+        position: usize,
+    }
+
+    impl<'a> Parser<'a> {
+        fn rest(&self) -> &'a str {
+            &self.input[self.position..]
+        }
+
+        fn error(&self, reason: &'static str) -> XmlParseError {
+            XmlParseError {
+                position: self.position,
+                reason,
+            }
+        }
+
+        fn skip_whitespace(&mut self) {
+            let rest = self.rest();
+            self.position += rest.len() - rest.trim_start().len();
+        }
+
+        /// Skips until after the given pattern
+        fn skip_past(&mut self, pattern: &str) -> Result<(), XmlParseError> {
+            match self.rest().find(pattern) {
+                Some(index) => {
+                    self.position += index + pattern.len();
+                    Ok(())
+                }
+                None => Err(self.error("unterminated construct")),
+            }
+        }
+
+        /// Skips whitespaces, comments, xml declarations and doctypes
+        fn skip_misc(&mut self) -> Result<(), XmlParseError> {
+            loop {
+                self.skip_whitespace();
+
+                if self.rest().starts_with("<!--") {
+                    self.skip_past("-->")?;
+                } else if self.rest().starts_with("<?") {
+                    self.skip_past("?>")?;
+                } else if self.rest().starts_with("<!") {
+                    self.skip_past(">")?;
+                } else {
+                    return Ok(());
+                }
+            }
+        }
+
+        fn name(&mut self) -> Result<String, XmlParseError> {
+            let rest = self.rest();
+            let end = rest
+                .find(|c: char| c.is_whitespace() || c == '>' || c == '/' || c == '=')
+                .unwrap_or(rest.len());
+
+            if end == 0 {
+                Err(self.error("expected a name"))?
+            }
+
+            self.position += end;
+            Ok(rest[..end].to_string())
+        }
+
+        fn element(&mut self) -> Result<Element, XmlParseError> {
+            if !self.rest().starts_with('<') {
+                Err(self.error("expected an element"))?
+            }
+            self.position += 1;
+
+            let name = self.name()?;
+            let mut attributes = Vec::new();
+
+            loop {
+                self.skip_whitespace();
+
+                if self.rest().starts_with("/>") {
+                    self.position += 2;
+                    return Ok(Element {
+                        name,
+                        attributes,
+                        children: vec![],
+                    });
+                }
+
+                if self.rest().starts_with('>') {
+                    self.position += 1;
+                    break;
+                }
+
+                let key = self.name()?;
+                self.skip_whitespace();
+
+                if !self.rest().starts_with('=') {
+                    Err(self.error("expected `=` after an attribute name"))?
+                }
+                self.position += 1;
+                self.skip_whitespace();
+
+                let quote = match self.rest().chars().next() {
+                    Some(quote @ ('"' | '\'')) => quote,
+                    _ => Err(self.error("expected a quoted attribute value"))?,
+                };
+                self.position += 1;
+
+                let end = self
+                    .rest()
+                    .find(quote)
+                    .ok_or_else(|| self.error("unterminated attribute value"))?;
+                let value = unescape(&self.rest()[..end])
+                    .ok_or_else(|| self.error("invalid entity in an attribute value"))?;
+                self.position += end + 1;
+
+                attributes.push((key, value));
+            }
+
+            let mut children = Vec::new();
+
+            loop {
+                let rest = self.rest();
+
+                if rest.starts_with("</") {
+                    self.position += 2;
+                    if self.name()? != name {
+                        Err(self.error("mismatched closing tag"))?
+                    }
+                    self.skip_whitespace();
+
+                    if !self.rest().starts_with('>') {
+                        Err(self.error("expected `>`"))?
+                    }
+                    self.position += 1;
+
+                    break;
+                } else if rest.starts_with("<!--") {
+                    self.skip_past("-->")?;
+                } else if rest.starts_with("<![CDATA[") {
+                    self.position += "<![CDATA[".len();
+                    let end = self
+                        .rest()
+                        .find("]]>")
+                        .ok_or_else(|| self.error("unterminated cdata"))?;
+                    children.push(Node::Text(self.rest()[..end].to_string()));
+                    self.position += end + 3;
+                } else if rest.starts_with('<') {
+                    children.push(Node::Element(self.element()?));
+                } else if rest.is_empty() {
+                    Err(self.error("unexpected end of input"))?
+                } else {
+                    let end = rest.find('<').unwrap_or(rest.len());
+                    let text = unescape(&rest[..end])
+                        .ok_or_else(|| self.error("invalid entity in a text"))?;
+                    self.position += end;
+
+                    children.push(Node::Text(text));
+                }
+            }
+
+            Ok(Element {
+                name,
+                attributes,
+                children,
+            })
+        }
+    }
+
+    /// Decodes xml entities, returns None if an invalid entity is found
+    fn unescape(s: &str) -> Option<String> {
+        let mut result = String::with_capacity(s.len());
+        let mut rest = s;
+
+        while let Some(start) = rest.find('&') {
+            result.push_str(&rest[..start]);
+            rest = &rest[start + 1..];
+
+            let end = rest.find(';')?;
+            let entity = &rest[..end];
+
+            result.push(match entity {
+                "lt" => '<',
+                "gt" => '>',
+                "amp" => '&',
+                "quot" => '"',
+                "apos" => '\'',
+                _ => {
+                    let code = if let Some(hex) = entity.strip_prefix("#x") {
+                        u32::from_str_radix(hex, 16).ok()?
+                    } else {
+                        entity.strip_prefix('#')?.parse().ok()?
+                    };
+
+                    char::from_u32(code)?
+                }
+            });
+
+            rest = &rest[end + 1..];
+        }
+
+        result.push_str(rest);
+        Some(result)
+    }
+}
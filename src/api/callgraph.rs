@@ -0,0 +1,357 @@
+//! Call graphs of screens: which events and moreblocks call which moreblocks. Built with
+//! [`CallGraph::build`], it can find unreachable moreblocks, recursion, what runs from a given
+//! event, and can be exported as DOT ([`CallGraph::to_dot`]) or Mermaid
+//! ([`CallGraph::to_mermaid`]).
+
+use crate::api::block::BlockPath;
+use crate::api::screen::Screen;
+use crate::api::snippet::first_text;
+use crate::util::{escape_dot, escape_mermaid};
+use std::collections::{HashMap, HashSet};
+use std::fmt::{Display, Formatter, Write};
+
+/// The block container id of the onCreate event
+const ON_CREATE: &str = "onCreate_initializeLogic";
+
+/// A node of a [`CallGraph`]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Node {
+    /// An event, identified by its block container id
+    Event(String),
+    MoreBlock(String),
+}
+
+impl Node {
+    /// The node of the onCreate event
+    pub fn on_create() -> Node {
+        Node::Event(ON_CREATE.to_string())
+    }
+
+    /// An id of this node that's unique across the graph and only contains alphanumeric
+    /// characters and underscores
+    fn graph_id(&self) -> String {
+        match self {
+            Node::Event(container) => format!("e_{}", container),
+            Node::MoreBlock(name) => format!("m_{}", name),
+        }
+    }
+}
+
+impl Display for Node {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Node::Event(container) => f.write_str(container),
+            Node::MoreBlock(name) => f.write_str(name),
+        }
+    }
+}
+
+/// A moreblock call (a `definedFunc` block)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Call {
+    pub from: Node,
+
+    /// The name of the called moreblock
+    pub to: String,
+
+    /// Where the call is in the blocks of `from`
+    pub path: BlockPath,
+}
+
+/// A call graph of a screen
+#[derive(Debug, Clone, PartialEq)]
+pub struct CallGraph {
+    /// The layout name of the screen
+    pub screen: String,
+
+    /// Every event and moreblock of the screen, events come first
+    pub nodes: Vec<Node>,
+
+    /// Calls to moreblocks that exist, in the order they appear
+    pub calls: Vec<Call>,
+
+    /// Calls to moreblocks that don't exist
+    pub unresolved: Vec<Call>,
+}
+
+impl CallGraph {
+    /// Builds the call graph of a screen
+    pub fn build(screen: &Screen) -> CallGraph {
+        let mut graph = CallGraph {
+            screen: screen.layout_name.to_owned(),
+            nodes: vec![],
+            calls: vec![],
+            unresolved: vec![],
+        };
+
+        let events = screen
+            .events
+            .iter()
+            .map(|event| (Node::Event(event.get_block_container_id()), &event.code));
+        let more_blocks = screen
+            .more_blocks
+            .iter()
+            .map(|(name, more_block)| (Node::MoreBlock(name.to_owned()), &more_block.code));
+
+        for (node, blocks) in events.chain(more_blocks) {
+            blocks.visit(|path, block| {
+                if block.op_code != "definedFunc" {
+                    return;
+                }
+
+                let to = match first_text(block) {
+                    Some(to) => to.to_string(),
+                    None => return,
+                };
+
+                let call = Call {
+                    from: node.to_owned(),
+                    to,
+                    path: path.to_owned(),
+                };
+
+                if screen.more_blocks.contains_key(&call.to) {
+                    graph.calls.push(call);
+                } else {
+                    graph.unresolved.push(call);
+                }
+            });
+
+            graph.nodes.push(node);
+        }
+
+        graph
+    }
+
+    /// Retrieves the names of the moreblocks a node calls directly, without duplicates
+    pub fn callees(&self, node: &Node) -> Vec<&str> {
+        let mut callees = Vec::new();
+
+        for call in self.calls.iter().filter(|call| &call.from == node) {
+            if !callees.contains(&call.to.as_str()) {
+                callees.push(call.to.as_str());
+            }
+        }
+
+        callees
+    }
+
+    /// Retrieves the events and moreblocks that call a moreblock directly
+    pub fn callers(&self, more_block: &str) -> Vec<&Node> {
+        let mut callers = Vec::new();
+
+        for call in self.calls.iter().filter(|call| call.to == more_block) {
+            if !callers.contains(&&call.from) {
+                callers.push(&call.from);
+            }
+        }
+
+        callers
+    }
+
+    /// Retrieves the names of every moreblock that runs (directly or indirectly) when a node
+    /// runs, in the order they are first called
+    pub fn reachable_from(&self, node: &Node) -> Vec<&str> {
+        let mut reachable: Vec<&str> = Vec::new();
+        let mut pending = vec![node.to_owned()];
+        let mut index = 0;
+
+        loop {
+            for callee in self.callees(&pending[index]) {
+                if !reachable.contains(&callee) {
+                    reachable.push(callee);
+                    pending.push(Node::MoreBlock(callee.to_string()));
+                }
+            }
+
+            index += 1;
+            if index == pending.len() {
+                break reachable;
+            }
+        }
+    }
+
+    /// Retrieves the names of the moreblocks that aren't reachable from any event
+    pub fn unreachable(&self) -> Vec<&str> {
+        let reachable: HashSet<&str> = self
+            .nodes
+            .iter()
+            .filter(|node| matches!(node, Node::Event(_)))
+            .flat_map(|node| self.reachable_from(node))
+            .collect();
+
+        self.nodes
+            .iter()
+            .filter_map(|node| match node {
+                Node::MoreBlock(name) if !reachable.contains(name.as_str()) => Some(name.as_str()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Finds recursion, each cycle is a group of moreblocks that (indirectly) call each other,
+    /// or a single moreblock that calls itself. Moreblocks in a cycle are in the order they are
+    /// declared.
+    pub fn cycles(&self) -> Vec<Vec<&str>> {
+        // tarjan's strongly connected components algorithm
+        struct State<'a> {
+            graph: &'a CallGraph,
+            index: usize,
+            indices: HashMap<&'a str, usize>,
+            low_links: HashMap<&'a str, usize>,
+            stack: Vec<&'a str>,
+            components: Vec<Vec<&'a str>>,
+        }
+
+        impl<'a> State<'a> {
+            fn connect(&mut self, name: &'a str) {
+                self.indices.insert(name, self.index);
+                self.low_links.insert(name, self.index);
+                self.index += 1;
+                self.stack.push(name);
+
+                for callee in self.graph.callees(&Node::MoreBlock(name.to_string())) {
+                    if !self.indices.contains_key(callee) {
+                        self.connect(callee);
+                        let low_link = self.low_links[callee].min(self.low_links[name]);
+                        self.low_links.insert(name, low_link);
+                    } else if self.stack.contains(&callee) {
+                        let low_link = self.indices[callee].min(self.low_links[name]);
+                        self.low_links.insert(name, low_link);
+                    }
+                }
+
+                if self.low_links[name] == self.indices[name] {
+                    let position = self.stack.iter().rposition(|item| *item == name).unwrap();
+                    self.components.push(self.stack.split_off(position));
+                }
+            }
+        }
+
+        let more_blocks: Vec<&str> = self
+            .nodes
+            .iter()
+            .filter_map(|node| match node {
+                Node::MoreBlock(name) => Some(name.as_str()),
+                _ => None,
+            })
+            .collect();
+
+        let mut state = State {
+            graph: self,
+            index: 0,
+            indices: HashMap::new(),
+            low_links: HashMap::new(),
+            stack: vec![],
+            components: vec![],
+        };
+
+        for name in &more_blocks {
+            if !state.indices.contains_key(name) {
+                state.connect(name);
+            }
+        }
+
+        let mut cycles: Vec<Vec<&str>> = state
+            .components
+            .into_iter()
+            .filter(|component| {
+                component.len() > 1
+                    || self
+                        .callees(&Node::MoreBlock(component[0].to_string()))
+                        .contains(&component[0])
+            })
+            .map(|component| {
+                more_blocks
+                    .iter()
+                    .copied()
+                    .filter(|name| component.contains(name))
+                    .collect()
+            })
+            .collect();
+
+        cycles.sort_by_key(|cycle| more_blocks.iter().position(|name| *name == cycle[0]));
+        cycles
+    }
+
+    /// Exports this graph in the DOT language, unreachable moreblocks are dashed
+    pub fn to_dot(&self) -> String {
+        let unreachable = self.unreachable();
+        let mut result = format!("digraph \"{}\" {{\n", escape_dot(&self.screen));
+
+        for node in &self.nodes {
+            let attributes = match node {
+                Node::Event(_) => "shape=box",
+                Node::MoreBlock(name) if unreachable.contains(&name.as_str()) => {
+                    "shape=ellipse, style=dashed"
+                }
+                Node::MoreBlock(_) => "shape=ellipse",
+            };
+
+            writeln!(
+                result,
+                "    {} [label=\"{}\", {}];",
+                node.graph_id(),
+                escape_dot(&node.to_string()),
+                attributes
+            )
+            .unwrap();
+        }
+
+        for (from, to) in self.edges() {
+            writeln!(result, "    {} -> {};", from.graph_id(), to.graph_id()).unwrap();
+        }
+
+        result.push_str("}\n");
+        result
+    }
+
+    /// Exports this graph as a Mermaid flowchart, unreachable moreblocks are dashed
+    pub fn to_mermaid(&self) -> String {
+        let unreachable = self.unreachable();
+        let mut result = String::from("flowchart LR\n");
+
+        for node in &self.nodes {
+            let label = escape_mermaid(&node.to_string());
+
+            match node {
+                Node::Event(_) => writeln!(result, "    {}[\"{}\"]", node.graph_id(), label),
+                Node::MoreBlock(_) => writeln!(result, "    {}([\"{}\"])", node.graph_id(), label),
+            }
+            .unwrap();
+        }
+
+        for (from, to) in self.edges() {
+            writeln!(result, "    {} --> {}", from.graph_id(), to.graph_id()).unwrap();
+        }
+
+        if !unreachable.is_empty() {
+            result.push_str("    classDef unreachable stroke-dasharray: 5 5\n");
+
+            for name in unreachable {
+                writeln!(
+                    result,
+                    "    class {} unreachable",
+                    Node::MoreBlock(name.to_string()).graph_id()
+                )
+                .unwrap();
+            }
+        }
+
+        result
+    }
+
+    /// Retrieves the calls without duplicates
+    fn edges(&self) -> Vec<(&Node, Node)> {
+        let mut edges = Vec::new();
+
+        for call in &self.calls {
+            let edge = (&call.from, Node::MoreBlock(call.to.to_owned()));
+            if !edges.contains(&edge) {
+                edges.push(edge);
+            }
+        }
+
+        edges
+    }
+}
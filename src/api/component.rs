@@ -1,5 +1,6 @@
+use crate::api::event::ComponentEvent;
+use crate::api::library::LibraryKind;
 use crate::parser::logic::component::Component as ParserComponent;
-use thiserror::Error;
 
 /// An enum that contains all kinds of components with its parameters
 #[derive(Debug, Clone, PartialEq)]
@@ -33,14 +34,20 @@ pub enum ComponentKind {
     SpeechToText,
     BluetoothConnect,
     LocationManager,
+
+    /// A component type that isn't known by swrs (like the ones added by sketchware mods), its
+    /// parameters are kept as is so it can be converted back
+    Unknown {
+        r#type: u8,
+        params: [String; 3], // param1, param2, param3
+    },
 }
 
 impl ComponentKind {
-    /// Constructs a [`ComponentKind`] using [`ParserComponent`]
-    pub fn from_parser_component(
-        component: &ParserComponent,
-    ) -> Result<ComponentKind, UnknownComponentType> {
-        Ok(match component.r#type {
+    /// Constructs a [`ComponentKind`] using [`ParserComponent`], unknown component types are
+    /// turned into [`ComponentKind::Unknown`]
+    pub fn from_parser_component(component: &ParserComponent) -> ComponentKind {
+        match component.r#type {
             1 => ComponentKind::Intent,
             2 => ComponentKind::SharedPreferences {
                 path: component.param1.to_owned(),
@@ -70,11 +77,251 @@ impl ComponentKind {
             19 => ComponentKind::SpeechToText,
             20 => ComponentKind::BluetoothConnect,
             21 => ComponentKind::LocationManager,
-            _ => Err(UnknownComponentType {
-                component_type: component.r#type,
-                component_id: component.id.to_owned(),
-            })?,
-        })
+            r#type => ComponentKind::Unknown {
+                r#type,
+                params: [
+                    component.param1.to_owned(),
+                    component.param2.to_owned(),
+                    component.param3.to_owned(),
+                ],
+            },
+        }
+    }
+
+    /// Gets the type id of this component kind
+    pub fn get_type_id(&self) -> u8 {
+        match self {
+            ComponentKind::Intent => 1,
+            ComponentKind::SharedPreferences { .. } => 2,
+            ComponentKind::Calendar => 3,
+            ComponentKind::Vibrator => 4,
+            ComponentKind::Timer => 5,
+            ComponentKind::FirebaseDatabase { .. } => 6,
+            ComponentKind::Dialog => 7,
+            ComponentKind::MediaPlayer => 8,
+            ComponentKind::SoundPool => 9,
+            ComponentKind::ObjectAnimator => 10,
+            ComponentKind::Gyroscope => 11,
+            ComponentKind::FirebaseAuth => 12,
+            ComponentKind::InterstitialAd => 13,
+            ComponentKind::FirebaseStorage { .. } => 14,
+            ComponentKind::Camera => 15,
+            ComponentKind::FilePicker { .. } => 16,
+            ComponentKind::RequestNetwork => 17,
+            ComponentKind::TextToSpeech => 18,
+            ComponentKind::SpeechToText => 19,
+            ComponentKind::BluetoothConnect => 20,
+            ComponentKind::LocationManager => 21,
+            ComponentKind::Unknown { r#type, .. } => *r#type,
+        }
+    }
+
+    /// Retrieves the name of this component kind
+    pub fn name(&self) -> &'static str {
+        match self {
+            ComponentKind::Intent => "Intent",
+            ComponentKind::SharedPreferences { .. } => "SharedPreferences",
+            ComponentKind::Calendar => "Calendar",
+            ComponentKind::Vibrator => "Vibrator",
+            ComponentKind::Timer => "Timer",
+            ComponentKind::FirebaseDatabase { .. } => "FirebaseDatabase",
+            ComponentKind::Dialog => "Dialog",
+            ComponentKind::MediaPlayer => "MediaPlayer",
+            ComponentKind::SoundPool => "SoundPool",
+            ComponentKind::ObjectAnimator => "ObjectAnimator",
+            ComponentKind::Gyroscope => "Gyroscope",
+            ComponentKind::FirebaseAuth => "FirebaseAuth",
+            ComponentKind::InterstitialAd => "InterstitialAd",
+            ComponentKind::FirebaseStorage { .. } => "FirebaseStorage",
+            ComponentKind::Camera => "Camera",
+            ComponentKind::FilePicker { .. } => "FilePicker",
+            ComponentKind::RequestNetwork => "RequestNetwork",
+            ComponentKind::TextToSpeech => "TextToSpeech",
+            ComponentKind::SpeechToText => "SpeechToText",
+            ComponentKind::BluetoothConnect => "BluetoothConnect",
+            ComponentKind::LocationManager => "LocationManager",
+            ComponentKind::Unknown { .. } => "Unknown",
+        }
+    }
+
+    /// Retrieves the events this component kind fires, see [`ComponentEvent::of`]
+    pub fn events(&self) -> &'static [ComponentEvent] {
+        ComponentEvent::of(self)
+    }
+
+    /// Retrieves the opcodes of the blocks that operate on this component kind
+    pub fn blocks(&self) -> &'static [&'static str] {
+        match self {
+            ComponentKind::Intent => &[
+                "intentSetAction",
+                "intentSetData",
+                "intentSetScreen",
+                "intentPutExtra",
+                "intentSetFlags",
+                "intentGetString",
+                "startActivity",
+            ],
+            ComponentKind::SharedPreferences { .. } => {
+                &["fileGetData", "fileSetData", "fileRemoveData"]
+            }
+            ComponentKind::Calendar => &[
+                "calendarGetNow",
+                "calendarAdd",
+                "calendarSet",
+                "calendarFormat",
+                "calendarDiff",
+                "calendarGetTime",
+                "calendarSetTime",
+            ],
+            ComponentKind::Vibrator => &["vibratorAction"],
+            ComponentKind::Timer => &["timerAfter", "timerEvery", "timerCancel"],
+            ComponentKind::FirebaseDatabase { .. } => &[
+                "firebaseAdd",
+                "firebasePush",
+                "firebaseGetPushKey",
+                "firebaseDelete",
+                "firebaseGetChildren",
+                "firebaseStartListen",
+                "firebaseStopListen",
+            ],
+            ComponentKind::Dialog => &[
+                "dialogSetTitle",
+                "dialogSetMessage",
+                "dialogOkButton",
+                "dialogCancelButton",
+                "dialogNeutralButton",
+                "dialogShow",
+                "dialogDismiss",
+            ],
+            ComponentKind::MediaPlayer => &[
+                "mediaplayerCreate",
+                "mediaplayerStart",
+                "mediaplayerPause",
+                "mediaplayerSeek",
+                "mediaplayerGetCurrent",
+                "mediaplayerGetDuration",
+                "mediaplayerIsPlaying",
+                "mediaplayerSetLooping",
+                "mediaplayerIsLooping",
+                "mediaplayerReset",
+                "mediaplayerRelease",
+            ],
+            ComponentKind::SoundPool => &[
+                "soundpoolCreate",
+                "soundpoolLoad",
+                "soundpoolStreamPlay",
+                "soundpoolStreamStop",
+            ],
+            ComponentKind::ObjectAnimator => &[
+                "objectanimatorSetTarget",
+                "objectanimatorSetProperty",
+                "objectanimatorSetValue",
+                "objectanimatorSetFromTo",
+                "objectanimatorSetDuration",
+                "objectanimatorSetRepeatMode",
+                "objectanimatorSetRepeatCount",
+                "objectanimatorSetInterpolator",
+                "objectanimatorStart",
+                "objectanimatorCancel",
+                "objectanimatorIsRunning",
+            ],
+            ComponentKind::Gyroscope => &["gyroscopeStartListen", "gyroscopeStopListen"],
+            ComponentKind::FirebaseAuth => &[
+                "firebaseauthCreateUser",
+                "firebaseauthSignInUser",
+                "firebaseauthSignInAnonymously",
+                "firebaseauthIsLoggedIn",
+                "firebaseauthGetCurrentUser",
+                "firebaseauthGetUid",
+                "firebaseauthResetPassword",
+                "firebaseauthSignOutUser",
+            ],
+            ComponentKind::InterstitialAd => &[
+                "interstitialadCreate",
+                "interstitialadLoadAd",
+                "interstitialadShow",
+            ],
+            ComponentKind::FirebaseStorage { .. } => &[
+                "firebasestorageUploadFile",
+                "firebasestorageDownloadFile",
+                "firebasestorageDelete",
+            ],
+            ComponentKind::Camera => &["camerastarttakepicture"],
+            ComponentKind::FilePicker { .. } => &["filepickerstartpickfiles"],
+            ComponentKind::RequestNetwork => &[
+                "requestnetworkSetParams",
+                "requestnetworkSetHeaders",
+                "requestnetworkStartRequestNetwork",
+            ],
+            ComponentKind::TextToSpeech => &[
+                "textToSpeechSetPitch",
+                "textToSpeechSetSpeechRate",
+                "textToSpeechSpeak",
+                "textToSpeechIsSpeaking",
+                "textToSpeechStop",
+                "textToSpeechShutdown",
+            ],
+            ComponentKind::SpeechToText => &[
+                "speechToTextStartListening",
+                "speechToTextStopListening",
+                "speechToTextShutdown",
+            ],
+            ComponentKind::BluetoothConnect => &[
+                "bluetoothConnectReadyConnection",
+                "bluetoothConnectStartConnection",
+                "bluetoothConnectStopConnection",
+                "bluetoothConnectSendData",
+                "bluetoothConnectIsBluetoothEnabled",
+                "bluetoothConnectIsBluetoothActivated",
+                "bluetoothConnectActivateBluetooth",
+                "bluetoothConnectGetPairedDevices",
+            ],
+            ComponentKind::LocationManager => &[
+                "locationManagerRequestLocationUpdates",
+                "locationManagerRemoveUpdates",
+            ],
+            ComponentKind::Unknown { .. } => &[],
+        }
+    }
+
+    /// Retrieves the android permissions this component kind needs
+    pub fn permissions(&self) -> &'static [&'static str] {
+        match self {
+            ComponentKind::Camera => &["android.permission.CAMERA"],
+            ComponentKind::LocationManager => &[
+                "android.permission.ACCESS_FINE_LOCATION",
+                "android.permission.ACCESS_COARSE_LOCATION",
+            ],
+            ComponentKind::BluetoothConnect => &[
+                "android.permission.BLUETOOTH",
+                "android.permission.BLUETOOTH_ADMIN",
+            ],
+            ComponentKind::Vibrator => &["android.permission.VIBRATE"],
+            ComponentKind::RequestNetwork => &[
+                "android.permission.INTERNET",
+                "android.permission.ACCESS_NETWORK_STATE",
+            ],
+            ComponentKind::SpeechToText => &["android.permission.RECORD_AUDIO"],
+            ComponentKind::FirebaseDatabase { .. }
+            | ComponentKind::FirebaseAuth
+            | ComponentKind::FirebaseStorage { .. } => &["android.permission.INTERNET"],
+            ComponentKind::InterstitialAd => &[
+                "android.permission.INTERNET",
+                "android.permission.ACCESS_NETWORK_STATE",
+            ],
+            _ => &[],
+        }
+    }
+
+    /// Retrieves the library that has to be enabled for this component kind to work
+    pub fn required_library(&self) -> Option<LibraryKind> {
+        match self {
+            ComponentKind::FirebaseDatabase { .. }
+            | ComponentKind::FirebaseAuth
+            | ComponentKind::FirebaseStorage { .. } => Some(LibraryKind::Firebase),
+            ComponentKind::InterstitialAd => Some(LibraryKind::AdMob),
+            _ => None,
+        }
     }
 
     /// Transforms [`ComponentKind`] back to [`ParserComponent`]
@@ -103,13 +350,10 @@ impl ComponentKind {
             ComponentKind::SpeechToText => ParserComponent::new_empty(id, 19),
             ComponentKind::BluetoothConnect => ParserComponent::new_empty(id, 20),
             ComponentKind::LocationManager => ParserComponent::new_empty(id, 21),
+            ComponentKind::Unknown {
+                r#type,
+                params: [param1, param2, param3],
+            } => ParserComponent::new(id, param1, param2, param3, r#type),
         }
     }
 }
-
-#[derive(Error, Debug)]
-#[error("unknown component type `{component_type}` of component id {component_id}")]
-pub struct UnknownComponentType {
-    pub component_type: u8,
-    pub component_id: String,
-}
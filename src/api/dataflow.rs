@@ -0,0 +1,340 @@
+//! Data flow of the variables and lists of a screen. [`DataFlow::analyze`] classifies every usage
+//! of a variable or a list in blocks as a read or a write, finds variables and lists that are
+//! only ever written or only ever read, and reads of variables that can happen before they're
+//! assigned.
+//!
+//! Blocks that modify a value (like `mapPut` or `addListInt`) count as writes, `increaseInt` and
+//! `decreaseInt` count as a read followed by a write.
+
+use crate::api::block::{ArgValue, Argument, Block, BlockPath, BlockPathSegment, Blocks};
+use crate::api::interpreter::block_text;
+use crate::api::screen::Screen;
+use crate::api::snippet::first_text;
+use crate::api::typecheck::{list_menu, variable_menu};
+use std::collections::HashSet;
+
+/// The block container id of the onCreate event
+const ON_CREATE: &str = "onCreate_initializeLogic";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum VariableKind {
+    Variable,
+    List,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Access {
+    Read,
+    Write,
+}
+
+/// A usage of a variable or a list in a block
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Usage {
+    pub name: String,
+    pub kind: VariableKind,
+    pub access: Access,
+
+    /// The block container id of the event or the moreblock the block is in
+    pub container: String,
+    pub path: BlockPath,
+}
+
+/// The data flow of a screen
+#[derive(Debug, Clone, PartialEq)]
+pub struct DataFlow {
+    /// The layout name of the screen
+    pub screen: String,
+
+    /// Every usage in events and moreblocks, in the order they are executed in each event or
+    /// moreblock
+    pub usages: Vec<Usage>,
+
+    /// Variables and lists that are written but never read
+    pub write_only: Vec<(VariableKind, String)>,
+
+    /// Variables and lists that are read but never written
+    pub read_only: Vec<(VariableKind, String)>,
+
+    /// Reads of variables that can happen before they're assigned. Events other than onCreate
+    /// are assumed to run after it, so variables assigned anywhere in onCreate (and the
+    /// moreblocks it calls) are assigned in them. Lists are always initialized empty, so they
+    /// aren't checked.
+    pub unassigned_reads: Vec<Usage>,
+}
+
+/// A step of an event or a moreblock
+enum Step {
+    Use(Usage),
+
+    /// A call to a moreblock
+    Call(String),
+}
+
+impl DataFlow {
+    /// Analyzes the data flow of a screen
+    pub fn analyze(screen: &Screen) -> DataFlow {
+        let mut events: Vec<(String, Vec<Step>)> = Vec::new();
+        let mut more_blocks: Vec<(String, Vec<Step>)> = Vec::new();
+
+        for (container, blocks) in screen.block_containers() {
+            let steps = steps(screen, &container, blocks);
+
+            match container.strip_suffix("_moreBlock") {
+                Some(name) => more_blocks.push((name.to_owned(), steps)),
+                None => events.push((container, steps)),
+            }
+        }
+
+        let usages: Vec<Usage> = events
+            .iter()
+            .chain(more_blocks.iter())
+            .flat_map(|(_, steps)| steps)
+            .filter_map(|step| match step {
+                Step::Use(usage) => Some(usage.to_owned()),
+                Step::Call(_) => None,
+            })
+            .collect();
+
+        let accessed = |kind: VariableKind, name: &str, access: Access| {
+            usages
+                .iter()
+                .any(|usage| usage.kind == kind && usage.name == name && usage.access == access)
+        };
+
+        let declarations: Vec<(VariableKind, String)> = screen
+            .variables
+            .keys()
+            .map(|name| (VariableKind::Variable, name.to_owned()))
+            .chain(
+                screen
+                    .list_variables
+                    .keys()
+                    .map(|name| (VariableKind::List, name.to_owned())),
+            )
+            .collect();
+
+        let write_only = declarations
+            .iter()
+            .filter(|(kind, name)| {
+                accessed(*kind, name, Access::Write) && !accessed(*kind, name, Access::Read)
+            })
+            .cloned()
+            .collect();
+
+        let read_only = declarations
+            .iter()
+            .filter(|(kind, name)| {
+                accessed(*kind, name, Access::Read) && !accessed(*kind, name, Access::Write)
+            })
+            .cloned()
+            .collect();
+
+        // onCreate runs first, everything it assigns is assigned for the other events
+        let mut runner = Runner {
+            more_blocks: &more_blocks,
+            calling: vec![],
+            unassigned_reads: vec![],
+        };
+
+        let mut assigned_by_on_create = HashSet::new();
+        if let Some((_, steps)) = events.iter().find(|(container, _)| container == ON_CREATE) {
+            runner.run(steps, &mut assigned_by_on_create);
+        }
+
+        for (_, steps) in events
+            .iter()
+            .filter(|(container, _)| container != ON_CREATE)
+        {
+            runner.run(steps, &mut assigned_by_on_create.clone());
+        }
+
+        DataFlow {
+            screen: screen.layout_name.to_owned(),
+            usages,
+            write_only,
+            read_only,
+            unassigned_reads: runner.unassigned_reads,
+        }
+    }
+
+    /// Retrieves the usages of a variable or a list
+    pub fn usages_of<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Usage> {
+        self.usages.iter().filter(move |usage| usage.name == name)
+    }
+}
+
+/// Runs through events (and the moreblocks they call) to find reads of unassigned variables
+struct Runner<'a> {
+    more_blocks: &'a [(String, Vec<Step>)],
+
+    /// Moreblocks that are being run, to not run into recursion
+    calling: Vec<&'a str>,
+    unassigned_reads: Vec<Usage>,
+}
+
+impl<'a> Runner<'a> {
+    fn run(&mut self, steps: &'a [Step], assigned: &mut HashSet<&'a str>) {
+        for step in steps {
+            match step {
+                Step::Use(usage) if usage.kind == VariableKind::Variable => match usage.access {
+                    Access::Write => {
+                        assigned.insert(&usage.name);
+                    }
+                    Access::Read => {
+                        if !assigned.contains(usage.name.as_str())
+                            && !self.unassigned_reads.contains(usage)
+                        {
+                            self.unassigned_reads.push(usage.to_owned());
+                        }
+                    }
+                },
+                Step::Use(_) => {}
+                Step::Call(name) => {
+                    if self.calling.contains(&name.as_str()) {
+                        continue;
+                    }
+
+                    if let Some((name, steps)) = self
+                        .more_blocks
+                        .iter()
+                        .find(|(more_block, _)| more_block == name)
+                    {
+                        self.calling.push(name);
+                        self.run(steps, assigned);
+                        self.calling.pop();
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Retrieves the steps of blocks in the order they are executed: the blocks put on the arguments
+/// of a block come before the block itself, reads of a block come before its writes
+fn steps(screen: &Screen, container: &str, blocks: &Blocks) -> Vec<Step> {
+    fn visit_stack(
+        screen: &Screen,
+        container: &str,
+        blocks: &Blocks,
+        path: &mut BlockPath,
+        result: &mut Vec<Step>,
+    ) {
+        for (index, block) in blocks.0.iter().enumerate() {
+            path.0.push(BlockPathSegment::Index(index));
+            visit_block(screen, container, block, path, result);
+            path.0.pop();
+        }
+    }
+
+    fn visit_block(
+        screen: &Screen,
+        container: &str,
+        block: &Block,
+        path: &mut BlockPath,
+        result: &mut Vec<Step>,
+    ) {
+        let args = block.content.get_args();
+
+        for (index, arg) in args.iter().enumerate() {
+            if let Some(arg_block) = arg.block() {
+                path.0.push(BlockPathSegment::Argument(index));
+                visit_block(screen, container, arg_block, path, result);
+                path.0.pop();
+            }
+        }
+
+        let mut reads = Vec::new();
+        let mut writes = Vec::new();
+
+        if block.op_code == "getVar" {
+            reads.push(block_text(block));
+        }
+
+        for (index, arg) in args.iter().enumerate() {
+            let name = match arg {
+                Argument::Menu {
+                    name,
+                    value: ArgValue::Value(value),
+                } if variable_menu(name).is_some() || list_menu(name).is_some() => value,
+                _ => continue,
+            };
+
+            match written_argument(&block.op_code) {
+                Some((written, read)) if written == index => {
+                    if read {
+                        reads.push(name.to_owned());
+                    }
+                    writes.push(name.to_owned());
+                }
+                _ => reads.push(name.to_owned()),
+            }
+        }
+
+        let usages = reads
+            .into_iter()
+            .map(|name| (name, Access::Read))
+            .chain(writes.into_iter().map(|name| (name, Access::Write)));
+
+        for (name, access) in usages {
+            let kind = if screen.variables.contains_key(&name) {
+                VariableKind::Variable
+            } else if screen.list_variables.contains_key(&name) {
+                VariableKind::List
+            } else {
+                continue;
+            };
+
+            result.push(Step::Use(Usage {
+                name,
+                kind,
+                access,
+                container: container.to_string(),
+                path: path.to_owned(),
+            }));
+        }
+
+        if block.op_code == "definedFunc" {
+            if let Some(name) = first_text(block) {
+                result.push(Step::Call(name.to_string()));
+            }
+        }
+
+        if let Some(blocks) = &block.sub_stack1 {
+            path.0.push(BlockPathSegment::SubStack1);
+            visit_stack(screen, container, blocks, path, result);
+            path.0.pop();
+        }
+
+        if let Some(blocks) = &block.sub_stack2 {
+            path.0.push(BlockPathSegment::SubStack2);
+            visit_stack(screen, container, blocks, path, result);
+            path.0.pop();
+        }
+    }
+
+    let mut result = Vec::new();
+    visit_stack(
+        screen,
+        container,
+        blocks,
+        &mut BlockPath::default(),
+        &mut result,
+    );
+    result
+}
+
+/// Retrieves the index of the argument that a block writes into, and whether it reads it before
+/// writing
+fn written_argument(op_code: &str) -> Option<(usize, bool)> {
+    Some(match op_code {
+        "setVarBoolean" | "setVarInt" | "setVarString" => (0, false),
+        "increaseInt" | "decreaseInt" => (0, true),
+        "mapCreateNew" | "mapClear" | "mapPut" | "mapRemoveKey" | "clearList" => (0, false),
+        "addListInt" | "addListStr" | "addMapToList" | "deleteList" | "mapGetAllKeys" => (1, false),
+        "addListMap" | "insertListInt" | "insertListStr" | "insertMapToList" | "getMapInList" => {
+            (2, false)
+        }
+        _ => return None,
+    })
+}
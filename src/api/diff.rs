@@ -0,0 +1,920 @@
+//! Structural diffing of projects. [`diff`] compares two projects and produces a typed list of
+//! changes, which can be rendered into a human-readable text through its [`Display`]
+//! implementation.
+//!
+//! Screens are matched by their layout names, views by their ids, events by their block container
+//! ids and blocks are diffed as trees over [`Blocks`]. Views of drawers are identified by their ids
+//! prefixed with [`DRAWER_PREFIX`], the same way the logic of their screens refers to them.
+
+use crate::api::block::{Argument, Block, BlockPath, BlockPathSegment, Blocks};
+use crate::api::component::ComponentKind;
+use crate::api::screen::{Screen, DRAWER_PREFIX};
+use crate::api::view::View;
+use crate::api::{Colors, Metadata, SketchwareProject};
+use crate::color::Color;
+use crate::parser::logic::variable::VariableType;
+use crate::util::lcs;
+use crate::LinkedHashMap;
+use std::fmt::{Display, Formatter};
+
+/// Compares two projects, `a` being the old one and `b` being the new one
+pub fn diff(a: &SketchwareProject, b: &SketchwareProject) -> ProjectDiff {
+    let mut changes = Vec::new();
+
+    for field in Metadata::FIELDS {
+        let (old, new) = (a.metadata.get_field(field), b.metadata.get_field(field));
+
+        if old != new {
+            changes.push(Change::MetadataChanged {
+                field,
+                old: old.unwrap_or_default(),
+                new: new.unwrap_or_default(),
+            });
+        }
+    }
+
+    for field in Colors::FIELDS {
+        if let (Some(old), Some(new)) = (a.colors.get_field(field), b.colors.get_field(field)) {
+            if old != new {
+                changes.push(Change::ColorChanged { field, old, new });
+            }
+        }
+    }
+
+    for screen in &a.screens {
+        if !b
+            .screens
+            .iter()
+            .any(|s| s.layout_name == screen.layout_name)
+        {
+            changes.push(Change::ScreenRemoved {
+                screen: screen.layout_name.to_owned(),
+            });
+        }
+    }
+
+    for screen in &b.screens {
+        match a
+            .screens
+            .iter()
+            .find(|s| s.layout_name == screen.layout_name)
+        {
+            None => changes.push(Change::ScreenAdded {
+                screen: screen.layout_name.to_owned(),
+            }),
+            Some(old) => {
+                let screen_changes = diff_screen(old, screen);
+
+                if !screen_changes.is_empty() {
+                    changes.push(Change::ScreenChanged {
+                        screen: screen.layout_name.to_owned(),
+                        changes: screen_changes,
+                    });
+                }
+            }
+        }
+    }
+
+    ProjectDiff { changes }
+}
+
+/// Compares two versions of a screen
+pub fn diff_screen(a: &Screen, b: &Screen) -> Vec<ScreenChange> {
+    let mut changes = Vec::new();
+
+    macro_rules! options {
+        ($($field:ident),*) => {$(
+            if a.$field != b.$field {
+                changes.push(ScreenChange::OptionChanged {
+                    option: stringify!($field),
+                    old: format!("{:?}", a.$field),
+                    new: format!("{:?}", b.$field),
+                });
+            }
+        )*};
+    }
+
+    options!(
+        java_name,
+        fullscreen_enabled,
+        toolbar_enabled,
+        drawer_enabled,
+        fab_enabled,
+        orientation,
+        theme,
+        keyboard_setting
+    );
+
+    diff_views(a, b, &mut changes);
+
+    // variables
+    for (name, variable) in &a.variables {
+        if !b.variables.contains_key(name) {
+            changes.push(ScreenChange::VariableRemoved {
+                name: name.to_owned(),
+                r#type: variable.r#type,
+            });
+        }
+    }
+
+    for (name, variable) in &b.variables {
+        match a.variables.get(name) {
+            None => changes.push(ScreenChange::VariableAdded {
+                name: name.to_owned(),
+                r#type: variable.r#type,
+            }),
+            Some(old) if old.r#type != variable.r#type => {
+                changes.push(ScreenChange::VariableTypeChanged {
+                    name: name.to_owned(),
+                    old: old.r#type,
+                    new: variable.r#type,
+                })
+            }
+            _ => {}
+        }
+    }
+
+    // list variables
+    for (name, list) in &a.list_variables {
+        if !b.list_variables.contains_key(name) {
+            changes.push(ScreenChange::ListVariableRemoved {
+                name: name.to_owned(),
+                r#type: list.r#type,
+            });
+        }
+    }
+
+    for (name, list) in &b.list_variables {
+        match a.list_variables.get(name) {
+            None => changes.push(ScreenChange::ListVariableAdded {
+                name: name.to_owned(),
+                r#type: list.r#type,
+            }),
+            Some(old) if old.r#type != list.r#type => {
+                changes.push(ScreenChange::ListVariableTypeChanged {
+                    name: name.to_owned(),
+                    old: old.r#type,
+                    new: list.r#type,
+                })
+            }
+            _ => {}
+        }
+    }
+
+    // components
+    for (id, component) in &a.components {
+        if !b.components.contains_key(id) {
+            changes.push(ScreenChange::ComponentRemoved {
+                id: id.to_owned(),
+                component: component.clone(),
+            });
+        }
+    }
+
+    for (id, component) in &b.components {
+        match a.components.get(id) {
+            None => changes.push(ScreenChange::ComponentAdded {
+                id: id.to_owned(),
+                component: component.clone(),
+            }),
+            Some(old) if old != component => changes.push(ScreenChange::ComponentChanged {
+                id: id.to_owned(),
+                old: old.clone(),
+                new: component.clone(),
+            }),
+            _ => {}
+        }
+    }
+
+    // moreblocks
+    for name in a.more_blocks.keys() {
+        if !b.more_blocks.contains_key(name) {
+            changes.push(ScreenChange::MoreBlockRemoved {
+                name: name.to_owned(),
+            });
+        }
+    }
+
+    for (name, more_block) in &b.more_blocks {
+        let old = match a.more_blocks.get(name) {
+            None => {
+                changes.push(ScreenChange::MoreBlockAdded {
+                    name: name.to_owned(),
+                });
+                continue;
+            }
+            Some(old) => old,
+        };
+
+        let (old_spec, new_spec) = (old.spec.to_string(), more_block.spec.to_string());
+        if old_spec != new_spec {
+            changes.push(ScreenChange::MoreBlockSpecChanged {
+                name: name.to_owned(),
+                old: old_spec,
+                new: new_spec,
+            });
+        }
+
+        let edits = diff_blocks(&old.code, &more_block.code);
+        if !edits.is_empty() {
+            changes.push(ScreenChange::MoreBlockCodeChanged {
+                name: name.to_owned(),
+                edits,
+            });
+        }
+    }
+
+    // events
+    let old_events = a
+        .events
+        .iter()
+        .map(|event| (event.get_block_container_id(), event))
+        .collect::<LinkedHashMap<_, _>>();
+
+    let new_events = b
+        .events
+        .iter()
+        .map(|event| (event.get_block_container_id(), event))
+        .collect::<LinkedHashMap<_, _>>();
+
+    for container in old_events.keys() {
+        if !new_events.contains_key(container) {
+            changes.push(ScreenChange::EventRemoved {
+                container: container.to_owned(),
+            });
+        }
+    }
+
+    for (container, event) in &new_events {
+        match old_events.get(container) {
+            None => changes.push(ScreenChange::EventAdded {
+                container: container.to_owned(),
+            }),
+            Some(old) => {
+                let edits = diff_blocks(&old.code, &event.code);
+
+                if !edits.is_empty() {
+                    changes.push(ScreenChange::EventCodeChanged {
+                        container: container.to_owned(),
+                        edits,
+                    });
+                }
+            }
+        }
+    }
+
+    changes
+}
+
+/// Where a view is located in a layout
+pub(crate) struct ViewLocation<'a> {
+    /// The id of the parent view, None if it's on the root
+    pub(crate) parent: Option<String>,
+    pub(crate) index: usize,
+    pub(crate) view: &'a View,
+}
+
+/// Collects every view in a tree of views (in pre-order) along with their locations
+pub(crate) fn collect_views<'a>(
+    views: &'a [View],
+    parent: Option<&str>,
+    result: &mut LinkedHashMap<String, ViewLocation<'a>>,
+) {
+    for (index, view) in views.iter().enumerate() {
+        result.insert(
+            view.id.to_owned(),
+            ViewLocation {
+                parent: parent.map(ToString::to_string),
+                index,
+                view,
+            },
+        );
+
+        collect_views(&view.children, Some(&view.id), result);
+    }
+}
+
+fn view_locations(screen: &Screen) -> LinkedHashMap<String, ViewLocation<'_>> {
+    let mut views = LinkedHashMap::new();
+    collect_views(&screen.layout, None, &mut views);
+
+    if let Some(fab) = &screen.fab {
+        views.insert(
+            fab.id.to_owned(),
+            ViewLocation {
+                parent: None,
+                index: 0,
+                view: fab,
+            },
+        );
+    }
+
+    views
+}
+
+/// Collects the views of the drawer of a screen along with their locations. Their ids and the ids
+/// of their parents are prefixed with [`DRAWER_PREFIX`].
+pub(crate) fn drawer_locations(screen: &Screen) -> LinkedHashMap<String, ViewLocation<'_>> {
+    let mut views = LinkedHashMap::new();
+    if let Some(drawer) = &screen.drawer {
+        collect_views(&drawer.layout, None, &mut views);
+    }
+
+    views
+        .into_iter()
+        .map(|(id, location)| {
+            let location = ViewLocation {
+                parent: location
+                    .parent
+                    .map(|parent| format!("{}{}", DRAWER_PREFIX, parent)),
+                ..location
+            };
+
+            (format!("{}{}", DRAWER_PREFIX, id), location)
+        })
+        .collect()
+}
+
+/// Retrieves the ids of views in `views` that are children of `parent` in both `views` and
+/// `others`, in order
+fn kept_siblings<'a>(
+    views: &'a LinkedHashMap<String, ViewLocation>,
+    others: &LinkedHashMap<String, ViewLocation>,
+    parent: &Option<String>,
+) -> Vec<&'a str> {
+    views
+        .iter()
+        .filter(|(id, location)| {
+            location.parent == *parent
+                && others
+                    .get(*id)
+                    .map(|other| other.parent == *parent)
+                    .unwrap_or(false)
+        })
+        .map(|(id, _)| id.as_str())
+        .collect()
+}
+
+fn diff_views(a: &Screen, b: &Screen, changes: &mut Vec<ScreenChange>) {
+    diff_locations(&view_locations(a), &view_locations(b), changes);
+
+    // the root views of the drawer are diffed separately from the root views of the layout
+    diff_locations(&drawer_locations(a), &drawer_locations(b), changes);
+}
+
+fn diff_locations(
+    old_views: &LinkedHashMap<String, ViewLocation>,
+    new_views: &LinkedHashMap<String, ViewLocation>,
+    changes: &mut Vec<ScreenChange>,
+) {
+    for (id, location) in old_views {
+        if !new_views.contains_key(id) {
+            changes.push(ScreenChange::ViewRemoved {
+                id: id.to_owned(),
+                parent: location.parent.to_owned(),
+            });
+        }
+    }
+
+    for (id, location) in new_views {
+        if !old_views.contains_key(id) {
+            let mut view = location.view.clone();
+            view.children.clear();
+
+            changes.push(ScreenChange::ViewAdded {
+                id: id.to_owned(),
+                parent: location.parent.to_owned(),
+                index: location.index,
+                view: Box::new(view),
+            });
+        }
+    }
+
+    // views that stay in the same parent are only considered moved when their order relative to
+    // their siblings changed, otherwise adding a view would move every view after it
+    let mut parents = Vec::<Option<String>>::new();
+    for location in new_views.values() {
+        if !parents.contains(&location.parent) {
+            parents.push(location.parent.to_owned());
+        }
+    }
+
+    let mut reordered = Vec::new();
+    for parent in &parents {
+        let old_order = kept_siblings(old_views, new_views, parent);
+        let new_order = kept_siblings(new_views, old_views, parent);
+        let kept = lcs(&old_order, &new_order, |a, b| a == b);
+
+        reordered.extend(
+            new_order
+                .iter()
+                .enumerate()
+                .filter(|(index, _)| !kept.iter().any(|(_, kept)| kept == index))
+                .map(|(_, id)| id.to_string()),
+        );
+    }
+
+    for (id, location) in new_views {
+        let old = match old_views.get(id) {
+            Some(old) => old,
+            None => continue,
+        };
+
+        if old.parent != location.parent || reordered.contains(id) {
+            changes.push(ScreenChange::ViewMoved {
+                id: id.to_owned(),
+                old_parent: old.parent.to_owned(),
+                old_index: old.index,
+                new_parent: location.parent.to_owned(),
+                new_index: location.index,
+            });
+        }
+    }
+
+    for (id, location) in new_views {
+        let old = match old_views.get(id) {
+            Some(old) => old,
+            None => continue,
+        };
+
+        let old_attributes = old.view.attributes();
+        for (attribute, value) in location.view.attributes() {
+            let old_value = old_attributes
+                .get(&attribute)
+                .cloned()
+                .unwrap_or(serde_json::Value::Null);
+
+            if old_value != value {
+                changes.push(ScreenChange::ViewAttributeChanged {
+                    id: id.to_owned(),
+                    attribute,
+                    old: old_value,
+                    new: value,
+                });
+            }
+        }
+    }
+}
+
+/// Diffs two trees of blocks. Blocks in a stack are matched by their opcode and spec, matched
+/// blocks are then compared argument by argument and substack by substack.
+///
+/// Paths of removed blocks point to where they were in `a`, while the paths of every other edit
+/// point to where they are in `b`.
+pub fn diff_blocks(a: &Blocks, b: &Blocks) -> Vec<BlockEdit> {
+    let mut edits = Vec::new();
+    diff_stack(
+        a,
+        b,
+        &BlockPath::default(),
+        &BlockPath::default(),
+        &mut edits,
+    );
+    edits
+}
+
+fn same_block(a: &Block, b: &Block) -> bool {
+    a.op_code == b.op_code && a.content.to_string() == b.content.to_string()
+}
+
+fn diff_stack(
+    a: &Blocks,
+    b: &Blocks,
+    a_path: &BlockPath,
+    b_path: &BlockPath,
+    edits: &mut Vec<BlockEdit>,
+) {
+    let matched = lcs(&a.0, &b.0, same_block);
+    let (mut a_index, mut b_index) = (0, 0);
+
+    for (a_match, b_match) in matched
+        .into_iter()
+        .chain(std::iter::once((a.0.len(), b.0.len())))
+    {
+        for index in a_index..a_match {
+            edits.push(BlockEdit::Removed {
+                path: a_path.join(BlockPathSegment::Index(index)),
+                block: a.0[index].clone(),
+            });
+        }
+
+        for index in b_index..b_match {
+            edits.push(BlockEdit::Inserted {
+                path: b_path.join(BlockPathSegment::Index(index)),
+                block: b.0[index].clone(),
+            });
+        }
+
+        if a_match < a.0.len() && b_match < b.0.len() {
+            diff_block(
+                &a.0[a_match],
+                &b.0[b_match],
+                &a_path.join(BlockPathSegment::Index(a_match)),
+                &b_path.join(BlockPathSegment::Index(b_match)),
+                edits,
+            );
+        }
+
+        a_index = a_match + 1;
+        b_index = b_match + 1;
+    }
+}
+
+fn diff_block(
+    a: &Block,
+    b: &Block,
+    a_path: &BlockPath,
+    b_path: &BlockPath,
+    edits: &mut Vec<BlockEdit>,
+) {
+    let (a_args, b_args) = (a.content.get_args(), b.content.get_args());
+
+    for (index, (a_arg, b_arg)) in a_args.into_iter().zip(b_args).enumerate() {
+        match (a_arg.block(), b_arg.block()) {
+            (Some(a_block), Some(b_block)) if same_block(a_block, b_block) => diff_block(
+                a_block,
+                b_block,
+                &a_path.join(BlockPathSegment::Argument(index)),
+                &b_path.join(BlockPathSegment::Argument(index)),
+                edits,
+            ),
+            _ if a_arg != b_arg => edits.push(BlockEdit::ArgumentChanged {
+                path: b_path.to_owned(),
+                op_code: b.op_code.to_owned(),
+                argument: index,
+                old: Box::new(a_arg.clone()),
+                new: Box::new(b_arg.clone()),
+            }),
+            _ => {}
+        }
+    }
+
+    let empty = Blocks::new();
+    for (segment, a_stack, b_stack) in [
+        (BlockPathSegment::SubStack1, &a.sub_stack1, &b.sub_stack1),
+        (BlockPathSegment::SubStack2, &a.sub_stack2, &b.sub_stack2),
+    ] {
+        diff_stack(
+            a_stack.as_ref().unwrap_or(&empty),
+            b_stack.as_ref().unwrap_or(&empty),
+            &a_path.join(segment),
+            &b_path.join(segment),
+            edits,
+        );
+    }
+}
+
+/// The result of [`diff`]
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ProjectDiff {
+    pub changes: Vec<Change>,
+}
+
+impl ProjectDiff {
+    /// Checks whether both projects are structurally the same
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+/// A change made on a project
+#[derive(Debug, Clone, PartialEq)]
+pub enum Change {
+    /// A field of [`crate::api::Metadata`] was changed
+    MetadataChanged {
+        field: &'static str,
+        old: String,
+        new: String,
+    },
+
+    /// A field of [`crate::api::Colors`] was changed
+    ColorChanged {
+        field: &'static str,
+        old: Color,
+        new: Color,
+    },
+
+    ScreenAdded {
+        screen: String,
+    },
+    ScreenRemoved {
+        screen: String,
+    },
+    ScreenChanged {
+        screen: String,
+        changes: Vec<ScreenChange>,
+    },
+}
+
+/// A change made on a screen
+#[derive(Debug, Clone, PartialEq)]
+pub enum ScreenChange {
+    /// An option of the screen (like `toolbar_enabled` or `orientation`) was changed
+    OptionChanged {
+        option: &'static str,
+        old: String,
+        new: String,
+    },
+
+    /// A view was added, `view` doesn't contain its children as they are reported as added
+    /// views on their own
+    ViewAdded {
+        id: String,
+        /// The parent view id, None if the view is on the root of its layout
+        parent: Option<String>,
+        index: usize,
+        view: Box<View>,
+    },
+    ViewRemoved {
+        id: String,
+        parent: Option<String>,
+    },
+    ViewMoved {
+        id: String,
+        old_parent: Option<String>,
+        old_index: usize,
+        new_parent: Option<String>,
+        new_index: usize,
+    },
+
+    /// An attribute of a view was changed, attributes are named after their raw names (see
+    /// [`View::attributes`])
+    ViewAttributeChanged {
+        id: String,
+        attribute: String,
+        old: serde_json::Value,
+        new: serde_json::Value,
+    },
+
+    VariableAdded {
+        name: String,
+        r#type: VariableType,
+    },
+    VariableRemoved {
+        name: String,
+        r#type: VariableType,
+    },
+    VariableTypeChanged {
+        name: String,
+        old: VariableType,
+        new: VariableType,
+    },
+
+    ListVariableAdded {
+        name: String,
+        r#type: VariableType,
+    },
+    ListVariableRemoved {
+        name: String,
+        r#type: VariableType,
+    },
+    ListVariableTypeChanged {
+        name: String,
+        old: VariableType,
+        new: VariableType,
+    },
+
+    ComponentAdded {
+        id: String,
+        component: ComponentKind,
+    },
+    ComponentRemoved {
+        id: String,
+        component: ComponentKind,
+    },
+    ComponentChanged {
+        id: String,
+        old: ComponentKind,
+        new: ComponentKind,
+    },
+
+    MoreBlockAdded {
+        name: String,
+    },
+    MoreBlockRemoved {
+        name: String,
+    },
+    MoreBlockSpecChanged {
+        name: String,
+        old: String,
+        new: String,
+    },
+    MoreBlockCodeChanged {
+        name: String,
+        edits: Vec<BlockEdit>,
+    },
+
+    /// An event was added, events are identified by their block container id (see
+    /// [`crate::api::screen::Event::get_block_container_id`])
+    EventAdded {
+        container: String,
+    },
+    EventRemoved {
+        container: String,
+    },
+    EventCodeChanged {
+        container: String,
+        edits: Vec<BlockEdit>,
+    },
+}
+
+/// An edit made on a tree of blocks, see [`diff_blocks`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum BlockEdit {
+    Inserted {
+        path: BlockPath,
+        block: Block,
+    },
+    Removed {
+        path: BlockPath,
+        block: Block,
+    },
+    ArgumentChanged {
+        path: BlockPath,
+        op_code: String,
+        argument: usize,
+        old: Box<Argument>,
+        new: Box<Argument>,
+    },
+}
+
+fn location(parent: &Option<String>, index: usize) -> String {
+    format!("{}[{}]", parent.as_deref().unwrap_or("root"), index)
+}
+
+fn format_argument(arg: &Argument) -> String {
+    use crate::api::block::ArgValue;
+
+    fn value<T: std::fmt::Debug + Clone + PartialEq>(
+        value: &ArgValue<T>,
+        format: impl Fn(&T) -> String,
+    ) -> String {
+        match value {
+            ArgValue::Value(value) => format(value),
+            ArgValue::Block(block) => format!("({})", block.op_code),
+            ArgValue::BlockPlaceholder { block_id } => format!("(@{})", block_id),
+            ArgValue::Empty => "(empty)".to_string(),
+        }
+    }
+
+    match arg {
+        Argument::String { value: v, .. } | Argument::Menu { value: v, .. } => {
+            value(v, |v| format!("{:?}", v))
+        }
+        Argument::Number { value: v, .. } => value(v, |v| v.to_string()),
+        Argument::Boolean { value: v, .. } => value(v, |v| v.to_string()),
+    }
+}
+
+impl Display for ProjectDiff {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for change in &self.changes {
+            writeln!(f, "{}", change)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for Change {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Change::MetadataChanged { field, old, new } => {
+                write!(f, "~ metadata {}: {:?} -> {:?}", field, old, new)
+            }
+            Change::ColorChanged { field, old, new } => {
+                write!(f, "~ color {}: {} -> {}", field, old, new)
+            }
+            Change::ScreenAdded { screen } => write!(f, "+ screen {}", screen),
+            Change::ScreenRemoved { screen } => write!(f, "- screen {}", screen),
+            Change::ScreenChanged { screen, changes } => {
+                write!(f, "~ screen {}", screen)?;
+
+                for change in changes {
+                    // indent every line of the change
+                    for line in change.to_string().lines() {
+                        write!(f, "\n    {}", line)?;
+                    }
+                }
+
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Display for ScreenChange {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let write_edits = |f: &mut Formatter<'_>, edits: &[BlockEdit]| {
+            for edit in edits {
+                write!(f, "\n    {}", edit)?;
+            }
+
+            Ok(())
+        };
+
+        match self {
+            ScreenChange::OptionChanged { option, old, new } => {
+                write!(f, "~ option {}: {} -> {}", option, old, new)
+            }
+            ScreenChange::ViewAdded {
+                id, parent, index, ..
+            } => write!(f, "+ view {} at {}", id, location(parent, *index)),
+            ScreenChange::ViewRemoved { id, parent } => write!(
+                f,
+                "- view {} from {}",
+                id,
+                parent.as_deref().unwrap_or("root")
+            ),
+            ScreenChange::ViewMoved {
+                id,
+                old_parent,
+                old_index,
+                new_parent,
+                new_index,
+            } => write!(
+                f,
+                "> view {} moved from {} to {}",
+                id,
+                location(old_parent, *old_index),
+                location(new_parent, *new_index)
+            ),
+            ScreenChange::ViewAttributeChanged {
+                id,
+                attribute,
+                old,
+                new,
+            } => write!(f, "~ view {} {}: {} -> {}", id, attribute, old, new),
+            ScreenChange::VariableAdded { name, r#type } => {
+                write!(f, "+ variable {} ({:?})", name, r#type)
+            }
+            ScreenChange::VariableRemoved { name, r#type } => {
+                write!(f, "- variable {} ({:?})", name, r#type)
+            }
+            ScreenChange::VariableTypeChanged { name, old, new } => {
+                write!(f, "~ variable {}: {:?} -> {:?}", name, old, new)
+            }
+            ScreenChange::ListVariableAdded { name, r#type } => {
+                write!(f, "+ list {} ({:?})", name, r#type)
+            }
+            ScreenChange::ListVariableRemoved { name, r#type } => {
+                write!(f, "- list {} ({:?})", name, r#type)
+            }
+            ScreenChange::ListVariableTypeChanged { name, old, new } => {
+                write!(f, "~ list {}: {:?} -> {:?}", name, old, new)
+            }
+            ScreenChange::ComponentAdded { id, component } => {
+                write!(f, "+ component {} ({})", id, component.name())
+            }
+            ScreenChange::ComponentRemoved { id, component } => {
+                write!(f, "- component {} ({})", id, component.name())
+            }
+            ScreenChange::ComponentChanged { id, old, new } => {
+                write!(f, "~ component {}: {:?} -> {:?}", id, old, new)
+            }
+            ScreenChange::MoreBlockAdded { name } => write!(f, "+ moreblock {}", name),
+            ScreenChange::MoreBlockRemoved { name } => write!(f, "- moreblock {}", name),
+            ScreenChange::MoreBlockSpecChanged { name, old, new } => {
+                write!(f, "~ moreblock {} spec: {:?} -> {:?}", name, old, new)
+            }
+            ScreenChange::MoreBlockCodeChanged { name, edits } => {
+                write!(f, "~ moreblock {}", name)?;
+                write_edits(f, edits)
+            }
+            ScreenChange::EventAdded { container } => write!(f, "+ event {}", container),
+            ScreenChange::EventRemoved { container } => write!(f, "- event {}", container),
+            ScreenChange::EventCodeChanged { container, edits } => {
+                write!(f, "~ event {}", container)?;
+                write_edits(f, edits)
+            }
+        }
+    }
+}
+
+impl Display for BlockEdit {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BlockEdit::Inserted { path, block } => {
+                write!(f, "+ block {} at {}", block.op_code, path)
+            }
+            BlockEdit::Removed { path, block } => {
+                write!(f, "- block {} at {}", block.op_code, path)
+            }
+            BlockEdit::ArgumentChanged {
+                path,
+                op_code,
+                argument,
+                old,
+                new,
+            } => write!(
+                f,
+                "~ block {} at {} argument {}: {} -> {}",
+                op_code,
+                path,
+                argument,
+                format_argument(old),
+                format_argument(new)
+            ),
+        }
+    }
+}
@@ -0,0 +1,364 @@
+//! A catalog of the events that sketchware provides for each view type ([`ViewEvent`]) and each
+//! component kind ([`ComponentEvent`]), along with their parameters.
+//!
+//! Events can be created with [`Event::for_view`] and [`Event::for_component`], which refuse
+//! events the target doesn't have, and existing events can be checked against the actual types
+//! of their targets with [`validate_screen`] or [`validate_project`].
+
+use crate::api::block::Blocks;
+use crate::api::component::ComponentKind;
+use crate::api::screen::{Event, EventType, Screen};
+use crate::api::view::{View, ViewType};
+use crate::api::SketchwareProject;
+use thiserror::Error;
+
+macro_rules! event_catalog {
+    ($(#[$meta:meta])* $enum_name:ident {
+        $($variant:ident => $name:literal, $parameters:literal;)*
+    }) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum $enum_name {
+            $($variant,)*
+        }
+
+        impl $enum_name {
+            /// Every event of this catalog
+            pub const ALL: &'static [$enum_name] = &[$($enum_name::$variant,)*];
+
+            /// Retrieves the name of this event, as it's stored in the logic file
+            pub fn name(&self) -> &'static str {
+                match self {
+                    $($enum_name::$variant => $name,)*
+                }
+            }
+
+            /// Retrieves the parameters this event gives to its blocks, in the same format as
+            /// the specs of moreblocks (like `%d.position`)
+            pub fn parameters(&self) -> &'static str {
+                match self {
+                    $($enum_name::$variant => $parameters,)*
+                }
+            }
+
+            /// Finds an event using its name
+            pub fn from_name(name: &str) -> Option<$enum_name> {
+                match name {
+                    $($name => Some($enum_name::$variant),)*
+                    _ => None,
+                }
+            }
+
+            /// Retrieves the block container id of this event on the target with the given id
+            pub fn container_id(&self, target_id: &str) -> String {
+                format!("{}_{}", target_id, self.name())
+            }
+        }
+    };
+}
+
+event_catalog! {
+    /// An event of a view
+    ViewEvent {
+        OnClick => "onClick", "";
+        OnTextChanged => "onTextChanged", "%s.charSeq";
+        OnCheckedChange => "onCheckedChange", "%b.isChecked";
+        OnProgressChanged => "onProgressChanged", "%d.progressValue";
+        OnStartTrackingTouch => "onStartTrackingTouch", "";
+        OnStopTrackingTouch => "onStopTrackingTouch", "";
+        OnItemSelected => "onItemSelected", "%d.position";
+        OnNothingSelected => "onNothingSelected", "";
+        OnItemClicked => "onItemClicked", "%d.position";
+        OnItemLongClicked => "onItemLongClicked", "%d.position";
+        OnBindCustomView => "onBindCustomView", "%d.position";
+        OnPageStarted => "onPageStarted", "%s.url";
+        OnPageFinished => "onPageFinished", "%s.url";
+        OnDateChange => "onDateChange", "%d.year %d.month %d.day";
+        OnBannerAdLoaded => "onBannerAdLoaded", "";
+        OnBannerAdFailedToLoad => "onBannerAdFailedToLoad", "%d.errorCode";
+        OnBannerAdOpened => "onBannerAdOpened", "";
+        OnBannerAdClosed => "onBannerAdClosed", "";
+        OnMapReady => "onMapReady", "";
+        OnMarkerClicked => "onMarkerClicked", "%s.tag";
+    }
+}
+
+event_catalog! {
+    /// An event of a component
+    ComponentEvent {
+        OnChildAdded => "onChildAdded", "%s.childKey %m.varMap.childValue";
+        OnChildChanged => "onChildChanged", "%s.childKey %m.varMap.childValue";
+        OnChildRemoved => "onChildRemoved", "%s.childKey %m.varMap.childValue";
+        OnCancelled => "onCancelled", "%d.errorCode %s.errorMessage";
+        OnAnimationStart => "onAnimationStart", "";
+        OnAnimationEnd => "onAnimationEnd", "";
+        OnAnimationCancel => "onAnimationCancel", "";
+        OnAnimationRepeat => "onAnimationRepeat", "";
+        OnSensorChanged => "onSensorChanged", "%d.x %d.y %d.z";
+        OnCreateUserComplete => "onCreateUserComplete", "%b.success %s.errorMessage";
+        OnSignInUserComplete => "onSignInUserComplete", "%b.success %s.errorMessage";
+        OnResetPasswordEmailSent => "onResetPasswordEmailSent", "%b.success";
+        OnAdLoaded => "onAdLoaded", "";
+        OnAdFailedToLoad => "onAdFailedToLoad", "%d.errorCode";
+        OnAdOpened => "onAdOpened", "";
+        OnAdClosed => "onAdClosed", "";
+        OnUploadProgress => "onUploadProgress", "%d.progressValue";
+        OnDownloadProgress => "onDownloadProgress", "%d.progressValue";
+        OnUploadSuccess => "onUploadSuccess", "%s.downloadUrl";
+        OnDownloadSuccess => "onDownloadSuccess", "%d.totalByteCount";
+        OnDeleteSuccess => "onDeleteSuccess", "";
+        OnFailure => "onFailure", "%s.message";
+        OnPictureTaken => "onPictureTaken", "%s.filePath";
+        OnPictureTakenCancel => "onPictureTakenCancel", "";
+        OnFilesPicked => "onFilesPicked", "%m.listStr.filePath";
+        OnFilesPickedCancel => "onFilesPickedCancel", "";
+        OnResponse => "onResponse", "%s.tag %s.response";
+        OnErrorResponse => "onErrorResponse", "%s.tag %s.message";
+        OnSpeechResponse => "onSpeechResponse", "%s.result";
+        OnSpeechError => "onSpeechError", "%s.errorMessage";
+        OnConnected => "onConnected", "%s.tag";
+        OnDataReceived => "onDataReceived", "%s.tag %s.data";
+        OnDataSent => "onDataSent", "%s.tag %s.data";
+        OnConnectionError => "onConnectionError", "%s.tag %s.connectionState %s.errorMessage";
+        OnConnectionStopped => "onConnectionStopped", "%s.tag";
+        OnLocationChanged => "onLocationChanged", "%d.latitude %d.longitude %d.accuracy";
+    }
+}
+
+impl ViewEvent {
+    /// Retrieves the events a view type has
+    pub fn of(view_type: &ViewType) -> &'static [ViewEvent] {
+        use ViewEvent::*;
+
+        match view_type {
+            ViewType::LinearLayout { .. }
+            | ViewType::ScrollView { .. }
+            | ViewType::Button { .. }
+            | ViewType::TextView { .. }
+            | ViewType::ImageView { .. }
+            | ViewType::Fab { .. } => &[OnClick],
+            ViewType::EditText { .. } => &[OnClick, OnTextChanged],
+            ViewType::CheckBox { .. } | ViewType::Switch { .. } => &[OnClick, OnCheckedChange],
+            ViewType::SeekBar { .. } => {
+                &[OnProgressChanged, OnStartTrackingTouch, OnStopTrackingTouch]
+            }
+            ViewType::Spinner { .. } => &[OnItemSelected, OnNothingSelected],
+            ViewType::ListView { .. } => &[OnItemClicked, OnItemLongClicked, OnBindCustomView],
+            ViewType::WebView => &[OnPageStarted, OnPageFinished],
+            ViewType::CalendarView { .. } => &[OnDateChange],
+            ViewType::AdView { .. } => &[
+                OnBannerAdLoaded,
+                OnBannerAdFailedToLoad,
+                OnBannerAdOpened,
+                OnBannerAdClosed,
+            ],
+            ViewType::MapView => &[OnMapReady, OnMarkerClicked],
+            ViewType::ProgressBar { .. } => &[],
+        }
+    }
+}
+
+impl ComponentEvent {
+    /// Retrieves the events a component kind has
+    pub fn of(kind: &ComponentKind) -> &'static [ComponentEvent] {
+        use ComponentEvent::*;
+
+        match kind {
+            ComponentKind::FirebaseDatabase { .. } => {
+                &[OnChildAdded, OnChildChanged, OnChildRemoved, OnCancelled]
+            }
+            ComponentKind::ObjectAnimator => &[
+                OnAnimationStart,
+                OnAnimationEnd,
+                OnAnimationCancel,
+                OnAnimationRepeat,
+            ],
+            ComponentKind::Gyroscope => &[OnSensorChanged],
+            ComponentKind::FirebaseAuth => &[
+                OnCreateUserComplete,
+                OnSignInUserComplete,
+                OnResetPasswordEmailSent,
+            ],
+            ComponentKind::InterstitialAd => {
+                &[OnAdLoaded, OnAdFailedToLoad, OnAdOpened, OnAdClosed]
+            }
+            ComponentKind::FirebaseStorage { .. } => &[
+                OnUploadProgress,
+                OnDownloadProgress,
+                OnUploadSuccess,
+                OnDownloadSuccess,
+                OnDeleteSuccess,
+                OnFailure,
+            ],
+            ComponentKind::Camera => &[OnPictureTaken, OnPictureTakenCancel],
+            ComponentKind::FilePicker { .. } => &[OnFilesPicked, OnFilesPickedCancel],
+            ComponentKind::RequestNetwork => &[OnResponse, OnErrorResponse],
+            ComponentKind::SpeechToText => &[OnSpeechResponse, OnSpeechError],
+            ComponentKind::BluetoothConnect => &[
+                OnConnected,
+                OnDataReceived,
+                OnDataSent,
+                OnConnectionError,
+                OnConnectionStopped,
+            ],
+            ComponentKind::LocationManager => &[OnLocationChanged],
+            ComponentKind::Intent
+            | ComponentKind::SharedPreferences { .. }
+            | ComponentKind::Calendar
+            | ComponentKind::Vibrator
+            | ComponentKind::Timer
+            | ComponentKind::Dialog
+            | ComponentKind::MediaPlayer
+            | ComponentKind::SoundPool
+            | ComponentKind::TextToSpeech
+            | ComponentKind::Unknown { .. } => &[],
+        }
+    }
+}
+
+impl Event {
+    /// Creates an empty event of a view, fails if the view doesn't have the event
+    pub fn for_view(view: &View, event: ViewEvent) -> Result<Event, UnsupportedEvent> {
+        let view_type = view.view.as_ref().map_err(|_| UnsupportedEvent {
+            event: event.name().to_string(),
+            target: "unknown view".to_string(),
+        })?;
+
+        if !ViewEvent::of(view_type).contains(&event) {
+            Err(UnsupportedEvent {
+                event: event.name().to_string(),
+                target: view_type.name().to_string(),
+            })?
+        }
+
+        Ok(Event {
+            name: event.name().to_string(),
+            event_type: EventType::ViewEvent {
+                id: view.id.to_owned(),
+            },
+            code: Blocks::new(),
+        })
+    }
+
+    /// Creates an empty event of a component, fails if the component doesn't have the event
+    pub fn for_component(
+        id: &str,
+        kind: &ComponentKind,
+        event: ComponentEvent,
+    ) -> Result<Event, UnsupportedEvent> {
+        if !ComponentEvent::of(kind).contains(&event) {
+            Err(UnsupportedEvent {
+                event: event.name().to_string(),
+                target: kind.name().to_string(),
+            })?
+        }
+
+        Ok(Event {
+            name: event.name().to_string(),
+            event_type: EventType::ComponentEvent {
+                id: id.to_string(),
+                component_type: kind.get_type_id(),
+            },
+            code: Blocks::new(),
+        })
+    }
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("{target} doesn't have the event `{event}`")]
+pub struct UnsupportedEvent {
+    pub event: String,
+
+    /// The name of the view type or the component kind
+    pub target: String,
+}
+
+/// An event that doesn't match the actual type of its target
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("{screen}, {container}: {kind}")]
+pub struct InvalidEvent {
+    /// The layout name of the screen
+    pub screen: String,
+
+    /// The block container id of the event
+    pub container: String,
+    pub kind: InvalidEventKind,
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum InvalidEventKind {
+    #[error("there is no view or component with the id `{id}`")]
+    MissingTarget { id: String },
+
+    #[error(transparent)]
+    Unsupported(#[from] UnsupportedEvent),
+
+    #[error("the event says its component is of type {found}, but it's {expected}")]
+    ComponentTypeMismatch { expected: u8, found: u8 },
+}
+
+/// Checks every view and component event of a screen against the actual types of their targets
+/// (views of the drawer included). Events of views that have an unknown type are skipped.
+pub fn validate_screen(screen: &Screen) -> Vec<InvalidEvent> {
+    let mut result = Vec::new();
+
+    for event in &screen.events {
+        let kind = match &event.event_type {
+            EventType::ViewEvent { id } => match screen.find_view(id) {
+                Some(View {
+                    view: Ok(view_type),
+                    ..
+                }) => check_name(
+                    ViewEvent::from_name(&event.name)
+                        .filter(|event| ViewEvent::of(view_type).contains(event))
+                        .is_some(),
+                    &event.name,
+                    view_type.name(),
+                ),
+                Some(_) => continue,
+                None => Some(InvalidEventKind::MissingTarget { id: id.to_owned() }),
+            },
+            EventType::ComponentEvent { id, component_type } => match screen.components.get(id) {
+                Some(kind) if kind.get_type_id() != *component_type => {
+                    Some(InvalidEventKind::ComponentTypeMismatch {
+                        expected: kind.get_type_id(),
+                        found: *component_type,
+                    })
+                }
+                Some(kind) => check_name(
+                    ComponentEvent::from_name(&event.name)
+                        .filter(|event| ComponentEvent::of(kind).contains(event))
+                        .is_some(),
+                    &event.name,
+                    kind.name(),
+                ),
+                None => Some(InvalidEventKind::MissingTarget { id: id.to_owned() }),
+            },
+            EventType::ActivityEvent => continue,
+        };
+
+        if let Some(kind) = kind {
+            result.push(InvalidEvent {
+                screen: screen.layout_name.to_owned(),
+                container: event.get_block_container_id(),
+                kind,
+            });
+        }
+    }
+
+    result
+}
+
+/// Checks the events of every screen of a project, see [`validate_screen`]
+pub fn validate_project(project: &SketchwareProject) -> Vec<InvalidEvent> {
+    project.screens.iter().flat_map(validate_screen).collect()
+}
+
+fn check_name(supported: bool, event: &str, target: &str) -> Option<InvalidEventKind> {
+    (!supported).then(|| {
+        InvalidEventKind::Unsupported(UnsupportedEvent {
+            event: event.to_string(),
+            target: target.to_string(),
+        })
+    })
+}
@@ -0,0 +1,444 @@
+//! Localization of projects. [`StringTable::extract`] collects every user-visible string of a
+//! project (texts and hints of views, and string literals of blocks) with stable keys, which can
+//! be written as Android `strings.xml` files per locale ([`write_strings_xml`]). A translated set
+//! of strings can then be put back into a copy of the project with [`localize`].
+//!
+//! Keys are derived from where the strings are, like `main_button1_text` or
+//! `main_button1_onclick_0_arg0`, so they stay the same as long as the views and blocks stay
+//! where they are.
+
+use crate::api::block::{ArgValue, Argument, BlockPath, BlockPathSegment, Blocks};
+use crate::api::blockly::xml::{self, XmlParseError};
+use crate::api::view::{View, ViewType};
+use crate::api::SketchwareProject;
+use crate::util::escape_xml;
+use crate::LinkedHashMap;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use thiserror::Error;
+
+/// Blocks whose string arguments are code rather than user-visible text
+const CODE_BLOCKS: &[&str] = &["addSourceDirectly"];
+
+/// The field of a view a string comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ViewField {
+    Text,
+    Hint,
+}
+
+/// Where an extracted string comes from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StringSource {
+    View {
+        /// The layout name of the screen or the name of the custom view
+        layout: String,
+
+        /// The id of the view, views of a drawer have their ids prefixed with
+        /// [`DRAWER_PREFIX`](crate::api::screen::DRAWER_PREFIX)
+        id: String,
+        field: ViewField,
+    },
+
+    Block {
+        /// The layout name of the screen
+        screen: String,
+
+        /// The block container id of the event or the moreblock the block is in
+        container: String,
+        path: BlockPath,
+
+        /// The index of the string argument of the block
+        argument: usize,
+    },
+}
+
+/// A user-visible string of a project
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExtractedString {
+    pub key: String,
+    pub value: String,
+    pub source: StringSource,
+}
+
+/// Every user-visible string of a project
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StringTable {
+    pub strings: Vec<ExtractedString>,
+}
+
+impl StringTable {
+    /// Extracts the texts and hints of every view in the screens (including their drawers) and
+    /// the custom views, and the string literals of every block (except `addSourceDirectly`).
+    /// Empty strings are skipped.
+    pub fn extract(project: &SketchwareProject) -> StringTable {
+        let mut strings = Vec::new();
+
+        for screen in &project.screens {
+            for (id, view) in screen.all_views() {
+                extract_view(&screen.layout_name, &id, view, &mut strings);
+            }
+        }
+
+        for custom_view in &project.custom_views {
+            extract_views(&custom_view.res_name, &custom_view.layout, &mut strings);
+        }
+
+        for screen in &project.screens {
+            for (container, blocks) in screen.block_containers() {
+                extract_blocks(&screen.layout_name, &container, blocks, &mut strings);
+            }
+        }
+
+        // make sure keys are unique, in case two different locations sanitize into the same key
+        let mut used = HashSet::new();
+        for string in &mut strings {
+            let mut key = string.key.to_owned();
+            let mut count = 1;
+
+            while !used.insert(key.to_owned()) {
+                count += 1;
+                key = format!("{}_{}", string.key, count);
+            }
+
+            string.key = key;
+        }
+
+        StringTable { strings }
+    }
+
+    /// Retrieves the value of a string using its key
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.strings
+            .iter()
+            .find(|string| string.key == key)
+            .map(|string| string.value.as_str())
+    }
+
+    /// Retrieves every string as a key-value map, to be used as the default locale
+    pub fn to_map(&self) -> LinkedHashMap<String, String> {
+        self.strings
+            .iter()
+            .map(|string| (string.key.to_owned(), string.value.to_owned()))
+            .collect()
+    }
+}
+
+fn extract_views(layout: &str, views: &[View], strings: &mut Vec<ExtractedString>) {
+    for view in views {
+        extract_view(layout, &view.id, view, strings);
+        extract_views(layout, &view.children, strings);
+    }
+}
+
+/// Extracts the strings of a single view, `id` is the id the view is referred to with
+fn extract_view(layout: &str, id: &str, view: &View, strings: &mut Vec<ExtractedString>) {
+    let fields: Vec<(ViewField, &str)> = match &view.view {
+        Ok(ViewType::EditText { text, hint, .. }) => {
+            vec![(ViewField::Text, text), (ViewField::Hint, hint)]
+        }
+        Ok(
+            ViewType::Button { text, .. }
+            | ViewType::TextView { text, .. }
+            | ViewType::CheckBox { text, .. }
+            | ViewType::Switch { text, .. },
+        ) => vec![(ViewField::Text, text)],
+        _ => vec![],
+    };
+
+    for (field, value) in fields {
+        if value.is_empty() {
+            continue;
+        }
+
+        let suffix = match field {
+            ViewField::Text => "text",
+            ViewField::Hint => "hint",
+        };
+
+        strings.push(ExtractedString {
+            key: key(&format!("{}_{}_{}", layout, id, suffix)),
+            value: value.to_string(),
+            source: StringSource::View {
+                layout: layout.to_string(),
+                id: id.to_string(),
+                field,
+            },
+        });
+    }
+}
+
+fn extract_blocks(
+    screen: &str,
+    container: &str,
+    blocks: &Blocks,
+    strings: &mut Vec<ExtractedString>,
+) {
+    blocks.visit(|path, block| {
+        if CODE_BLOCKS.contains(&block.op_code.as_str()) {
+            return;
+        }
+
+        for (index, arg) in block.content.get_args().into_iter().enumerate() {
+            let value = match arg {
+                Argument::String {
+                    value: ArgValue::Value(value),
+                    ..
+                } if !value.trim().is_empty() => value,
+                _ => continue,
+            };
+
+            let location = path.join(BlockPathSegment::Argument(index));
+
+            strings.push(ExtractedString {
+                key: key(&format!(
+                    "{}_{}_{}",
+                    screen,
+                    container,
+                    location.to_string().replace('/', "_")
+                )),
+                value: value.to_owned(),
+                source: StringSource::Block {
+                    screen: screen.to_string(),
+                    container: container.to_string(),
+                    path: path.to_owned(),
+                    argument: index,
+                },
+            });
+        }
+    });
+}
+
+/// Turns a name into a valid Android resource name: lowercase letters, digits and underscores,
+/// starting with a letter
+fn key(name: &str) -> String {
+    let key: String = name
+        .chars()
+        .map(|char| {
+            if char.is_ascii_alphanumeric() {
+                char.to_ascii_lowercase()
+            } else {
+                '_'
+            }
+        })
+        .collect();
+
+    if key.starts_with(|char: char| char.is_ascii_lowercase()) {
+        key
+    } else {
+        format!("s_{}", key)
+    }
+}
+
+/// Creates a copy of a project with its strings replaced by the given translations, keyed the
+/// same way as [`StringTable::extract`]. Strings that aren't translated are left as is.
+pub fn localize(
+    project: &SketchwareProject,
+    translations: &LinkedHashMap<String, String>,
+) -> SketchwareProject {
+    let table = StringTable::extract(project);
+    let mut project = project.clone();
+
+    for string in table.strings {
+        let translation = match translations.get(&string.key) {
+            Some(translation) => translation.to_owned(),
+            None => continue,
+        };
+
+        match string.source {
+            StringSource::View { layout, id, field } => {
+                let view = project
+                    .screens
+                    .iter_mut()
+                    .filter(|screen| screen.layout_name == layout)
+                    .find_map(|screen| screen.find_view_mut(&id))
+                    .or_else(|| {
+                        project
+                            .custom_views
+                            .iter_mut()
+                            .filter(|custom_view| custom_view.res_name == layout)
+                            .flat_map(|custom_view| custom_view.layout.iter_mut())
+                            .find_map(|view| view.find_id_mut(&id))
+                    });
+
+                if let Some(Ok(view_type)) = view.map(|view| &mut view.view) {
+                    set_view_string(view_type, field, translation);
+                }
+            }
+
+            StringSource::Block {
+                screen,
+                container,
+                path,
+                argument,
+            } => {
+                let screen = match project
+                    .screens
+                    .iter_mut()
+                    .find(|candidate| candidate.layout_name == screen)
+                {
+                    Some(screen) => screen,
+                    None => continue,
+                };
+
+                let blocks = match container.strip_suffix("_moreBlock") {
+                    Some(name) if screen.more_blocks.contains_key(name) => screen
+                        .more_blocks
+                        .get_mut(name)
+                        .map(|more_block| &mut more_block.code),
+                    _ => screen
+                        .events
+                        .iter_mut()
+                        .find(|event| event.get_block_container_id() == container)
+                        .map(|event| &mut event.code),
+                };
+
+                let arg = blocks
+                    .and_then(|blocks| blocks.get_mut(&path))
+                    .and_then(|block| block.content.get_args_mut().into_iter().nth(argument));
+
+                if let Some(Argument::String { value, .. }) = arg {
+                    *value = ArgValue::Value(translation);
+                }
+            }
+        }
+    }
+
+    project
+}
+
+fn set_view_string(view_type: &mut ViewType, field: ViewField, value: String) {
+    match (view_type, field) {
+        (ViewType::EditText { hint, .. }, ViewField::Hint) => *hint = value,
+        (
+            ViewType::Button { text, .. }
+            | ViewType::TextView { text, .. }
+            | ViewType::EditText { text, .. }
+            | ViewType::CheckBox { text, .. }
+            | ViewType::Switch { text, .. },
+            ViewField::Text,
+        ) => *text = value,
+        _ => {}
+    }
+}
+
+/// Generates an Android `strings.xml` file from the given strings
+pub fn to_strings_xml(strings: &LinkedHashMap<String, String>) -> String {
+    let mut result = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<resources>\n");
+
+    for (key, value) in strings {
+        result.push_str(&format!(
+            "    <string name=\"{}\">{}</string>\n",
+            escape_xml(key),
+            escape_xml(&escape_android(value))
+        ));
+    }
+
+    result.push_str("</resources>\n");
+    result
+}
+
+/// Writes the given strings into `values/strings.xml` (or `values-<locale>/strings.xml` if a
+/// locale is given) inside of a resource folder, returns the path of the written file
+pub fn write_strings_xml(
+    res: &Path,
+    locale: Option<&str>,
+    strings: &LinkedHashMap<String, String>,
+) -> std::io::Result<PathBuf> {
+    let folder = match locale {
+        Some(locale) => res.join(format!("values-{}", locale)),
+        None => res.join("values"),
+    };
+
+    std::fs::create_dir_all(&folder)?;
+
+    let path = folder.join("strings.xml");
+    std::fs::write(&path, to_strings_xml(strings))?;
+
+    Ok(path)
+}
+
+/// Parses the `<string>` elements of an Android `strings.xml` file, other elements are ignored
+pub fn parse_strings_xml(
+    content: &str,
+) -> Result<LinkedHashMap<String, String>, StringsXmlParseError> {
+    let root =
+        xml::parse(content).map_err(|source| StringsXmlParseError::MalformedXml { source })?;
+
+    if root.name != "resources" {
+        Err(StringsXmlParseError::InvalidRoot {
+            name: root.name.to_owned(),
+        })?
+    }
+
+    root.elements("string")
+        .map(|element| {
+            let name = element
+                .attribute("name")
+                .ok_or(StringsXmlParseError::MissingName)?;
+
+            Ok((name.to_string(), unescape_android(&element.text())))
+        })
+        .collect()
+}
+
+#[derive(Error, Debug)]
+pub enum StringsXmlParseError {
+    #[error("malformed xml")]
+    MalformedXml { source: XmlParseError },
+
+    #[error("expected the root element to be `resources`, found `{name}`")]
+    InvalidRoot { name: String },
+
+    #[error("a string element doesn't have a name")]
+    MissingName,
+}
+
+/// Escapes the characters that Android treats specially in string resources
+fn escape_android(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+
+    for (index, char) in value.chars().enumerate() {
+        match char {
+            '\\' => result.push_str("\\\\"),
+            '\'' => result.push_str("\\'"),
+            '"' => result.push_str("\\\""),
+            '\n' => result.push_str("\\n"),
+            '\t' => result.push_str("\\t"),
+            '@' | '?' if index == 0 => {
+                result.push('\\');
+                result.push(char);
+            }
+            _ => result.push(char),
+        }
+    }
+
+    result
+}
+
+/// Reverses [`escape_android`], strings wrapped in double quotes are unwrapped
+fn unescape_android(value: &str) -> String {
+    let value = value
+        .strip_prefix('"')
+        .and_then(|value| value.strip_suffix('"'))
+        .unwrap_or(value);
+
+    let mut result = String::with_capacity(value.len());
+    let mut chars = value.chars();
+
+    while let Some(char) = chars.next() {
+        if char != '\\' {
+            result.push(char);
+            continue;
+        }
+
+        match chars.next() {
+            Some('n') => result.push('\n'),
+            Some('t') => result.push('\t'),
+            Some(escaped) => result.push(escaped),
+            None => result.push('\\'),
+        }
+    }
+
+    result
+}
@@ -0,0 +1,130 @@
+//! Generates ids the way sketchware does: a prefix that depends on the type of the view or
+//! component (like `linear` or `textview`) followed by the lowest number that isn't used yet
+//! (like `linear1` or `textview3`).
+
+use crate::api::component::ComponentKind;
+use crate::api::screen::Screen;
+use crate::api::view::{View, ViewType};
+use crate::parser::view::models::layout::Orientation;
+use std::collections::HashSet;
+
+/// Allocates unique ids for the views and components of a screen. Every name that's used in the
+/// screen is taken into account: views (including the fab and the views of the drawer),
+/// components, variables, list variables and moreblocks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IdAllocator {
+    used: HashSet<String>,
+}
+
+impl IdAllocator {
+    /// Creates an allocator that avoids the names used in a screen
+    pub fn new(screen: &Screen) -> Self {
+        let views = screen
+            .views()
+            .into_iter()
+            .chain(screen.drawer.iter().flat_map(|drawer| drawer.views()))
+            .map(|view| view.id.to_owned());
+
+        let used = views
+            .chain(screen.components.keys().cloned())
+            .chain(screen.variables.keys().cloned())
+            .chain(screen.list_variables.keys().cloned())
+            .chain(screen.more_blocks.keys().cloned())
+            .collect();
+
+        IdAllocator { used }
+    }
+
+    /// Creates an allocator that avoids the given ids
+    pub fn from_ids<I: IntoIterator<Item = String>>(ids: I) -> Self {
+        IdAllocator {
+            used: ids.into_iter().collect(),
+        }
+    }
+
+    /// Allocates an id for a view of the given type, like `linear1` or `textview3`
+    pub fn view_id(&mut self, view_type: &ViewType) -> String {
+        self.next(view_prefix(view_type))
+    }
+
+    /// Allocates an id for a component of the given kind, like `timer1` or `intent2`
+    pub fn component_id(&mut self, kind: &ComponentKind) -> String {
+        self.next(&component_prefix(kind))
+    }
+
+    /// Allocates an id with the given prefix followed by the lowest number that isn't used
+    pub fn next(&mut self, prefix: &str) -> String {
+        let id = (1..)
+            .map(|number| format!("{}{}", prefix, number))
+            .find(|id| !self.used.contains(id))
+            .unwrap();
+
+        self.used.insert(id.to_owned());
+        id
+    }
+
+    /// Marks an id as used, returns false if it's already used
+    pub fn reserve(&mut self, id: &str) -> bool {
+        self.used.insert(id.to_string())
+    }
+
+    /// Checks whether an id is used
+    pub fn is_used(&self, id: &str) -> bool {
+        self.used.contains(id)
+    }
+}
+
+/// Retrieves the prefix sketchware gives to the ids of views of the given type
+fn view_prefix(view_type: &ViewType) -> &'static str {
+    match view_type {
+        ViewType::LinearLayout { .. } => "linear",
+        ViewType::ScrollView {
+            orientation: Orientation::Horizontal,
+            ..
+        } => "hscroll",
+        ViewType::ScrollView { .. } => "vscroll",
+        ViewType::Button { .. } => "button",
+        ViewType::TextView { .. } => "textview",
+        ViewType::EditText { .. } => "edittext",
+        ViewType::ImageView { .. } => "imageview",
+        ViewType::WebView => "webview",
+        ViewType::ProgressBar { .. } => "progressbar",
+        ViewType::ListView { .. } => "listview",
+        ViewType::Spinner { .. } => "spinner",
+        ViewType::CheckBox { .. } => "checkbox",
+        ViewType::Switch { .. } => "switch",
+        ViewType::SeekBar { .. } => "seekbar",
+        ViewType::CalendarView { .. } => "calendarview",
+        ViewType::Fab { .. } => "fab",
+        ViewType::AdView { .. } => "adview",
+        ViewType::MapView => "mapview",
+    }
+}
+
+/// Retrieves the prefix of the ids of components of the given kind, which is its lowercased name
+fn component_prefix(kind: &ComponentKind) -> String {
+    match kind {
+        ComponentKind::Unknown { .. } => "component".to_string(),
+        kind => kind.name().to_ascii_lowercase(),
+    }
+}
+
+impl Screen {
+    /// Creates an [`IdAllocator`] for this screen
+    pub fn id_allocator(&self) -> IdAllocator {
+        IdAllocator::new(self)
+    }
+
+    /// Adds a component of the given kind with a newly allocated id, returns its id
+    pub fn add_component(&mut self, kind: ComponentKind) -> String {
+        let id = self.id_allocator().component_id(&kind);
+        self.components.insert(id.to_owned(), kind);
+        id
+    }
+
+    /// Creates a view of the given type with a newly allocated id, without adding it to the
+    /// layout (see [`Screen::add_view`])
+    pub fn new_view(&self, view_type: ViewType) -> View {
+        View::new(&self.id_allocator().view_id(&view_type), view_type)
+    }
+}
@@ -0,0 +1,906 @@
+//! An interpreter that runs block logic outside of android. It executes control, operator, math,
+//! string, variable, list and moreblock blocks by itself, and hands everything else (android
+//! specific blocks like `setText` or `doToast`) over to a [`Host`].
+
+use crate::api::block::{ArgValue, Argument, Block, Blocks, SpecItem};
+use crate::api::screen::{Event, MoreBlock, Screen};
+use crate::parser::logic::variable::VariableType;
+use crate::LinkedHashMap;
+use std::fmt::{Display, Formatter};
+use thiserror::Error;
+
+/// The default maximum amount of steps an [`Interpreter`] can take
+pub const DEFAULT_STEP_BUDGET: usize = 100_000;
+
+/// The maximum depth of nested moreblock calls
+pub const MAX_CALL_DEPTH: usize = 128;
+
+/// A value that blocks work with
+#[derive(Debug, Clone, PartialEq)]
+pub enum Value {
+    Boolean(bool),
+    Number(f64),
+    String(String),
+    Map(LinkedHashMap<String, Value>),
+    List(Vec<Value>),
+}
+
+impl Value {
+    /// Retrieves the default value of a variable type, like how sketchware initializes them
+    pub fn default_of(variable_type: VariableType) -> Self {
+        match variable_type {
+            VariableType::Boolean => Value::Boolean(false),
+            VariableType::Integer => Value::Number(0.0),
+            VariableType::String => Value::String(String::new()),
+            VariableType::HashMap => Value::Map(LinkedHashMap::new()),
+        }
+    }
+
+    /// The name of the type of this value
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Value::Boolean(_) => "boolean",
+            Value::Number(_) => "number",
+            Value::String(_) => "string",
+            Value::Map(_) => "map",
+            Value::List(_) => "list",
+        }
+    }
+}
+
+impl Display for Value {
+    /// Formats the value the same way java's `toString` would
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Value::Boolean(value) => write!(f, "{}", value),
+            Value::Number(value) => write!(f, "{}", format_double(*value)),
+            Value::String(value) => write!(f, "{}", value),
+            Value::Map(map) => {
+                write!(f, "{{")?;
+                for (index, (key, value)) in map.iter().enumerate() {
+                    if index != 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}={}", key, value)?;
+                }
+                write!(f, "}}")
+            }
+            Value::List(list) => {
+                write!(f, "[")?;
+                for (index, value) in list.iter().enumerate() {
+                    if index != 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", value)?;
+                }
+                write!(f, "]")
+            }
+        }
+    }
+}
+
+/// Formats a double like java's `String.valueOf(double)` for the usual cases
+fn format_double(value: f64) -> String {
+    if value.is_finite() && value.fract() == 0.0 && value.abs() < 1e7 {
+        format!("{:.1}", value)
+    } else {
+        value.to_string()
+    }
+}
+
+/// The global variables and lists the blocks work on
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Environment {
+    pub variables: LinkedHashMap<String, Value>,
+    pub lists: LinkedHashMap<String, Vec<Value>>,
+}
+
+impl Environment {
+    /// Creates an environment that contains the variables and lists of a screen, initialized with
+    /// their default values
+    pub fn from_screen(screen: &Screen) -> Self {
+        Environment {
+            variables: screen
+                .variables
+                .iter()
+                .map(|(name, variable)| (name.to_owned(), Value::default_of(variable.r#type)))
+                .collect(),
+            lists: screen
+                .list_variables
+                .keys()
+                .map(|name| (name.to_owned(), Vec::new()))
+                .collect(),
+        }
+    }
+}
+
+/// Executes blocks that the [`Interpreter`] doesn't support by itself. This is where android
+/// specific blocks (and nondeterministic ones like `random`) should be implemented or mocked.
+///
+/// Closures with the same signature as [`Host::execute`] are hosts too.
+pub trait Host {
+    /// Executes the given block with its already evaluated arguments, returns the value it
+    /// returns (if any)
+    fn execute(
+        &mut self,
+        block: &Block,
+        args: Vec<Value>,
+    ) -> Result<Option<Value>, InterpreterError>;
+}
+
+impl<F> Host for F
+where
+    F: FnMut(&Block, Vec<Value>) -> Result<Option<Value>, InterpreterError>,
+{
+    fn execute(
+        &mut self,
+        block: &Block,
+        args: Vec<Value>,
+    ) -> Result<Option<Value>, InterpreterError> {
+        self(block, args)
+    }
+}
+
+/// A host that doesn't support any blocks
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct NoHost;
+
+impl Host for NoHost {
+    fn execute(
+        &mut self,
+        block: &Block,
+        _args: Vec<Value>,
+    ) -> Result<Option<Value>, InterpreterError> {
+        Err(InterpreterError::UnsupportedBlock {
+            op_code: block.op_code.to_owned(),
+        })
+    }
+}
+
+/// What should happen after a block has been executed
+enum Flow {
+    Continue,
+    Break,
+}
+
+/// Runs blocks of a screen against an in-memory [`Environment`]
+pub struct Interpreter<'a, H: Host> {
+    pub env: Environment,
+    pub host: H,
+
+    more_blocks: &'a LinkedHashMap<String, MoreBlock>,
+
+    /// Arguments of the moreblocks that are currently being called
+    frames: Vec<LinkedHashMap<String, Value>>,
+
+    step_budget: usize,
+    steps: usize,
+}
+
+impl<'a, H: Host> Interpreter<'a, H> {
+    /// Creates an interpreter with an environment seeded from the variables and lists of a screen
+    /// which can call the moreblocks of the screen
+    pub fn new(screen: &'a Screen, host: H) -> Self {
+        Interpreter {
+            env: Environment::from_screen(screen),
+            host,
+            more_blocks: &screen.more_blocks,
+            frames: Vec::new(),
+            step_budget: DEFAULT_STEP_BUDGET,
+            steps: 0,
+        }
+    }
+
+    /// Sets the maximum amount of steps this interpreter can take, a step is taken every time a
+    /// block is executed or a loop iterates. Defaults to [`DEFAULT_STEP_BUDGET`]
+    pub fn with_step_budget(mut self, step_budget: usize) -> Self {
+        self.step_budget = step_budget;
+        self
+    }
+
+    /// Retrieves how many steps has been taken
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
+    /// Runs the code of an event
+    pub fn run_event(&mut self, event: &Event) -> Result<(), InterpreterError> {
+        self.run(&event.code)
+    }
+
+    /// Runs the given blocks
+    pub fn run(&mut self, blocks: &Blocks) -> Result<(), InterpreterError> {
+        self.execute(blocks).map(|_| ())
+    }
+
+    /// Calls a moreblock of the screen with the given arguments
+    pub fn call_more_block(
+        &mut self,
+        name: &str,
+        args: Vec<Value>,
+    ) -> Result<(), InterpreterError> {
+        let more_blocks = self.more_blocks;
+        let more_block =
+            more_blocks
+                .get(name)
+                .ok_or_else(|| InterpreterError::UnknownMoreBlock {
+                    name: name.to_string(),
+                })?;
+
+        if self.frames.len() >= MAX_CALL_DEPTH {
+            return Err(InterpreterError::CallDepthExceeded {
+                max: MAX_CALL_DEPTH,
+            });
+        }
+
+        let frame = more_block
+            .spec
+            .get_args()
+            .into_iter()
+            .zip(args)
+            .filter_map(|(arg, value)| arg_name(arg).map(|name| (name.to_string(), value)))
+            .collect();
+
+        self.frames.push(frame);
+        let result = self.execute(&more_block.code);
+        self.frames.pop();
+
+        result.map(|_| ())
+    }
+
+    /// Evaluates a block, returns the value it returns (if any)
+    pub fn evaluate(&mut self, block: &Block) -> Result<Option<Value>, InterpreterError> {
+        self.step()?;
+
+        let op_code = block.op_code.as_str();
+        let value = match op_code {
+            // operators
+            "true" => Value::Boolean(true),
+            "false" => Value::Boolean(false),
+            "&&" => Value::Boolean(self.boolean(block, 0)? && self.boolean(block, 1)?),
+            "||" => Value::Boolean(self.boolean(block, 0)? || self.boolean(block, 1)?),
+            "not" => Value::Boolean(!self.boolean(block, 0)?),
+            "<" => Value::Boolean(self.number(block, 0)? < self.number(block, 1)?),
+            "=" => Value::Boolean(self.number(block, 0)? == self.number(block, 1)?),
+            ">" => Value::Boolean(self.number(block, 0)? > self.number(block, 1)?),
+            "+" => Value::Number(self.number(block, 0)? + self.number(block, 1)?),
+            "-" => Value::Number(self.number(block, 0)? - self.number(block, 1)?),
+            "*" => Value::Number(self.number(block, 0)? * self.number(block, 1)?),
+            "/" => Value::Number(self.number(block, 0)? / self.number(block, 1)?),
+            "%" => Value::Number(self.number(block, 0)? % self.number(block, 1)?),
+
+            // math
+            "mathPi" => Value::Number(std::f64::consts::PI),
+            "mathE" => Value::Number(std::f64::consts::E),
+            "mathPow" => Value::Number(self.number(block, 0)?.powf(self.number(block, 1)?)),
+            "mathMin" => Value::Number(self.number(block, 0)?.min(self.number(block, 1)?)),
+            "mathMax" => Value::Number(self.number(block, 0)?.max(self.number(block, 1)?)),
+            "mathSqrt" => Value::Number(self.number(block, 0)?.sqrt()),
+            "mathAbs" => Value::Number(self.number(block, 0)?.abs()),
+            // java's Math.round rounds half up
+            "mathRound" => Value::Number((self.number(block, 0)? + 0.5).floor()),
+            "mathCeil" => Value::Number(self.number(block, 0)?.ceil()),
+            "mathFloor" => Value::Number(self.number(block, 0)?.floor()),
+            "mathSin" => Value::Number(self.number(block, 0)?.sin()),
+            "mathCos" => Value::Number(self.number(block, 0)?.cos()),
+            "mathTan" => Value::Number(self.number(block, 0)?.tan()),
+            "mathAsin" => Value::Number(self.number(block, 0)?.asin()),
+            "mathAcos" => Value::Number(self.number(block, 0)?.acos()),
+            "mathAtan" => Value::Number(self.number(block, 0)?.atan()),
+            "mathExp" => Value::Number(self.number(block, 0)?.exp()),
+            "mathLog" => Value::Number(self.number(block, 0)?.ln()),
+            "mathLog10" => Value::Number(self.number(block, 0)?.log10()),
+            "mathToRadian" => Value::Number(self.number(block, 0)?.to_radians()),
+            "mathToDegree" => Value::Number(self.number(block, 0)?.to_degrees()),
+
+            // strings
+            "stringLength" => Value::Number(self.string(block, 0)?.chars().count() as f64),
+            "stringJoin" => Value::String(self.string(block, 0)? + &self.string(block, 1)?),
+            "stringIndex" => {
+                let needle = self.string(block, 0)?;
+                Value::Number(char_index(&self.string(block, 1)?, &needle, false))
+            }
+            "stringLastIndex" => {
+                let needle = self.string(block, 0)?;
+                Value::Number(char_index(&self.string(block, 1)?, &needle, true))
+            }
+            "stringSub" => {
+                let string = self.string(block, 0)?;
+                let (start, end) = (self.index(block, 1)?, self.index(block, 2)?);
+                let length = string.chars().count();
+
+                if start < 0 || end < start || end as usize > length {
+                    return Err(InterpreterError::IndexOutOfBounds {
+                        op_code: op_code.to_string(),
+                        index: if start < 0 { start } else { end },
+                        length,
+                    });
+                }
+
+                Value::String(
+                    string
+                        .chars()
+                        .skip(start as usize)
+                        .take((end - start) as usize)
+                        .collect(),
+                )
+            }
+            "stringEquals" => Value::Boolean(self.string(block, 0)? == self.string(block, 1)?),
+            "stringContains" => {
+                Value::Boolean(self.string(block, 0)?.contains(&self.string(block, 1)?))
+            }
+            "stringReplace" => {
+                let string = self.string(block, 0)?;
+                Value::String(string.replace(&self.string(block, 1)?, &self.string(block, 2)?))
+            }
+            "toNumber" => {
+                let string = self.string(block, 0)?;
+                Value::Number(
+                    string
+                        .trim()
+                        .parse()
+                        .map_err(|_| InterpreterError::InvalidNumber { value: string })?,
+                )
+            }
+            "trim" => Value::String(self.string(block, 0)?.trim().to_string()),
+            "toUpperCase" => Value::String(self.string(block, 0)?.to_uppercase()),
+            "toLowerCase" => Value::String(self.string(block, 0)?.to_lowercase()),
+            "toString" => Value::String((self.number(block, 0)? as i64).to_string()),
+            "toStringWithDecimal" => Value::String(format_double(self.number(block, 0)?)),
+
+            // variables
+            "getVar" => {
+                let name = block_text(block);
+                self.env
+                    .variables
+                    .get(&name)
+                    .cloned()
+                    .ok_or(InterpreterError::UnknownVariable { name })?
+            }
+            "setVarBoolean" => {
+                let value = Value::Boolean(self.boolean(block, 1)?);
+                return self.set_variable(block, value);
+            }
+            "setVarInt" => {
+                let value = Value::Number(self.number(block, 1)?);
+                return self.set_variable(block, value);
+            }
+            "setVarString" => {
+                let value = Value::String(self.string(block, 1)?);
+                return self.set_variable(block, value);
+            }
+            "increaseInt" | "decreaseInt" => {
+                let name = self.string(block, 0)?;
+                let value = match self.variable(&name)? {
+                    Value::Number(value) => value,
+                    other => {
+                        return Err(InterpreterError::MismatchedVariable {
+                            name,
+                            expected: "number",
+                            found: other.type_name(),
+                        })
+                    }
+                };
+
+                *value += if op_code == "increaseInt" { 1.0 } else { -1.0 };
+                return Ok(None);
+            }
+
+            // maps
+            "mapCreateNew" | "mapClear" => {
+                self.map(block, 0)?.clear();
+                return Ok(None);
+            }
+            "mapPut" => {
+                let (key, value) = (self.string(block, 1)?, self.string(block, 2)?);
+                self.map(block, 0)?.insert(key, Value::String(value));
+                return Ok(None);
+            }
+            "mapGet" => {
+                let key = self.string(block, 1)?;
+                Value::String(
+                    self.map(block, 0)?
+                        .get(&key)
+                        .map(|value| value.to_string())
+                        .unwrap_or_else(|| "null".to_string()),
+                )
+            }
+            "mapContainKey" => {
+                let key = self.string(block, 1)?;
+                Value::Boolean(self.map(block, 0)?.contains_key(&key))
+            }
+            "mapRemoveKey" => {
+                let key = self.string(block, 1)?;
+                self.map(block, 0)?.remove(&key);
+                return Ok(None);
+            }
+            "mapSize" => Value::Number(self.map(block, 0)?.len() as f64),
+            "mapIsEmpty" => Value::Boolean(self.map(block, 0)?.is_empty()),
+            "mapGetAllKeys" => {
+                let keys = self
+                    .map(block, 0)?
+                    .keys()
+                    .map(|key| Value::String(key.to_owned()))
+                    .collect::<Vec<_>>();
+
+                *self.list(block, 1)? = keys;
+                return Ok(None);
+            }
+
+            // lists
+            "addListInt" => {
+                let value = Value::Number(self.number(block, 0)?);
+                self.list(block, 1)?.push(value);
+                return Ok(None);
+            }
+            "addListStr" => {
+                let value = Value::String(self.string(block, 0)?);
+                self.list(block, 1)?.push(value);
+                return Ok(None);
+            }
+            "addListMap" => {
+                let mut map = LinkedHashMap::new();
+                map.insert(
+                    self.string(block, 0)?,
+                    Value::String(self.string(block, 1)?),
+                );
+                self.list(block, 2)?.push(Value::Map(map));
+                return Ok(None);
+            }
+            "addMapToList" => {
+                let map = Value::Map(self.map(block, 0)?.clone());
+                self.list(block, 1)?.push(map);
+                return Ok(None);
+            }
+            "insertListInt" | "insertListStr" => {
+                let value = if op_code == "insertListInt" {
+                    Value::Number(self.number(block, 0)?)
+                } else {
+                    Value::String(self.string(block, 0)?)
+                };
+
+                let index = self.index(block, 1)?;
+                let list = self.list(block, 2)?;
+                check_bounds(op_code, index, list.len() + 1)?;
+                list.insert(index as usize, value);
+                return Ok(None);
+            }
+            "insertMapToList" => {
+                let map = Value::Map(self.map(block, 0)?.clone());
+                let index = self.index(block, 1)?;
+                let list = self.list(block, 2)?;
+                check_bounds(op_code, index, list.len() + 1)?;
+                list.insert(index as usize, map);
+                return Ok(None);
+            }
+            "getAtListInt" | "getAtListStr" => {
+                let index = self.index(block, 0)?;
+                let list = self.list(block, 1)?;
+                check_bounds(op_code, index, list.len())?;
+                list[index as usize].clone()
+            }
+            "getAtListMap" => {
+                let index = self.index(block, 0)?;
+                let key = self.string(block, 1)?;
+                let list = self.list(block, 2)?;
+                check_bounds(op_code, index, list.len())?;
+
+                Value::String(match &list[index as usize] {
+                    Value::Map(map) => map
+                        .get(&key)
+                        .map(|value| value.to_string())
+                        .unwrap_or_else(|| "null".to_string()),
+                    _ => "null".to_string(),
+                })
+            }
+            "getMapInList" => {
+                let index = self.index(block, 0)?;
+                let list = self.list(block, 1)?;
+                check_bounds(op_code, index, list.len())?;
+
+                let map = match &list[index as usize] {
+                    Value::Map(map) => map.clone(),
+                    _ => LinkedHashMap::new(),
+                };
+
+                *self.map(block, 2)? = map;
+                return Ok(None);
+            }
+            "indexListInt" | "indexListStr" => {
+                let value = if op_code == "indexListInt" {
+                    Value::Number(self.number(block, 0)?)
+                } else {
+                    Value::String(self.string(block, 0)?)
+                };
+
+                let list = self.list(block, 1)?;
+                Value::Number(
+                    list.iter()
+                        .position(|item| *item == value)
+                        .map(|index| index as f64)
+                        .unwrap_or(-1.0),
+                )
+            }
+            "containListInt" | "containListStr" => {
+                let value = if op_code == "containListInt" {
+                    Value::Number(self.number(block, 1)?)
+                } else {
+                    Value::String(self.string(block, 1)?)
+                };
+
+                Value::Boolean(self.list(block, 0)?.contains(&value))
+            }
+            "deleteList" => {
+                let index = self.index(block, 0)?;
+                let list = self.list(block, 1)?;
+                check_bounds(op_code, index, list.len())?;
+                list.remove(index as usize);
+                return Ok(None);
+            }
+            "lengthList" => Value::Number(self.list(block, 0)?.len() as f64),
+            "clearList" => {
+                self.list(block, 0)?.clear();
+                return Ok(None);
+            }
+
+            // moreblocks
+            "definedFunc" => {
+                let name = block
+                    .content
+                    .items
+                    .iter()
+                    .find_map(|item| match item {
+                        SpecItem::Text(text) => Some(text.to_owned()),
+                        _ => None,
+                    })
+                    .unwrap_or_default();
+
+                let mut args = Vec::new();
+                for index in 0..block.content.get_args().len() {
+                    args.push(self.value(block, index)?);
+                }
+
+                self.call_more_block(&name, args)?;
+                return Ok(None);
+            }
+            "getArg" => {
+                let name = block_text(block);
+                self.frames
+                    .last()
+                    .and_then(|frame| frame.get(&name))
+                    .cloned()
+                    .ok_or(InterpreterError::UnknownMoreBlockArgument { name })?
+            }
+
+            // everything else goes to the host
+            _ => {
+                let mut args = Vec::new();
+                for index in 0..block.content.get_args().len() {
+                    args.push(self.value(block, index)?);
+                }
+
+                return self.host.execute(block, args);
+            }
+        };
+
+        Ok(Some(value))
+    }
+
+    fn execute(&mut self, blocks: &Blocks) -> Result<Flow, InterpreterError> {
+        for block in &blocks.0 {
+            let flow = match block.op_code.as_str() {
+                "if" => {
+                    self.step()?;
+                    if self.boolean(block, 0)? {
+                        self.execute_substack(&block.sub_stack1)?
+                    } else {
+                        Flow::Continue
+                    }
+                }
+                "ifElse" => {
+                    self.step()?;
+                    if self.boolean(block, 0)? {
+                        self.execute_substack(&block.sub_stack1)?
+                    } else {
+                        self.execute_substack(&block.sub_stack2)?
+                    }
+                }
+                "repeat" => {
+                    self.step()?;
+                    let times = self.number(block, 0)? as i64;
+
+                    for _ in 0..times {
+                        self.step()?;
+                        if let Flow::Break = self.execute_substack(&block.sub_stack1)? {
+                            break;
+                        }
+                    }
+
+                    Flow::Continue
+                }
+                "forever" => {
+                    self.step()?;
+
+                    loop {
+                        self.step()?;
+                        if let Flow::Break = self.execute_substack(&block.sub_stack1)? {
+                            break;
+                        }
+                    }
+
+                    Flow::Continue
+                }
+                "break" => {
+                    self.step()?;
+                    Flow::Break
+                }
+                _ => {
+                    self.evaluate(block)?;
+                    Flow::Continue
+                }
+            };
+
+            if let Flow::Break = flow {
+                return Ok(Flow::Break);
+            }
+        }
+
+        Ok(Flow::Continue)
+    }
+
+    fn execute_substack(&mut self, substack: &Option<Blocks>) -> Result<Flow, InterpreterError> {
+        match substack {
+            Some(blocks) => self.execute(blocks),
+            None => Ok(Flow::Continue),
+        }
+    }
+
+    fn step(&mut self) -> Result<(), InterpreterError> {
+        self.steps += 1;
+
+        if self.steps > self.step_budget {
+            Err(InterpreterError::StepBudgetExceeded {
+                budget: self.step_budget,
+            })
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Evaluates an argument of a block
+    fn value(&mut self, block: &Block, index: usize) -> Result<Value, InterpreterError> {
+        let args = block.content.get_args();
+        let missing = || InterpreterError::MissingArgument {
+            op_code: block.op_code.to_owned(),
+            index,
+        };
+
+        let arg = *args.get(index).ok_or_else(missing)?;
+        if let Some(arg_block) = arg.block() {
+            return self
+                .evaluate(arg_block)?
+                .ok_or_else(|| InterpreterError::NoValue {
+                    op_code: arg_block.op_code.to_owned(),
+                });
+        }
+
+        Ok(match arg {
+            Argument::String { value, .. } => match value {
+                ArgValue::Value(value) => Value::String(value.to_owned()),
+                ArgValue::Empty => Value::String(String::new()),
+                _ => return Err(missing()),
+            },
+            Argument::Number { value, .. } => match value {
+                ArgValue::Value(value) => Value::Number(*value),
+                ArgValue::Empty => Value::Number(0.0),
+                _ => return Err(missing()),
+            },
+            Argument::Boolean { value, .. } => match value {
+                ArgValue::Value(value) => Value::Boolean(*value),
+                ArgValue::Empty => Value::Boolean(false),
+                _ => return Err(missing()),
+            },
+            Argument::Menu { value, .. } => match value {
+                ArgValue::Value(value) => Value::String(value.to_owned()),
+                _ => return Err(missing()),
+            },
+        })
+    }
+
+    fn mismatched(
+        block: &Block,
+        index: usize,
+        expected: &'static str,
+        found: &Value,
+    ) -> InterpreterError {
+        InterpreterError::MismatchedType {
+            op_code: block.op_code.to_owned(),
+            index,
+            expected,
+            found: found.type_name(),
+        }
+    }
+
+    fn boolean(&mut self, block: &Block, index: usize) -> Result<bool, InterpreterError> {
+        match self.value(block, index)? {
+            Value::Boolean(value) => Ok(value),
+            other => Err(Self::mismatched(block, index, "boolean", &other)),
+        }
+    }
+
+    fn number(&mut self, block: &Block, index: usize) -> Result<f64, InterpreterError> {
+        match self.value(block, index)? {
+            Value::Number(value) => Ok(value),
+            other => Err(Self::mismatched(block, index, "number", &other)),
+        }
+    }
+
+    fn string(&mut self, block: &Block, index: usize) -> Result<String, InterpreterError> {
+        match self.value(block, index)? {
+            Value::String(value) => Ok(value),
+            other => Err(Self::mismatched(block, index, "string", &other)),
+        }
+    }
+
+    /// Evaluates a number argument as an index, the same way java casts a double into an int
+    fn index(&mut self, block: &Block, index: usize) -> Result<i64, InterpreterError> {
+        Ok(self.number(block, index)? as i64)
+    }
+
+    fn variable(&mut self, name: &str) -> Result<&mut Value, InterpreterError> {
+        self.env
+            .variables
+            .get_mut(name)
+            .ok_or_else(|| InterpreterError::UnknownVariable {
+                name: name.to_string(),
+            })
+    }
+
+    /// Sets the variable named by the first argument of a block to the given value
+    fn set_variable(
+        &mut self,
+        block: &Block,
+        value: Value,
+    ) -> Result<Option<Value>, InterpreterError> {
+        let name = self.string(block, 0)?;
+        *self.variable(&name)? = value;
+        Ok(None)
+    }
+
+    /// Retrieves the map variable named by an argument of a block
+    fn map(
+        &mut self,
+        block: &Block,
+        index: usize,
+    ) -> Result<&mut LinkedHashMap<String, Value>, InterpreterError> {
+        let name = self.string(block, index)?;
+        match self.variable(&name)? {
+            Value::Map(map) => Ok(map),
+            other => Err(InterpreterError::MismatchedVariable {
+                expected: "map",
+                found: other.type_name(),
+                name,
+            }),
+        }
+    }
+
+    /// Retrieves the list named by an argument of a block
+    fn list(&mut self, block: &Block, index: usize) -> Result<&mut Vec<Value>, InterpreterError> {
+        let name = self.string(block, index)?;
+        self.env
+            .lists
+            .get_mut(&name)
+            .ok_or(InterpreterError::UnknownList { name })
+    }
+}
+
+/// Retrieves the texts of a block, used by blocks like `getVar` whose spec is the variable name
+pub(crate) fn block_text(block: &Block) -> String {
+    block
+        .content
+        .items
+        .iter()
+        .filter_map(|item| match item {
+            SpecItem::Text(text) => Some(text.as_str()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Retrieves the name of a moreblock parameter, menu parameters (like `%m.view.target`) have
+/// their names prefixed with the menu type, which gets stripped
+fn arg_name(arg: &Argument) -> Option<&str> {
+    match arg {
+        Argument::String { name, .. }
+        | Argument::Number { name, .. }
+        | Argument::Boolean { name, .. } => name.as_deref(),
+        Argument::Menu { name, .. } => name.rsplit('.').next(),
+    }
+}
+
+/// Finds the char index of `needle` in `haystack` like java's `indexOf`, gives -1 if not found
+fn char_index(haystack: &str, needle: &str, last: bool) -> f64 {
+    let byte_index = if last {
+        haystack.rfind(needle)
+    } else {
+        haystack.find(needle)
+    };
+
+    byte_index
+        .map(|index| haystack[..index].chars().count() as f64)
+        .unwrap_or(-1.0)
+}
+
+fn check_bounds(op_code: &str, index: i64, length: usize) -> Result<(), InterpreterError> {
+    if index < 0 || index as usize >= length {
+        Err(InterpreterError::IndexOutOfBounds {
+            op_code: op_code.to_string(),
+            index,
+            length,
+        })
+    } else {
+        Ok(())
+    }
+}
+
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum InterpreterError {
+    #[error("block `{op_code}` is not supported")]
+    UnsupportedBlock { op_code: String },
+
+    #[error("exceeded the step budget of {budget} steps")]
+    StepBudgetExceeded { budget: usize },
+
+    #[error("exceeded the maximum call depth of {max}")]
+    CallDepthExceeded { max: usize },
+
+    #[error("argument {index} of block `{op_code}` is missing")]
+    MissingArgument { op_code: String, index: usize },
+
+    #[error("block `{op_code}` doesn't return a value")]
+    NoValue { op_code: String },
+
+    #[error(
+        "argument {index} of block `{op_code}` is expected to be a {expected}, found a {found}"
+    )]
+    MismatchedType {
+        op_code: String,
+        index: usize,
+        expected: &'static str,
+        found: &'static str,
+    },
+
+    #[error("variable `{name}` is expected to be a {expected}, found a {found}")]
+    MismatchedVariable {
+        name: String,
+        expected: &'static str,
+        found: &'static str,
+    },
+
+    #[error("there is no variable named `{name}`")]
+    UnknownVariable { name: String },
+
+    #[error("there is no list named `{name}`")]
+    UnknownList { name: String },
+
+    #[error("there is no moreblock named `{name}`")]
+    UnknownMoreBlock { name: String },
+
+    #[error("there is no moreblock argument named `{name}`")]
+    UnknownMoreBlockArgument { name: String },
+
+    #[error("index {index} is out of bounds of length {length} on block `{op_code}`")]
+    IndexOutOfBounds {
+        op_code: String,
+        index: i64,
+        length: usize,
+    },
+
+    #[error("`{value}` is not a valid number")]
+    InvalidNumber { value: String },
+
+    /// An error raised by a [`Host`]
+    #[error("{0}")]
+    Host(String),
+}
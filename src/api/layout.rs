@@ -0,0 +1,350 @@
+//! Structural validation of layouts. Sketchware's editor crashes on layouts that are broken in
+//! ways the format itself allows, [`validate_raw`] checks raw layouts ([`Layout`]) and
+//! [`validate`] checks trees of [`View`]s for those problems. [`normalize_raw`] and
+//! [`normalize`] repair what can be repaired.
+
+use crate::api::id::IdAllocator;
+use crate::api::view::{flatten_views, parse_raw_layout, walk, View, ViewType};
+use crate::parser::view::models::layout::{Orientation, Size};
+use crate::parser::view::models::AndroidView;
+use crate::parser::view::Layout;
+use crate::LinkedHashMap;
+use std::collections::{HashMap, HashSet};
+use thiserror::Error;
+
+/// The type id of FABs, they're stored separately from the layout
+const FAB_TYPE: u8 = 16;
+
+/// A structural problem of a layout, views are identified by their ids
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum LayoutProblem {
+    #[error("`{id}` doesn't have a parent")]
+    NoParent { id: String },
+
+    #[error("the parent of `{id}`, `{parent}`, doesn't exist")]
+    MissingParent { id: String, parent: String },
+
+    #[error("`{id}` comes before its parent `{parent}`")]
+    ParentAfterChild { id: String, parent: String },
+
+    #[error("`{id}` is inside of `{parent}`, which is a {parent_type} that can't have children")]
+    NonContainerParent {
+        id: String,
+        parent: String,
+        parent_type: String,
+    },
+
+    #[error("the scroll view `{id}` has {count} children, it can only have one")]
+    CrowdedScrollView { id: String, count: usize },
+
+    #[error("the id `{id}` is used by {count} views")]
+    DuplicateId { id: String, count: usize },
+
+    #[error("the children of `{parent}` have the indexes {indexes:?}, not counting up from 0")]
+    NonContiguousIndex { parent: String, indexes: Vec<u32> },
+
+    #[error("the fab `{id}` is inside of the layout")]
+    FabInLayout { id: String },
+
+    #[error("`{id}` says its parent `{parent}` is of type {found}, but it's of type {expected}")]
+    ParentTypeMismatch {
+        id: String,
+        parent: String,
+        expected: u8,
+        found: i8,
+    },
+}
+
+/// Checks a raw layout for structural problems, in the order of its views
+pub fn validate_raw(layout: &Layout) -> Vec<LayoutProblem> {
+    let views = &layout.0;
+    let mut problems = duplicate_ids(views.iter().map(|view| view.id.as_str()));
+
+    // where each view is first defined
+    let mut positions: HashMap<&str, usize> = HashMap::new();
+    for (position, view) in views.iter().enumerate() {
+        positions.entry(view.id.as_str()).or_insert(position);
+    }
+
+    // indexes of the children of each parent, in order
+    let mut indexes: LinkedHashMap<&str, Vec<u32>> = LinkedHashMap::new();
+
+    for (position, view) in views.iter().enumerate() {
+        let id = view.id.to_owned();
+
+        if view.r#type == FAB_TYPE {
+            problems.push(LayoutProblem::FabInLayout { id: id.to_owned() });
+        }
+
+        let parent = match view.parent.as_deref() {
+            Some(parent) => parent,
+            None => {
+                problems.push(LayoutProblem::NoParent { id });
+                continue;
+            }
+        };
+
+        indexes
+            .entry(parent)
+            .or_insert_with(Vec::new)
+            .push(view.index);
+
+        // the root is a hardcoded vertical LinearLayout
+        let parent_type = if parent == "root" {
+            0
+        } else {
+            let parent_position = match positions.get(parent) {
+                Some(parent_position) => *parent_position,
+                None => {
+                    problems.push(LayoutProblem::MissingParent {
+                        id,
+                        parent: parent.to_string(),
+                    });
+                    continue;
+                }
+            };
+
+            if parent_position > position {
+                problems.push(LayoutProblem::ParentAfterChild {
+                    id: id.to_owned(),
+                    parent: parent.to_string(),
+                });
+            }
+
+            let parent_view = &views[parent_position];
+            if let Some(parent_type) = non_container(&ViewType::from_view(parent_view)) {
+                problems.push(LayoutProblem::NonContainerParent {
+                    id: id.to_owned(),
+                    parent: parent.to_string(),
+                    parent_type,
+                });
+            }
+
+            parent_view.r#type
+        };
+
+        if view.parent_type != parent_type as i8 {
+            problems.push(LayoutProblem::ParentTypeMismatch {
+                id,
+                parent: parent.to_string(),
+                expected: parent_type,
+                found: view.parent_type,
+            });
+        }
+    }
+
+    for (parent, indexes) in indexes {
+        let parent_view = positions.get(parent).map(|position| &views[*position]);
+        if indexes.len() > 1 && is_scroll_view(parent_view) {
+            problems.push(LayoutProblem::CrowdedScrollView {
+                id: parent.to_string(),
+                count: indexes.len(),
+            });
+        }
+
+        if !indexes.iter().copied().eq(0..indexes.len() as u32) {
+            problems.push(LayoutProblem::NonContiguousIndex {
+                parent: parent.to_string(),
+                indexes,
+            });
+        }
+    }
+
+    problems
+}
+
+/// Checks a tree of views for structural problems. The indexes and parent types of views are
+/// derived from the tree itself when it's turned into a raw layout, so they aren't checked.
+pub fn validate(views: &[View]) -> Vec<LayoutProblem> {
+    let all = walk(views);
+
+    let mut problems = duplicate_ids(all.iter().map(|view| view.id.as_str()));
+
+    for view in all {
+        if matches!(view.view, Ok(ViewType::Fab { .. })) {
+            problems.push(LayoutProblem::FabInLayout {
+                id: view.id.to_owned(),
+            });
+        }
+
+        if view.children.is_empty() {
+            continue;
+        }
+
+        if let Some(parent_type) = non_container(&view.view) {
+            problems.extend(
+                view.children
+                    .iter()
+                    .map(|child| LayoutProblem::NonContainerParent {
+                        id: child.id.to_owned(),
+                        parent: view.id.to_owned(),
+                        parent_type: parent_type.to_owned(),
+                    }),
+            );
+        }
+
+        if matches!(view.view, Ok(ViewType::ScrollView { .. })) && view.children.len() > 1 {
+            problems.push(LayoutProblem::CrowdedScrollView {
+                id: view.id.to_owned(),
+                count: view.children.len(),
+            });
+        }
+    }
+
+    problems
+}
+
+/// Repairs a tree of views, new ids are taken from `allocator`:
+///  - FABs are removed from the tree
+///  - children of views that can't have children are moved right after them
+///  - children of scroll views that have more than one are wrapped in a new LinearLayout
+///  - views whose ids are used by a view that comes before them are given new ids
+///
+/// Returns the problems that are left, see [`validate`].
+pub fn normalize(views: &mut Vec<View>, allocator: &mut IdAllocator) -> Vec<LayoutProblem> {
+    repair(views, allocator);
+
+    let mut seen = HashSet::new();
+    rename_duplicates(views, &mut seen, allocator);
+
+    validate(views)
+}
+
+/// Repairs a raw layout: views without an existing parent are moved to the root, views are
+/// ordered so that parents come before their children, then the tree is repaired with
+/// [`normalize`], which also corrects the indexes and the parent types of every view. Views
+/// are written in the same order swrs writes layouts.
+///
+/// Returns the problems that are left, see [`validate_raw`].
+pub fn normalize_raw(layout: &mut Layout) -> Vec<LayoutProblem> {
+    let mut allocator = IdAllocator::from_ids(layout.0.iter().map(|view| view.id.to_owned()));
+
+    let mut remaining: Vec<AndroidView> = std::mem::take(&mut layout.0)
+        .into_iter()
+        .filter(|view| view.r#type != FAB_TYPE)
+        .collect();
+
+    let ids: HashSet<String> = remaining.iter().map(|view| view.id.to_owned()).collect();
+    for view in &mut remaining {
+        if !matches!(&view.parent, Some(parent) if parent == "root" || ids.contains(parent)) {
+            view.parent = Some("root".to_string());
+        }
+    }
+
+    // put parents before their children, views left in a cycle of parents go to the root
+    let mut ordered: Vec<AndroidView> = Vec::new();
+    let mut defined: HashSet<String> = HashSet::new();
+
+    while !remaining.is_empty() {
+        let (ready, rest): (Vec<_>, Vec<_>) = remaining.into_iter().partition(|view| {
+            matches!(&view.parent, Some(parent) if parent == "root" || defined.contains(parent))
+        });
+
+        remaining = rest;
+
+        if ready.is_empty() {
+            remaining[0].parent = Some("root".to_string());
+            continue;
+        }
+
+        defined.extend(ready.iter().map(|view| view.id.to_owned()));
+        ordered.extend(ready);
+    }
+
+    let mut views = parse_raw_layout(Layout(ordered)).expect("every parent is defined");
+    normalize(&mut views, &mut allocator);
+
+    *layout = Layout(flatten_views(views, None, None));
+    validate_raw(layout)
+}
+
+/// Reports every id that's used more than once, in the order they first appear
+fn duplicate_ids<'a, I: Iterator<Item = &'a str>>(ids: I) -> Vec<LayoutProblem> {
+    let mut counts: LinkedHashMap<&str, usize> = LinkedHashMap::new();
+    for id in ids {
+        *counts.entry(id).or_insert(0) += 1;
+    }
+
+    counts
+        .into_iter()
+        .filter(|(_, count)| *count > 1)
+        .map(|(id, count)| LayoutProblem::DuplicateId {
+            id: id.to_string(),
+            count,
+        })
+        .collect()
+}
+
+/// Retrieves the name of a view type that can't have children, `None` if it can
+fn non_container<E>(view_type: &Result<ViewType, E>) -> Option<String> {
+    match view_type {
+        Ok(view_type) if view_type.accepts_children() => None,
+        Ok(view_type) => Some(view_type.name().to_string()),
+        Err(_) => Some("view of an unknown type".to_string()),
+    }
+}
+
+fn is_scroll_view(view: Option<&AndroidView>) -> bool {
+    matches!(
+        view.map(ViewType::from_view),
+        Some(Ok(ViewType::ScrollView { .. }))
+    )
+}
+
+fn repair(views: &mut Vec<View>, allocator: &mut IdAllocator) {
+    let mut index = 0;
+    while index < views.len() {
+        if matches!(views[index].view, Ok(ViewType::Fab { .. })) {
+            views.remove(index);
+            continue;
+        }
+
+        let view = &mut views[index];
+
+        // hoist the children of views that can't have them, they're then checked as siblings
+        if !view.children.is_empty() && non_container(&view.view).is_some() {
+            let children = std::mem::take(&mut view.children);
+            views.splice(index + 1..index + 1, children);
+
+            index += 1;
+            continue;
+        }
+
+        if let Ok(ViewType::ScrollView { orientation, .. }) = &view.view {
+            if view.children.len() > 1 {
+                let orientation = match orientation {
+                    Orientation::Horizontal => Orientation::Horizontal,
+                    _ => Orientation::Vertical,
+                };
+
+                let linear = ViewType::LinearLayout {
+                    orientation,
+                    gravity: Default::default(),
+                };
+
+                let mut wrapper = View::new(&allocator.view_id(&linear), linear);
+                wrapper.width = Size::MatchParent;
+                wrapper.children = std::mem::take(&mut view.children);
+                view.children.push(wrapper);
+            }
+        }
+
+        repair(&mut view.children, allocator);
+        index += 1;
+    }
+}
+
+fn rename_duplicates(views: &mut [View], seen: &mut HashSet<String>, allocator: &mut IdAllocator) {
+    for view in views {
+        if !seen.insert(view.id.to_owned()) {
+            view.id = match &view.view {
+                Ok(view_type) => allocator.view_id(view_type),
+                Err(_) => allocator.next(view.id.trim_end_matches(|ch: char| ch.is_ascii_digit())),
+            };
+
+            seen.insert(view.id.to_owned());
+        }
+
+        rename_duplicates(&mut view.children, seen, allocator);
+    }
+}
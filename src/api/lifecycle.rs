@@ -0,0 +1,336 @@
+//! Adding, removing, renaming and duplicating screens of a project. Screens are identified by
+//! their layout names (like `main`), their java names (like `MainActivity`) are derived from it
+//! the same way sketchware does.
+
+use crate::api::block::{ArgValue, Argument, BlockPath, Blocks};
+use crate::api::screen::{Screen, DRAWER_PREFIX};
+use crate::api::SketchwareProject;
+use thiserror::Error;
+
+/// The screen every project starts with, it can't be removed nor renamed
+pub const MAIN_SCREEN: &str = "main";
+
+const JAVA_KEYWORDS: &[&str] = &[
+    "abstract",
+    "assert",
+    "boolean",
+    "break",
+    "byte",
+    "case",
+    "catch",
+    "char",
+    "class",
+    "const",
+    "continue",
+    "default",
+    "do",
+    "double",
+    "else",
+    "enum",
+    "extends",
+    "false",
+    "final",
+    "finally",
+    "float",
+    "for",
+    "goto",
+    "if",
+    "implements",
+    "import",
+    "instanceof",
+    "int",
+    "interface",
+    "long",
+    "native",
+    "new",
+    "null",
+    "package",
+    "private",
+    "protected",
+    "public",
+    "return",
+    "short",
+    "static",
+    "strictfp",
+    "super",
+    "switch",
+    "synchronized",
+    "this",
+    "throw",
+    "throws",
+    "transient",
+    "true",
+    "try",
+    "void",
+    "volatile",
+    "while",
+];
+
+/// Derives the java name of a screen from its layout name, like `main` into `MainActivity` and
+/// `screen_display` into `ScreenDisplayActivity`
+pub fn java_name(layout_name: &str) -> String {
+    super::view_name_to_logic(layout_name)
+}
+
+/// Checks whether a layout name follows the rules of sketchware: it must start with a lowercase
+/// letter, followed by lowercase letters, digits or underscores, and must not be a java keyword
+pub fn validate_screen_name(name: &str) -> Result<(), InvalidScreenName> {
+    let mut chars = name.chars();
+
+    match chars.next() {
+        None => return Err(InvalidScreenName::Empty),
+        Some(first) if !first.is_ascii_lowercase() => {
+            return Err(InvalidScreenName::InvalidStart(first))
+        }
+        _ => {}
+    }
+
+    if let Some(invalid) =
+        chars.find(|ch| !(ch.is_ascii_lowercase() || ch.is_ascii_digit() || *ch == '_'))
+    {
+        return Err(InvalidScreenName::InvalidCharacter(invalid));
+    }
+
+    if JAVA_KEYWORDS.contains(&name) {
+        return Err(InvalidScreenName::Keyword);
+    }
+
+    Ok(())
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum InvalidScreenName {
+    #[error("it's empty")]
+    Empty,
+
+    #[error("it starts with `{0}`, it must start with a lowercase letter")]
+    InvalidStart(char),
+
+    #[error("it contains `{0}`, only lowercase letters, digits and underscores are allowed")]
+    InvalidCharacter(char),
+
+    #[error("it's a java keyword")]
+    Keyword,
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ScreenLifecycleError {
+    #[error("`{name}` isn't a valid screen name, {reason}")]
+    InvalidName {
+        name: String,
+        reason: InvalidScreenName,
+    },
+
+    #[error("a screen or a custom view named `{name}` already exists")]
+    AlreadyExists { name: String },
+
+    #[error("the java name `{java_name}` is already used by the screen `{screen}`")]
+    JavaNameTaken { java_name: String, screen: String },
+
+    #[error("couldn't find the screen `{name}`")]
+    NotFound { name: String },
+
+    #[error("the main screen can't be removed nor renamed")]
+    MainScreen,
+}
+
+impl SketchwareProject {
+    /// Retrieves a screen using its layout name
+    pub fn get_screen(&self, name: &str) -> Option<&Screen> {
+        self.screens
+            .iter()
+            .find(|screen| screen.layout_name == name)
+    }
+
+    /// Retrieves a screen using its layout name as a mutable reference
+    pub fn get_screen_mut(&mut self, name: &str) -> Option<&mut Screen> {
+        self.screens
+            .iter_mut()
+            .find(|screen| screen.layout_name == name)
+    }
+
+    /// Adds an empty screen with the given layout name, its java name is derived from it
+    pub fn add_screen(&mut self, name: &str) -> Result<&mut Screen, ScreenLifecycleError> {
+        let java_name = self.check_new_name(name, None)?;
+        self.remove_stray_drawer(name);
+
+        self.screens
+            .push(Screen::new_empty(name.to_string(), java_name));
+        Ok(self.screens.last_mut().unwrap())
+    }
+
+    /// Removes a screen along with its fab and drawer. `intentSetScreen` blocks of the other
+    /// screens that target it are reset to not have any screen set.
+    pub fn remove_screen(&mut self, name: &str) -> Result<Screen, ScreenLifecycleError> {
+        if name == MAIN_SCREEN {
+            return Err(ScreenLifecycleError::MainScreen);
+        }
+
+        let index = self.index_of(name)?;
+        let screen = self.screens.remove(index);
+
+        self.remove_stray_drawer(name);
+        self.retarget_intents(&screen.java_name, "");
+
+        Ok(screen)
+    }
+
+    /// Renames a screen and its java name, along with the targets of `intentSetScreen` blocks
+    /// that go to it. The fab and the drawer of the screen follow its new name.
+    pub fn rename_screen(
+        &mut self,
+        name: &str,
+        new_name: &str,
+    ) -> Result<(), ScreenLifecycleError> {
+        if name == MAIN_SCREEN {
+            return Err(ScreenLifecycleError::MainScreen);
+        }
+
+        let index = self.index_of(name)?;
+        let java_name = self.check_new_name(new_name, Some(index))?;
+        self.remove_stray_drawer(name);
+        self.remove_stray_drawer(new_name);
+
+        let screen = &mut self.screens[index];
+        let old_java_name = std::mem::replace(&mut screen.java_name, java_name.to_owned());
+        screen.layout_name = new_name.to_string();
+
+        self.retarget_intents(&old_java_name, &java_name);
+        Ok(())
+    }
+
+    /// Creates a copy of a screen with a new name, placed right after the original. Its blocks
+    /// are copied as is, so its intents still go to the same screens as the original's.
+    pub fn duplicate_screen(
+        &mut self,
+        name: &str,
+        new_name: &str,
+    ) -> Result<&mut Screen, ScreenLifecycleError> {
+        let index = self.index_of(name)?;
+        let java_name = self.check_new_name(new_name, None)?;
+        self.remove_stray_drawer(new_name);
+
+        let mut screen = self.screens[index].clone();
+        screen.layout_name = new_name.to_string();
+        screen.java_name = java_name;
+
+        self.screens.insert(index + 1, screen);
+        Ok(&mut self.screens[index + 1])
+    }
+
+    fn index_of(&self, name: &str) -> Result<usize, ScreenLifecycleError> {
+        self.screens
+            .iter()
+            .position(|screen| screen.layout_name == name)
+            .ok_or_else(|| ScreenLifecycleError::NotFound {
+                name: name.to_string(),
+            })
+    }
+
+    /// Validates a new layout name and returns its java name, `ignored` is the index of the
+    /// screen that's getting renamed
+    fn check_new_name(
+        &self,
+        name: &str,
+        ignored: Option<usize>,
+    ) -> Result<String, ScreenLifecycleError> {
+        validate_screen_name(name).map_err(|reason| ScreenLifecycleError::InvalidName {
+            name: name.to_string(),
+            reason,
+        })?;
+
+        let others = self
+            .screens
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| Some(*index) != ignored)
+            .map(|(_, screen)| screen);
+
+        let java_name = java_name(name);
+
+        for screen in others {
+            if screen.layout_name == name {
+                return Err(ScreenLifecycleError::AlreadyExists {
+                    name: name.to_string(),
+                });
+            }
+
+            if screen.java_name == java_name {
+                return Err(ScreenLifecycleError::JavaNameTaken {
+                    java_name,
+                    screen: screen.layout_name.to_owned(),
+                });
+            }
+        }
+
+        if self
+            .custom_views
+            .iter()
+            .any(|custom_view| custom_view.res_name == name)
+        {
+            return Err(ScreenLifecycleError::AlreadyExists {
+                name: name.to_string(),
+            });
+        }
+
+        Ok(java_name)
+    }
+
+    /// Removes the drawer layout of a screen named `name` that's left as a custom view, so it
+    /// doesn't get picked up by another screen with the same name
+    fn remove_stray_drawer(&mut self, name: &str) {
+        let drawer_name = format!("{}{}", DRAWER_PREFIX, name);
+        self.custom_views
+            .retain(|custom_view| custom_view.res_name != drawer_name);
+    }
+
+    /// Sets the activity of every `intentSetScreen` block that goes to `java_name` into
+    /// `new_java_name`
+    fn retarget_intents(&mut self, java_name: &str, new_java_name: &str) {
+        for screen in &mut self.screens {
+            let containers = screen.events.iter_mut().map(|event| &mut event.code).chain(
+                screen
+                    .more_blocks
+                    .values_mut()
+                    .map(|more_block| &mut more_block.code),
+            );
+
+            for blocks in containers {
+                retarget_blocks(blocks, java_name, new_java_name);
+            }
+        }
+    }
+}
+
+fn retarget_blocks(blocks: &mut Blocks, java_name: &str, new_java_name: &str) {
+    let mut paths: Vec<BlockPath> = Vec::new();
+
+    blocks.visit(|path, block| {
+        if block.op_code == "intentSetScreen"
+            && activity_arg(block.content.get_args()) == Some(java_name)
+        {
+            paths.push(path.to_owned());
+        }
+    });
+
+    for path in paths {
+        let arg = blocks
+            .get_mut(&path)
+            .and_then(|block| block.content.get_args_mut().into_iter().nth(1));
+
+        if let Some(Argument::Menu { value, .. }) = arg {
+            *value = ArgValue::Value(new_java_name.to_string());
+        }
+    }
+}
+
+/// Retrieves the activity an `intentSetScreen` block is set to
+fn activity_arg(args: Vec<&Argument>) -> Option<&str> {
+    match *args.get(1)? {
+        Argument::Menu {
+            value: ArgValue::Value(value),
+            ..
+        } => Some(value.trim_end_matches(".java")),
+        _ => None,
+    }
+}
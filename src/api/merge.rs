@@ -0,0 +1,1232 @@
+//! Three-way merging of projects. [`merge`] combines the changes two projects made on top of their
+//! common ancestor, changes that can't be combined are returned as [`Conflict`]s which can be
+//! resolved afterwards using [`Conflict::resolve`].
+//!
+//! Conflicting values are left as they are in "ours", except for views that are modified on one
+//! side but deleted on the other, which are kept. Views of drawers are identified by their ids
+//! prefixed with [`DRAWER_PREFIX`].
+
+use crate::api::component::ComponentKind;
+use crate::api::diff::{collect_views, ViewLocation};
+use crate::api::screen::{Drawer, Event, MoreBlock, Screen, DRAWER_PREFIX};
+use crate::api::view::{take_view, SetAttributeError, View};
+use crate::api::{Colors, CustomView, Libraries, Metadata, ResourceId, SketchwareProject};
+use crate::color::Color;
+use crate::parser::file::{KeyboardSetting, Orientation, Theme};
+use crate::parser::logic::list_variable::ListVariable;
+use crate::parser::logic::variable::{Variable, VariableType};
+use crate::parser::{ResourceFileWrapper, ResourceType};
+use crate::LinkedHashMap;
+use serde::{Deserialize, Serialize};
+use std::hash::Hash;
+use thiserror::Error;
+
+/// One of the two sides of a merge
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    Ours,
+    Theirs,
+}
+
+/// The result of [`merge`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct MergeResult {
+    /// The merged project, conflicting values are taken from "ours"
+    pub project: SketchwareProject,
+    pub conflicts: Vec<Conflict>,
+}
+
+impl MergeResult {
+    /// Checks whether the merge has no conflicts
+    pub fn is_clean(&self) -> bool {
+        self.conflicts.is_empty()
+    }
+
+    /// Resolves every conflict by picking the values of the given side
+    pub fn resolve_all(&mut self, side: Side) -> Result<(), ResolveError> {
+        for conflict in std::mem::take(&mut self.conflicts) {
+            conflict.resolve(&mut self.project, side)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Merges the changes `ours` and `theirs` made on top of `base`
+pub fn merge(
+    base: &SketchwareProject,
+    ours: &SketchwareProject,
+    theirs: &SketchwareProject,
+) -> MergeResult {
+    let mut conflicts = Vec::new();
+    let mut project = ours.clone();
+
+    for field in Metadata::FIELDS {
+        let (ours_value, theirs_value) = (
+            ours.metadata.get_field(field),
+            theirs.metadata.get_field(field),
+        );
+
+        match merge_value(
+            base.metadata.get_field(field).as_ref(),
+            ours_value.as_ref(),
+            theirs_value.as_ref(),
+        ) {
+            Merged::Value(Some(value)) => {
+                project.metadata.set_field(field, &value);
+            }
+            Merged::Value(None) => {}
+            Merged::Conflict => conflicts.push(Conflict::Metadata {
+                field,
+                ours: ours_value.unwrap_or_default(),
+                theirs: theirs_value.unwrap_or_default(),
+            }),
+        }
+    }
+
+    for field in Colors::FIELDS {
+        let color = |colors: &Colors| colors.get_field(field).expect("every field is listed");
+        let (ours_color, theirs_color) = (color(&ours.colors), color(&theirs.colors));
+
+        match merge_value(
+            Some(&color(&base.colors)),
+            Some(&ours_color),
+            Some(&theirs_color),
+        ) {
+            Merged::Value(Some(color)) => {
+                project.colors.set_field(field, color);
+            }
+            Merged::Value(None) => {}
+            Merged::Conflict => conflicts.push(Conflict::Color {
+                field,
+                ours: ours_color,
+                theirs: theirs_color,
+            }),
+        }
+    }
+
+    match merge_value(
+        Some(&base.libraries),
+        Some(&ours.libraries),
+        Some(&theirs.libraries),
+    ) {
+        Merged::Value(libraries) => {
+            project.libraries = libraries.expect("libraries exist on every side")
+        }
+        Merged::Conflict => conflicts.push(Conflict::Libraries {
+            ours: Box::new(ours.libraries.clone()),
+            theirs: Box::new(theirs.libraries.clone()),
+        }),
+    }
+
+    match merge_value(
+        base.custom_icon.as_ref(),
+        ours.custom_icon.as_ref(),
+        theirs.custom_icon.as_ref(),
+    ) {
+        Merged::Value(icon) => project.custom_icon = icon,
+        Merged::Conflict => conflicts.push(Conflict::CustomIcon {
+            ours: ours.custom_icon.clone(),
+            theirs: theirs.custom_icon.clone(),
+        }),
+    }
+
+    // custom views
+    let by_name = |project: &SketchwareProject| {
+        project
+            .custom_views
+            .iter()
+            .map(|custom_view| (custom_view.res_name.to_owned(), custom_view.clone()))
+            .collect::<LinkedHashMap<_, _>>()
+    };
+
+    project.custom_views = merge_map(
+        Some(&by_name(base)),
+        &by_name(ours),
+        &by_name(theirs),
+        |name, ours, theirs| {
+            conflicts.push(Conflict::CustomView {
+                name: name.to_owned(),
+                ours: ours.cloned().map(Box::new),
+                theirs: theirs.cloned().map(Box::new),
+            })
+        },
+    )
+    .into_iter()
+    .map(|(_, custom_view)| custom_view)
+    .collect();
+
+    // resources
+    macro_rules! resources {
+        ($($field:ident: $res_type:expr),*) => {$(
+            project.resources.$field = merge_map(
+                Some(&base.resources.$field),
+                &ours.resources.$field,
+                &theirs.resources.$field,
+                |id, ours, theirs| {
+                    conflicts.push(Conflict::Resource {
+                        res_type: $res_type,
+                        id: id.to_owned(),
+                        ours: ours.cloned(),
+                        theirs: theirs.cloned(),
+                    })
+                },
+            );
+        )*};
+    }
+
+    resources!(
+        images: ResourceType::Image,
+        sounds: ResourceType::Sound,
+        fonts: ResourceType::Font
+    );
+
+    // screens
+    let mut screens = Vec::new();
+    let find = |project: &'_ SketchwareProject, name: &str| -> Option<usize> {
+        project
+            .screens
+            .iter()
+            .position(|screen| screen.layout_name == name)
+    };
+
+    let names = ours
+        .screens
+        .iter()
+        .chain(theirs.screens.iter())
+        .map(|screen| screen.layout_name.as_str())
+        .fold(Vec::<&str>::new(), |mut names, name| {
+            if !names.contains(&name) {
+                names.push(name);
+            }
+            names
+        });
+
+    for name in names {
+        let base_screen = find(base, name).map(|index| &base.screens[index]);
+        let our_screen = find(ours, name).map(|index| &ours.screens[index]);
+        let their_screen = find(theirs, name).map(|index| &theirs.screens[index]);
+
+        match (our_screen, their_screen) {
+            (Some(our_screen), Some(their_screen)) => screens.push(merge_screen(
+                base_screen,
+                our_screen,
+                their_screen,
+                &mut conflicts,
+            )),
+            (our_screen, their_screen) => {
+                // the screen is either added or deleted on one side
+                match merge_value(base_screen, our_screen, their_screen) {
+                    Merged::Value(screen) => screens.extend(screen),
+                    Merged::Conflict => {
+                        conflicts.push(Conflict::Screen {
+                            screen: name.to_string(),
+                            ours: our_screen.cloned().map(Box::new),
+                            theirs: their_screen.cloned().map(Box::new),
+                        });
+
+                        screens.extend(our_screen.cloned());
+                    }
+                }
+            }
+        }
+    }
+
+    project.screens = screens;
+
+    MergeResult { project, conflicts }
+}
+
+/// Merges the changes `ours` and `theirs` made on top of `base` on a screen, `base` is None when
+/// the screen is added on both sides
+fn merge_screen(
+    base: Option<&Screen>,
+    ours: &Screen,
+    theirs: &Screen,
+    conflicts: &mut Vec<Conflict>,
+) -> Screen {
+    let mut screen = ours.clone();
+    let name = &ours.layout_name;
+
+    // options
+    let base_options = base.map(ScreenOption::all);
+    for (index, (our_option, their_option)) in ScreenOption::all(ours)
+        .into_iter()
+        .zip(ScreenOption::all(theirs))
+        .enumerate()
+    {
+        let base_option = base_options.as_ref().map(|options| &options[index]);
+
+        match merge_value(base_option, Some(&our_option), Some(&their_option)) {
+            Merged::Value(Some(option)) => option.apply(&mut screen),
+            Merged::Value(None) => {}
+            Merged::Conflict => conflicts.push(Conflict::ScreenOption {
+                screen: name.to_owned(),
+                ours: our_option,
+                theirs: their_option,
+            }),
+        }
+    }
+
+    // variables
+    let types = |variables: &LinkedHashMap<String, Variable>| {
+        variables
+            .iter()
+            .map(|(name, variable)| (name.to_owned(), variable.r#type))
+            .collect::<LinkedHashMap<_, _>>()
+    };
+
+    screen.variables = merge_map(
+        base.map(|base| types(&base.variables)).as_ref(),
+        &types(&ours.variables),
+        &types(&theirs.variables),
+        |variable, ours, theirs| {
+            conflicts.push(Conflict::Variable {
+                screen: name.to_owned(),
+                name: variable.to_owned(),
+                ours: ours.copied(),
+                theirs: theirs.copied(),
+            })
+        },
+    )
+    .into_iter()
+    .map(|(name, r#type)| (name.to_owned(), Variable { name, r#type }))
+    .collect();
+
+    let list_types = |lists: &LinkedHashMap<String, ListVariable>| {
+        lists
+            .iter()
+            .map(|(name, list)| (name.to_owned(), list.r#type))
+            .collect::<LinkedHashMap<_, _>>()
+    };
+
+    screen.list_variables = merge_map(
+        base.map(|base| list_types(&base.list_variables)).as_ref(),
+        &list_types(&ours.list_variables),
+        &list_types(&theirs.list_variables),
+        |list, ours, theirs| {
+            conflicts.push(Conflict::ListVariable {
+                screen: name.to_owned(),
+                name: list.to_owned(),
+                ours: ours.copied(),
+                theirs: theirs.copied(),
+            })
+        },
+    )
+    .into_iter()
+    .map(|(name, r#type)| (name.to_owned(), ListVariable { name, r#type }))
+    .collect();
+
+    // components
+    screen.components = merge_map(
+        base.map(|base| &base.components),
+        &ours.components,
+        &theirs.components,
+        |id, ours, theirs| {
+            conflicts.push(Conflict::Component {
+                screen: name.to_owned(),
+                id: id.to_owned(),
+                ours: ours.cloned(),
+                theirs: theirs.cloned(),
+            })
+        },
+    );
+
+    // moreblocks
+    screen.more_blocks = merge_map(
+        base.map(|base| &base.more_blocks),
+        &ours.more_blocks,
+        &theirs.more_blocks,
+        |more_block, ours, theirs| {
+            conflicts.push(Conflict::MoreBlock {
+                screen: name.to_owned(),
+                name: more_block.to_owned(),
+                ours: ours.cloned().map(Box::new),
+                theirs: theirs.cloned().map(Box::new),
+            })
+        },
+    );
+
+    // events
+    let events = |events: &Vec<Event>| {
+        events
+            .iter()
+            .map(|event| (event.get_block_container_id(), event.clone()))
+            .collect::<LinkedHashMap<_, _>>()
+    };
+
+    screen.events = merge_map(
+        base.map(|base| events(&base.events)).as_ref(),
+        &events(&ours.events),
+        &events(&theirs.events),
+        |container, ours, theirs| {
+            conflicts.push(Conflict::Event {
+                screen: name.to_owned(),
+                container: container.to_owned(),
+                ours: ours.cloned().map(Box::new),
+                theirs: theirs.cloned().map(Box::new),
+            })
+        },
+    )
+    .into_iter()
+    .map(|(_, event)| event)
+    .collect();
+
+    // fab
+    match merge_value(
+        base.and_then(|base| base.fab.as_ref()),
+        ours.fab.as_ref(),
+        theirs.fab.as_ref(),
+    ) {
+        Merged::Value(fab) if base.is_some() => screen.fab = fab,
+        Merged::Value(_) => {}
+        Merged::Conflict => conflicts.push(Conflict::Fab {
+            screen: name.to_owned(),
+            ours: ours.fab.clone().map(Box::new),
+            theirs: theirs.fab.clone().map(Box::new),
+        }),
+    }
+
+    screen.layout = merge_layout(
+        name,
+        base.map(|base| base.layout.as_slice()).unwrap_or(&[]),
+        &ours.layout,
+        &theirs.layout,
+        conflicts,
+    );
+
+    // drawer
+    let base_drawer = base.and_then(|base| base.drawer.as_ref());
+    match (&ours.drawer, &theirs.drawer) {
+        (Some(our_drawer), Some(their_drawer)) => {
+            let mut drawer_conflicts = Vec::new();
+            let layout = merge_layout(
+                name,
+                base_drawer
+                    .map(|base| base.layout.as_slice())
+                    .unwrap_or(&[]),
+                &our_drawer.layout,
+                &their_drawer.layout,
+                &mut drawer_conflicts,
+            );
+
+            conflicts.extend(drawer_conflicts.into_iter().map(prefix_drawer_ids));
+            screen.drawer = Some(Drawer { layout });
+        }
+        (our_drawer, their_drawer) => {
+            match merge_value(base_drawer, our_drawer.as_ref(), their_drawer.as_ref()) {
+                Merged::Value(drawer) if base.is_some() => screen.drawer = drawer,
+                Merged::Value(_) => {}
+                Merged::Conflict => conflicts.push(Conflict::Drawer {
+                    screen: name.to_owned(),
+                    ours: our_drawer.clone().map(Box::new),
+                    theirs: their_drawer.clone().map(Box::new),
+                }),
+            }
+        }
+    }
+
+    screen
+}
+
+/// Prefixes the ids of the views a conflict of a drawer layout refers to with [`DRAWER_PREFIX`]
+fn prefix_drawer_ids(conflict: Conflict) -> Conflict {
+    let prefix = |id: String| format!("{}{}", DRAWER_PREFIX, id);
+
+    match conflict {
+        Conflict::ViewAttribute {
+            screen,
+            id,
+            attribute,
+            ours,
+            theirs,
+        } => Conflict::ViewAttribute {
+            screen,
+            id: prefix(id),
+            attribute,
+            ours,
+            theirs,
+        },
+        Conflict::ViewParent {
+            screen,
+            id,
+            ours,
+            theirs,
+        } => Conflict::ViewParent {
+            screen,
+            id: prefix(id),
+            ours: ours.map(prefix),
+            theirs: theirs.map(prefix),
+        },
+        Conflict::ViewDeleted {
+            screen,
+            id,
+            deleted_by,
+        } => Conflict::ViewDeleted {
+            screen,
+            id: prefix(id),
+            deleted_by,
+        },
+        conflict => conflict,
+    }
+}
+
+/// Merges layouts, views are matched by their ids and are merged attribute by attribute
+fn merge_layout(
+    screen: &str,
+    base: &[View],
+    ours: &[View],
+    theirs: &[View],
+    conflicts: &mut Vec<Conflict>,
+) -> Vec<View> {
+    let locations = |views| {
+        let mut locations = LinkedHashMap::new();
+        collect_views(views, None, &mut locations);
+        locations
+    };
+
+    let (base_views, our_views, their_views) =
+        (locations(base), locations(ours), locations(theirs));
+
+    // ids of the views that are kept along with their merged views and parents
+    let mut merged = LinkedHashMap::<String, (View, Option<String>)>::new();
+
+    let ids = our_views
+        .keys()
+        .chain(their_views.keys().filter(|id| !our_views.contains_key(*id)))
+        .cloned()
+        .collect::<Vec<_>>();
+
+    for id in &ids {
+        let base_view = base_views.get(id);
+
+        let (view, parent) = match (our_views.get(id), their_views.get(id)) {
+            (Some(our_view), Some(their_view)) => (
+                merge_view(screen, base_view, our_view, their_view, conflicts),
+                merge_parent(screen, id, base_view, our_view, their_view, conflicts),
+            ),
+            (Some(location), None) | (None, Some(location)) => {
+                if let Some(base_view) = base_view {
+                    // deleted on the other side, keep it if it has been modified on this side
+                    if base_view.parent == location.parent
+                        && base_view.view.attributes() == location.view.attributes()
+                    {
+                        continue;
+                    }
+
+                    conflicts.push(Conflict::ViewDeleted {
+                        screen: screen.to_string(),
+                        id: id.to_owned(),
+                        deleted_by: if our_views.contains_key(id) {
+                            Side::Theirs
+                        } else {
+                            Side::Ours
+                        },
+                    });
+                }
+
+                (location.view.clone(), location.parent.to_owned())
+            }
+            (None, None) => unreachable!(),
+        };
+
+        merged.insert(id.to_owned(), (view, parent));
+    }
+
+    // views whose parent got deleted while they are kept, keep their parents too
+    loop {
+        let missing = merged.values().find_map(|(_, parent)| {
+            parent
+                .as_ref()
+                .filter(|parent| !merged.contains_key(*parent))
+                .cloned()
+        });
+
+        let missing = match missing {
+            Some(missing) => missing,
+            None => break,
+        };
+
+        let location = our_views
+            .get(&missing)
+            .or_else(|| their_views.get(&missing))
+            .expect("a parent of a view must exist on either side");
+
+        conflicts.push(Conflict::ViewDeleted {
+            screen: screen.to_string(),
+            id: missing.to_owned(),
+            deleted_by: if our_views.contains_key(&missing) {
+                Side::Theirs
+            } else {
+                Side::Ours
+            },
+        });
+
+        merged.insert(missing, (location.view.clone(), location.parent.to_owned()));
+    }
+
+    // order the children of each parent, the order of a side is kept when the other side didn't
+    // reorder them
+    let order = |views: &LinkedHashMap<String, ViewLocation>, parent: &Option<String>| {
+        views
+            .iter()
+            .filter(|(id, location)| {
+                location.parent == *parent
+                    && merged
+                        .get(*id)
+                        .map(|(_, merged_parent)| merged_parent == parent)
+                        .unwrap_or(false)
+            })
+            .map(|(id, _)| id.to_owned())
+            .collect::<Vec<_>>()
+    };
+
+    let mut parents = vec![None];
+    parents.extend(merged.keys().cloned().map(Some));
+
+    let mut children = LinkedHashMap::<Option<String>, Vec<String>>::new();
+    for parent in parents {
+        let (base_order, our_order, their_order) = (
+            order(&base_views, &parent),
+            order(&our_views, &parent),
+            order(&their_views, &parent),
+        );
+
+        let common = |order: &[String], other: &[String]| {
+            order
+                .iter()
+                .filter(|id| other.contains(id))
+                .cloned()
+                .collect::<Vec<_>>()
+        };
+
+        let (primary, secondary) =
+            if common(&our_order, &base_order) == common(&base_order, &our_order) {
+                (their_order, our_order)
+            } else {
+                (our_order, their_order)
+            };
+
+        let mut result = primary;
+        for (index, id) in secondary.iter().enumerate() {
+            if result.contains(id) {
+                continue;
+            }
+
+            // put it after the view that precedes it
+            let position = secondary[..index]
+                .iter()
+                .rev()
+                .find_map(|previous| result.iter().position(|id| id == previous))
+                .map(|position| position + 1)
+                .unwrap_or(0);
+
+            result.insert(position, id.to_owned());
+        }
+
+        children.insert(parent, result);
+    }
+
+    fn build(
+        parent: &Option<String>,
+        children: &LinkedHashMap<Option<String>, Vec<String>>,
+        merged: &mut LinkedHashMap<String, (View, Option<String>)>,
+    ) -> Vec<View> {
+        let mut result = Vec::new();
+
+        for id in children.get(parent).into_iter().flatten() {
+            if let Some((mut view, _)) = merged.remove(id) {
+                view.children = build(&Some(id.to_owned()), children, merged);
+                result.push(view);
+            }
+        }
+
+        result
+    }
+
+    let mut layout = build(&None, &children, &mut merged);
+
+    // views that are left out are in a cycle (both sides moved them into each other), put them on
+    // the root
+    let leftover = merged.keys().cloned().collect::<Vec<_>>();
+    for id in leftover {
+        if let Some((mut view, _)) = merged.remove(&id) {
+            view.children = build(&Some(id), &children, &mut merged);
+            layout.push(view);
+        }
+    }
+
+    layout
+}
+
+/// Merges the attributes of a view, the returned view doesn't have any children
+fn merge_view(
+    screen: &str,
+    base: Option<&ViewLocation>,
+    ours: &ViewLocation,
+    theirs: &ViewLocation,
+    conflicts: &mut Vec<Conflict>,
+) -> View {
+    let mut view = ours.view.clone();
+    view.children.clear();
+
+    let base_attributes = base.map(|base| base.view.attributes()).unwrap_or_default();
+    let our_attributes = ours.view.attributes();
+
+    for (attribute, their_value) in theirs.view.attributes() {
+        let our_value = our_attributes.get(&attribute);
+
+        match merge_value(
+            base_attributes.get(&attribute),
+            our_value,
+            Some(&their_value),
+        ) {
+            Merged::Value(Some(value)) if Some(&value) != our_value => {
+                // the attribute came from an existing view, so this shouldn't fail
+                let _ = view.set_attribute(&attribute, value);
+            }
+            Merged::Value(_) => {}
+            Merged::Conflict => conflicts.push(Conflict::ViewAttribute {
+                screen: screen.to_string(),
+                id: view.id.to_owned(),
+                attribute,
+                ours: our_value.cloned().unwrap_or_default(),
+                theirs: their_value,
+            }),
+        }
+    }
+
+    view
+}
+
+fn merge_parent(
+    screen: &str,
+    id: &str,
+    base: Option<&ViewLocation>,
+    ours: &ViewLocation,
+    theirs: &ViewLocation,
+    conflicts: &mut Vec<Conflict>,
+) -> Option<String> {
+    match merge_value(
+        base.map(|base| &base.parent),
+        Some(&ours.parent),
+        Some(&theirs.parent),
+    ) {
+        Merged::Value(parent) => parent.flatten(),
+        Merged::Conflict => {
+            conflicts.push(Conflict::ViewParent {
+                screen: screen.to_string(),
+                id: id.to_string(),
+                ours: ours.parent.to_owned(),
+                theirs: theirs.parent.to_owned(),
+            });
+
+            ours.parent.to_owned()
+        }
+    }
+}
+
+enum Merged<T> {
+    /// The merged value, None if it's deleted
+    Value(Option<T>),
+    Conflict,
+}
+
+/// Merges a value three ways, a None means that the value doesn't exist on that side
+fn merge_value<T: PartialEq + Clone>(
+    base: Option<&T>,
+    ours: Option<&T>,
+    theirs: Option<&T>,
+) -> Merged<T> {
+    if ours == theirs || base == theirs {
+        Merged::Value(ours.cloned())
+    } else if base == ours {
+        Merged::Value(theirs.cloned())
+    } else {
+        Merged::Conflict
+    }
+}
+
+/// Merges maps entry by entry, conflicting entries are taken from `ours`
+fn merge_map<K, V, F>(
+    base: Option<&LinkedHashMap<K, V>>,
+    ours: &LinkedHashMap<K, V>,
+    theirs: &LinkedHashMap<K, V>,
+    mut on_conflict: F,
+) -> LinkedHashMap<K, V>
+where
+    K: Hash + Eq + Clone,
+    V: PartialEq + Clone,
+    F: FnMut(&K, Option<&V>, Option<&V>),
+{
+    let mut result = LinkedHashMap::new();
+
+    let keys = ours
+        .keys()
+        .chain(theirs.keys().filter(|key| !ours.contains_key(*key)));
+
+    for key in keys {
+        let (our_value, their_value) = (ours.get(key), theirs.get(key));
+
+        match merge_value(base.and_then(|base| base.get(key)), our_value, their_value) {
+            Merged::Value(Some(value)) => {
+                result.insert(key.clone(), value);
+            }
+            Merged::Value(None) => {}
+            Merged::Conflict => {
+                on_conflict(key, our_value, their_value);
+
+                if let Some(value) = our_value {
+                    result.insert(key.clone(), value.clone());
+                }
+            }
+        }
+    }
+
+    result
+}
+
+/// An option of a screen
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "option", content = "value", rename_all = "snake_case")]
+pub enum ScreenOption {
+    JavaName(String),
+    FullscreenEnabled(bool),
+    ToolbarEnabled(bool),
+    DrawerEnabled(bool),
+    FabEnabled(bool),
+    Orientation(Orientation),
+    Theme(Theme),
+    KeyboardSetting(KeyboardSetting),
+}
+
+impl ScreenOption {
+    /// Retrieves every option of a screen
+    pub fn all(screen: &Screen) -> [ScreenOption; 8] {
+        [
+            ScreenOption::JavaName(screen.java_name.to_owned()),
+            ScreenOption::FullscreenEnabled(screen.fullscreen_enabled),
+            ScreenOption::ToolbarEnabled(screen.toolbar_enabled),
+            ScreenOption::DrawerEnabled(screen.drawer_enabled),
+            ScreenOption::FabEnabled(screen.fab_enabled),
+            ScreenOption::Orientation(screen.orientation),
+            ScreenOption::Theme(screen.theme),
+            ScreenOption::KeyboardSetting(screen.keyboard_setting),
+        ]
+    }
+
+    /// Sets this option on a screen
+    pub fn apply(&self, screen: &mut Screen) {
+        match self {
+            ScreenOption::JavaName(java_name) => screen.java_name = java_name.to_owned(),
+            ScreenOption::FullscreenEnabled(enabled) => screen.fullscreen_enabled = *enabled,
+            ScreenOption::ToolbarEnabled(enabled) => screen.toolbar_enabled = *enabled,
+            ScreenOption::DrawerEnabled(enabled) => screen.drawer_enabled = *enabled,
+            ScreenOption::FabEnabled(enabled) => screen.fab_enabled = *enabled,
+            ScreenOption::Orientation(orientation) => screen.orientation = *orientation,
+            ScreenOption::Theme(theme) => screen.theme = *theme,
+            ScreenOption::KeyboardSetting(setting) => screen.keyboard_setting = *setting,
+        }
+    }
+}
+
+/// A change that is made differently on both sides, holds the values of both sides
+#[derive(Debug, Clone, PartialEq)]
+pub enum Conflict {
+    Metadata {
+        field: &'static str,
+        ours: String,
+        theirs: String,
+    },
+    Color {
+        field: &'static str,
+        ours: Color,
+        theirs: Color,
+    },
+    Libraries {
+        ours: Box<Libraries>,
+        theirs: Box<Libraries>,
+    },
+    CustomIcon {
+        ours: Option<ResourceFileWrapper>,
+        theirs: Option<ResourceFileWrapper>,
+    },
+    CustomView {
+        name: String,
+        ours: Option<Box<CustomView>>,
+        theirs: Option<Box<CustomView>>,
+    },
+    Resource {
+        res_type: ResourceType,
+        id: ResourceId,
+        ours: Option<ResourceFileWrapper>,
+        theirs: Option<ResourceFileWrapper>,
+    },
+
+    /// A screen is modified on one side but deleted on the other
+    Screen {
+        screen: String,
+        ours: Option<Box<Screen>>,
+        theirs: Option<Box<Screen>>,
+    },
+    ScreenOption {
+        screen: String,
+        ours: ScreenOption,
+        theirs: ScreenOption,
+    },
+    Variable {
+        screen: String,
+        name: String,
+        ours: Option<VariableType>,
+        theirs: Option<VariableType>,
+    },
+    ListVariable {
+        screen: String,
+        name: String,
+        ours: Option<VariableType>,
+        theirs: Option<VariableType>,
+    },
+    Component {
+        screen: String,
+        id: String,
+        ours: Option<ComponentKind>,
+        theirs: Option<ComponentKind>,
+    },
+    MoreBlock {
+        screen: String,
+        name: String,
+        ours: Option<Box<MoreBlock>>,
+        theirs: Option<Box<MoreBlock>>,
+    },
+
+    /// An event is edited differently (or edited on one side and deleted on the other), events
+    /// are identified by their block container ids
+    Event {
+        screen: String,
+        container: String,
+        ours: Option<Box<Event>>,
+        theirs: Option<Box<Event>>,
+    },
+    Fab {
+        screen: String,
+        ours: Option<Box<View>>,
+        theirs: Option<Box<View>>,
+    },
+
+    /// The drawer of a screen is added or removed on one side and modified on the other
+    Drawer {
+        screen: String,
+        ours: Option<Box<Drawer>>,
+        theirs: Option<Box<Drawer>>,
+    },
+
+    /// An attribute of a view is changed differently, see [`View::attributes`]
+    ViewAttribute {
+        screen: String,
+        id: String,
+        attribute: String,
+        ours: serde_json::Value,
+        theirs: serde_json::Value,
+    },
+
+    /// A view is moved into different parents, the view is put on the root if the parent is None
+    ViewParent {
+        screen: String,
+        id: String,
+        ours: Option<String>,
+        theirs: Option<String>,
+    },
+
+    /// A view is modified on one side but deleted on the other, the merged project keeps the view
+    ViewDeleted {
+        screen: String,
+        id: String,
+        deleted_by: Side,
+    },
+}
+
+impl Conflict {
+    /// Resolves this conflict on the merged project by picking the value of the given side
+    pub fn resolve(&self, project: &mut SketchwareProject, side: Side) -> Result<(), ResolveError> {
+        fn pick<'a, T>(side: Side, ours: &'a T, theirs: &'a T) -> &'a T {
+            match side {
+                Side::Ours => ours,
+                Side::Theirs => theirs,
+            }
+        }
+
+        fn screen_mut<'a>(
+            project: &'a mut SketchwareProject,
+            name: &str,
+        ) -> Result<&'a mut Screen, ResolveError> {
+            project
+                .screens
+                .iter_mut()
+                .find(|screen| screen.layout_name == name)
+                .ok_or_else(|| ResolveError::ScreenNotFound {
+                    screen: name.to_string(),
+                })
+        }
+
+        match self {
+            Conflict::Metadata {
+                field,
+                ours,
+                theirs,
+            } => {
+                let value = pick(side, ours, theirs);
+
+                if !project.metadata.set_field(field, value) {
+                    return Err(ResolveError::InvalidMetadata {
+                        field,
+                        value: value.to_owned(),
+                    });
+                }
+            }
+            Conflict::Color {
+                field,
+                ours,
+                theirs,
+            } => {
+                let color = *pick(side, ours, theirs);
+
+                if !project.colors.set_field(field, color) {
+                    return Err(ResolveError::InvalidMetadata {
+                        field,
+                        value: color.to_string(),
+                    });
+                }
+            }
+            Conflict::Libraries { ours, theirs } => {
+                project.libraries = pick(side, ours, theirs).as_ref().clone();
+            }
+            Conflict::CustomIcon { ours, theirs } => {
+                project.custom_icon = pick(side, ours, theirs).clone();
+            }
+            Conflict::CustomView { name, ours, theirs } => set_in_vec(
+                &mut project.custom_views,
+                |custom_view| &custom_view.res_name == name,
+                pick(side, ours, theirs).as_deref().cloned(),
+            ),
+            Conflict::Resource {
+                res_type,
+                id,
+                ours,
+                theirs,
+            } => {
+                let resources = match res_type {
+                    ResourceType::Image => &mut project.resources.images,
+                    ResourceType::Sound => &mut project.resources.sounds,
+                    ResourceType::Font => &mut project.resources.fonts,
+                    ResourceType::CustomIcon => return Ok(()),
+                };
+
+                set_in_map(resources, id, pick(side, ours, theirs).clone());
+            }
+            Conflict::Screen {
+                screen,
+                ours,
+                theirs,
+            } => set_in_vec(
+                &mut project.screens,
+                |s| &s.layout_name == screen,
+                pick(side, ours, theirs).as_deref().cloned(),
+            ),
+            Conflict::ScreenOption {
+                screen,
+                ours,
+                theirs,
+            } => pick(side, ours, theirs).apply(screen_mut(project, screen)?),
+            Conflict::Variable {
+                screen,
+                name,
+                ours,
+                theirs,
+            } => set_in_map(
+                &mut screen_mut(project, screen)?.variables,
+                name,
+                pick(side, ours, theirs).map(|r#type| Variable {
+                    name: name.to_owned(),
+                    r#type,
+                }),
+            ),
+            Conflict::ListVariable {
+                screen,
+                name,
+                ours,
+                theirs,
+            } => set_in_map(
+                &mut screen_mut(project, screen)?.list_variables,
+                name,
+                pick(side, ours, theirs).map(|r#type| ListVariable {
+                    name: name.to_owned(),
+                    r#type,
+                }),
+            ),
+            Conflict::Component {
+                screen,
+                id,
+                ours,
+                theirs,
+            } => set_in_map(
+                &mut screen_mut(project, screen)?.components,
+                id,
+                pick(side, ours, theirs).clone(),
+            ),
+            Conflict::MoreBlock {
+                screen,
+                name,
+                ours,
+                theirs,
+            } => set_in_map(
+                &mut screen_mut(project, screen)?.more_blocks,
+                name,
+                pick(side, ours, theirs).as_deref().cloned(),
+            ),
+            Conflict::Event {
+                screen,
+                container,
+                ours,
+                theirs,
+            } => set_in_vec(
+                &mut screen_mut(project, screen)?.events,
+                |event| &event.get_block_container_id() == container,
+                pick(side, ours, theirs).as_deref().cloned(),
+            ),
+            Conflict::Fab {
+                screen,
+                ours,
+                theirs,
+            } => {
+                screen_mut(project, screen)?.fab = pick(side, ours, theirs).as_deref().cloned();
+            }
+            Conflict::Drawer {
+                screen,
+                ours,
+                theirs,
+            } => {
+                screen_mut(project, screen)?.drawer = pick(side, ours, theirs).as_deref().cloned();
+            }
+            Conflict::ViewAttribute {
+                screen,
+                id,
+                attribute,
+                ours,
+                theirs,
+            } => {
+                let view = screen_mut(project, screen)?
+                    .find_view_mut(id)
+                    .ok_or_else(|| ResolveError::ViewNotFound {
+                        screen: screen.to_owned(),
+                        id: id.to_owned(),
+                    })?;
+
+                view.set_attribute(attribute, pick(side, ours, theirs).clone())?;
+            }
+            Conflict::ViewParent {
+                screen,
+                id,
+                ours,
+                theirs,
+            } => {
+                let not_found = || ResolveError::ViewNotFound {
+                    screen: screen.to_owned(),
+                    id: id.to_owned(),
+                };
+
+                let (layout, layout_id) = layout_mut(screen_mut(project, screen)?, id);
+                let layout = layout.ok_or_else(not_found)?;
+                let view = take_view(layout, layout_id).ok_or_else(not_found)?;
+
+                match pick(side, ours, theirs) {
+                    None => layout.push(view),
+                    Some(parent) => match layout.iter_mut().find_map(|v| {
+                        v.find_id_mut(parent.strip_prefix(DRAWER_PREFIX).unwrap_or(parent))
+                    }) {
+                        Some(parent) => parent.children.push(view),
+                        None => {
+                            // put it back to where it can be found
+                            layout.push(view);
+                            return Err(ResolveError::ViewNotFound {
+                                screen: screen.to_owned(),
+                                id: parent.to_owned(),
+                            });
+                        }
+                    },
+                }
+            }
+            Conflict::ViewDeleted {
+                screen,
+                id,
+                deleted_by,
+            } => {
+                if side == *deleted_by {
+                    let (layout, layout_id) = layout_mut(screen_mut(project, screen)?, id);
+
+                    layout
+                        .and_then(|layout| take_view(layout, layout_id))
+                        .ok_or_else(|| ResolveError::ViewNotFound {
+                            screen: screen.to_owned(),
+                            id: id.to_owned(),
+                        })?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Retrieves the layout a view is in along with its id in that layout, views whose ids start with
+/// [`DRAWER_PREFIX`] are in the drawer
+fn layout_mut<'a, 'b>(screen: &'a mut Screen, id: &'b str) -> (Option<&'a mut Vec<View>>, &'b str) {
+    match id.strip_prefix(DRAWER_PREFIX) {
+        Some(id) => (screen.drawer.as_mut().map(|drawer| &mut drawer.layout), id),
+        None => (Some(&mut screen.layout), id),
+    }
+}
+
+/// Replaces, removes or adds an item in a vector
+fn set_in_vec<T, F: Fn(&T) -> bool>(items: &mut Vec<T>, matches: F, value: Option<T>) {
+    let position = items.iter().position(matches);
+
+    match (position, value) {
+        (Some(position), Some(value)) => items[position] = value,
+        (Some(position), None) => {
+            items.remove(position);
+        }
+        (None, Some(value)) => items.push(value),
+        (None, None) => {}
+    }
+}
+
+/// Replaces, removes or adds an entry in a map
+fn set_in_map<K: Hash + Eq + Clone, V>(map: &mut LinkedHashMap<K, V>, key: &K, value: Option<V>) {
+    match value {
+        Some(value) => {
+            if let Some(existing) = map.get_mut(key) {
+                *existing = value;
+            } else {
+                map.insert(key.clone(), value);
+            }
+        }
+        None => {
+            map.remove(key);
+        }
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum ResolveError {
+    #[error("there is no screen named `{screen}`")]
+    ScreenNotFound { screen: String },
+
+    #[error("there is no view with the id `{id}` in screen `{screen}`")]
+    ViewNotFound { screen: String, id: String },
+
+    #[error("`{value}` is not a valid value of `{field}`")]
+    InvalidMetadata { field: &'static str, value: String },
+
+    #[error("couldn't set the attribute of a view")]
+    SetAttribute(#[from] SetAttributeError),
+}
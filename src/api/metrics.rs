@@ -0,0 +1,284 @@
+//! Size and complexity metrics of projects. [`ProjectMetrics::measure`] measures a whole project
+//! with a breakdown of every screen, every event and every moreblock. All metrics are
+//! serializable.
+
+use crate::api::block::{BlockControl, BlockPathSegment, BlockType, Blocks};
+use crate::api::screen::Screen;
+use crate::api::view::View;
+use crate::api::{ResourceId, SketchwareProject};
+use crate::parser::ResourceFileWrapper;
+use crate::LinkedHashMap;
+use serde::{Deserialize, Serialize};
+
+/// Metrics of a whole project
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProjectMetrics {
+    pub screens: Vec<ScreenMetrics>,
+
+    pub custom_views: Vec<CustomViewMetrics>,
+    pub resources: ResourceMetrics,
+
+    /// The total amount of blocks in every screen
+    pub blocks: usize,
+
+    /// The deepest nesting depth across every screen
+    pub max_nesting_depth: usize,
+
+    /// The highest cyclomatic complexity across every event and moreblock
+    pub max_cyclomatic_complexity: usize,
+    pub add_source_directly: usize,
+
+    /// The total amount of views in every screen and custom view
+    pub views: usize,
+}
+
+impl ProjectMetrics {
+    pub fn measure(project: &SketchwareProject) -> ProjectMetrics {
+        let screens: Vec<ScreenMetrics> =
+            project.screens.iter().map(ScreenMetrics::measure).collect();
+        let custom_views: Vec<CustomViewMetrics> = project
+            .custom_views
+            .iter()
+            .map(|custom_view| CustomViewMetrics {
+                name: custom_view.res_name.to_owned(),
+                layout: LayoutMetrics::measure(&custom_view.layout),
+            })
+            .collect();
+
+        ProjectMetrics {
+            blocks: screens.iter().map(|screen| screen.blocks).sum(),
+            max_nesting_depth: screens
+                .iter()
+                .map(|screen| screen.max_nesting_depth)
+                .max()
+                .unwrap_or(0),
+            max_cyclomatic_complexity: screens
+                .iter()
+                .map(|screen| screen.max_cyclomatic_complexity)
+                .max()
+                .unwrap_or(0),
+            add_source_directly: screens
+                .iter()
+                .map(|screen| screen.add_source_directly)
+                .sum(),
+            views: screens
+                .iter()
+                .map(|screen| screen.layout.views)
+                .chain(
+                    custom_views
+                        .iter()
+                        .map(|custom_view| custom_view.layout.views),
+                )
+                .sum(),
+            resources: ResourceMetrics::measure(project),
+            screens,
+            custom_views,
+        }
+    }
+}
+
+/// Metrics of a screen
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScreenMetrics {
+    /// The layout name of the screen
+    pub screen: String,
+
+    /// Metrics of every view of the screen, including its fab and the views of its drawer
+    pub layout: LayoutMetrics,
+
+    /// Metrics of every event and moreblock
+    pub containers: Vec<BlocksMetrics>,
+
+    pub blocks: usize,
+    pub max_nesting_depth: usize,
+    pub max_cyclomatic_complexity: usize,
+    pub add_source_directly: usize,
+}
+
+impl ScreenMetrics {
+    pub fn measure(screen: &Screen) -> ScreenMetrics {
+        let containers: Vec<BlocksMetrics> = screen
+            .block_containers()
+            .into_iter()
+            .map(|(container, blocks)| BlocksMetrics::measure(container, blocks))
+            .collect();
+
+        let mut layout = LayoutMetrics::measure(&screen.layout);
+        let others = screen
+            .fab
+            .iter()
+            .map(|fab| LayoutMetrics::measure(std::slice::from_ref(fab)))
+            .chain(
+                screen
+                    .drawer
+                    .iter()
+                    .map(|drawer| LayoutMetrics::measure(&drawer.layout)),
+            );
+
+        for other in others {
+            layout.views += other.views;
+            layout.depth = layout.depth.max(other.depth);
+        }
+
+        ScreenMetrics {
+            screen: screen.layout_name.to_owned(),
+            layout,
+            blocks: containers.iter().map(|metrics| metrics.blocks).sum(),
+            max_nesting_depth: containers
+                .iter()
+                .map(|metrics| metrics.max_nesting_depth)
+                .max()
+                .unwrap_or(0),
+            max_cyclomatic_complexity: containers
+                .iter()
+                .map(|metrics| metrics.cyclomatic_complexity)
+                .max()
+                .unwrap_or(0),
+            add_source_directly: containers
+                .iter()
+                .map(|metrics| metrics.add_source_directly)
+                .sum(),
+            containers,
+        }
+    }
+}
+
+/// Metrics of the blocks of an event or a moreblock
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlocksMetrics {
+    /// The block container id of the event or the moreblock
+    pub container: String,
+
+    /// The amount of blocks, including blocks put on arguments and blocks inside substacks
+    pub blocks: usize,
+
+    /// How deep substacks are nested, blocks that aren't inside of any substack have a depth of 0
+    pub max_nesting_depth: usize,
+
+    /// 1 + the amount of blocks that have substacks (like `if`, `ifElse` and `repeat`)
+    pub cyclomatic_complexity: usize,
+
+    /// The amount of `addSourceDirectly` blocks
+    pub add_source_directly: usize,
+}
+
+impl BlocksMetrics {
+    pub fn measure(container: String, blocks: &Blocks) -> BlocksMetrics {
+        let mut metrics = BlocksMetrics {
+            container,
+            blocks: 0,
+            max_nesting_depth: 0,
+            cyclomatic_complexity: 1,
+            add_source_directly: 0,
+        };
+
+        blocks.visit(|path, block| {
+            metrics.blocks += 1;
+
+            let depth = path
+                .0
+                .iter()
+                .filter(|segment| {
+                    matches!(
+                        segment,
+                        BlockPathSegment::SubStack1 | BlockPathSegment::SubStack2
+                    )
+                })
+                .count();
+            metrics.max_nesting_depth = metrics.max_nesting_depth.max(depth);
+
+            if matches!(
+                block.block_type,
+                BlockType::Control(BlockControl::OneNest | BlockControl::TwoNest)
+            ) {
+                metrics.cyclomatic_complexity += 1;
+            }
+
+            if block.op_code == "addSourceDirectly" {
+                metrics.add_source_directly += 1;
+            }
+        });
+
+        metrics
+    }
+}
+
+/// Metrics of the layout of a custom view
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CustomViewMetrics {
+    pub name: String,
+    pub layout: LayoutMetrics,
+}
+
+/// Metrics of a layout
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LayoutMetrics {
+    /// The amount of views, including their children
+    pub views: usize,
+
+    /// How deep views are nested, a layout with only root views has a depth of 1
+    pub depth: usize,
+}
+
+impl LayoutMetrics {
+    pub fn measure(views: &[View]) -> LayoutMetrics {
+        LayoutMetrics {
+            views: views
+                .iter()
+                .map(|view| 1 + LayoutMetrics::measure(&view.children).views)
+                .sum(),
+            depth: views
+                .iter()
+                .map(|view| 1 + LayoutMetrics::measure(&view.children).depth)
+                .max()
+                .unwrap_or(0),
+        }
+    }
+}
+
+/// Metrics of the resources of a project
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResourceMetrics {
+    pub images: usize,
+    pub sounds: usize,
+    pub fonts: usize,
+
+    /// The total size of the resources (and the custom icon) in bytes
+    pub bytes: u64,
+
+    /// The amount of resources whose sizes are unknown, either because they aren't real files
+    /// or because they can't be read. They aren't counted in `bytes`.
+    pub unknown_sizes: usize,
+}
+
+impl ResourceMetrics {
+    pub fn measure(project: &SketchwareProject) -> ResourceMetrics {
+        let resources = &project.resources;
+        let mut metrics = ResourceMetrics {
+            images: resources.images.len(),
+            sounds: resources.sounds.len(),
+            fonts: resources.fonts.len(),
+            bytes: 0,
+            unknown_sizes: 0,
+        };
+
+        let files = [&resources.images, &resources.sounds, &resources.fonts]
+            .into_iter()
+            .flat_map(
+                |resources: &LinkedHashMap<ResourceId, ResourceFileWrapper>| resources.values(),
+            )
+            .chain(project.custom_icon.iter());
+
+        for file in files {
+            match file {
+                ResourceFileWrapper::Path(path) => match std::fs::metadata(path) {
+                    Ok(metadata) => metrics.bytes += metadata.len(),
+                    Err(_) => metrics.unknown_sizes += 1,
+                },
+                _ => metrics.unknown_sizes += 1,
+            }
+        }
+
+        metrics
+    }
+}
@@ -1,11 +1,31 @@
 pub mod block;
+pub mod blockly;
+pub mod callgraph;
 pub mod component;
+pub mod dataflow;
+pub mod diff;
+pub mod event;
+pub mod i18n;
+pub mod id;
+pub mod interpreter;
+pub mod layout;
+pub mod lifecycle;
+pub mod merge;
+pub mod metrics;
+pub mod navigation;
+pub mod patch;
+pub mod permissions;
 pub mod screen;
+pub mod secrets;
+pub mod snippet;
+pub mod tree;
+pub mod typecheck;
+pub mod unused;
 pub mod view;
 
 use crate::api::component::ComponentKind;
 use crate::api::library::{AdMob, Firebase, GoogleMap};
-use crate::api::screen::{Event, MoreBlock, Screen, ScreenConstructionError};
+use crate::api::screen::{Event, MoreBlock, Screen, ScreenConstructionError, DRAWER_PREFIX};
 use crate::api::view::{flatten_views, parse_raw_layout, ParseLayoutError, View};
 use crate::color::Color;
 use crate::parser;
@@ -27,6 +47,7 @@ use crate::parser::{
     SketchwareProjectReconstructionError,
 };
 use crate::LinkedHashMap;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::PathBuf;
 use thiserror::Error;
@@ -66,6 +87,92 @@ pub struct Colors {
     pub color_control_highlight: Color,
 }
 
+impl Metadata {
+    /// The names of the fields accessible through [`Metadata::get_field`] and
+    /// [`Metadata::set_field`]
+    pub(crate) const FIELDS: [&'static str; 6] = [
+        "name",
+        "workspace_name",
+        "package_name",
+        "version_name",
+        "version_code",
+        "sketchware_version",
+    ];
+
+    /// Retrieves a field by its name as a string, used by diffs and patches. Only the fields that
+    /// are meant to be edited (not `local_id` nor `time_created`) are accessible
+    pub(crate) fn get_field(&self, field: &str) -> Option<String> {
+        Some(match field {
+            "name" => self.name.to_owned(),
+            "workspace_name" => self.workspace_name.to_owned(),
+            "package_name" => self.package_name.to_owned(),
+            "version_name" => self.version_name.to_owned(),
+            "version_code" => self.version_code.to_string(),
+            "sketchware_version" => self.sketchware_version.to_string(),
+            _ => return None,
+        })
+    }
+
+    /// Sets a field by its name from a string, returns false if the field doesn't exist or the
+    /// value is invalid. See [`Metadata::get_field`]
+    pub(crate) fn set_field(&mut self, field: &str, value: &str) -> bool {
+        match field {
+            "name" => self.name = value.to_owned(),
+            "workspace_name" => self.workspace_name = value.to_owned(),
+            "package_name" => self.package_name = value.to_owned(),
+            "version_name" => self.version_name = value.to_owned(),
+            "version_code" => match value.parse() {
+                Ok(value) => self.version_code = value,
+                Err(_) => return false,
+            },
+            "sketchware_version" => match value.parse() {
+                Ok(value) => self.sketchware_version = value,
+                Err(_) => return false,
+            },
+            _ => return false,
+        }
+
+        true
+    }
+}
+
+impl Colors {
+    /// The names of the fields accessible through [`Colors::get_field`] and [`Colors::set_field`]
+    pub(crate) const FIELDS: [&'static str; 5] = [
+        "color_primary",
+        "color_primary_dark",
+        "color_accent",
+        "color_control_normal",
+        "color_control_highlight",
+    ];
+
+    /// Retrieves a color by its field name
+    pub(crate) fn get_field(&self, field: &str) -> Option<Color> {
+        Some(match field {
+            "color_primary" => self.color_primary,
+            "color_primary_dark" => self.color_primary_dark,
+            "color_accent" => self.color_accent,
+            "color_control_normal" => self.color_control_normal,
+            "color_control_highlight" => self.color_control_highlight,
+            _ => return None,
+        })
+    }
+
+    /// Sets a color by its field name, returns false if the field doesn't exist
+    pub(crate) fn set_field(&mut self, field: &str, color: Color) -> bool {
+        match field {
+            "color_primary" => self.color_primary = color,
+            "color_primary_dark" => self.color_primary_dark = color,
+            "color_accent" => self.color_accent = color,
+            "color_control_normal" => self.color_control_normal = color,
+            "color_control_highlight" => self.color_control_highlight = color,
+            _ => return false,
+        }
+
+        true
+    }
+}
+
 /// A model that stores libraries' information of a project
 #[derive(Debug, Clone, PartialEq)]
 pub struct Libraries {
@@ -75,7 +182,37 @@ pub struct Libraries {
     pub google_map: Option<library::GoogleMap>,
 }
 
+impl Libraries {
+    /// Checks whether a library is enabled
+    pub fn is_enabled(&self, library: library::LibraryKind) -> bool {
+        match library {
+            library::LibraryKind::Firebase => self.firebase.is_some(),
+            library::LibraryKind::AdMob => self.ad_mob.is_some(),
+            library::LibraryKind::GoogleMap => self.google_map.is_some(),
+        }
+    }
+}
+
 pub mod library {
+    /// The libraries that can be enabled in a project, without their configurations
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub enum LibraryKind {
+        Firebase,
+        AdMob,
+        GoogleMap,
+    }
+
+    impl LibraryKind {
+        /// Retrieves the name of this library, as it's shown in sketchware
+        pub fn name(&self) -> &'static str {
+            match self {
+                LibraryKind::Firebase => "Firebase",
+                LibraryKind::AdMob => "AdMob",
+                LibraryKind::GoogleMap => "Google Maps",
+            }
+        }
+    }
+
     #[derive(Debug, Clone, PartialEq)]
     pub struct Firebase {
         pub project_id: String,     // key: data
@@ -123,7 +260,7 @@ impl Resources {
 }
 
 /// A newtype struct of a resource id, used in [`Resources`]
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct ResourceId(pub String);
 
 impl Resources {
@@ -398,6 +535,21 @@ impl TryFrom<ParsedSketchwareProject> for SketchwareProject {
             }}
         }
 
+        // drawers are listed along with the custom views, take the ones that belong to a screen
+        let (drawers, file_custom_views): (Vec<FileItem>, Vec<FileItem>) =
+            val.file.custom_views.into_iter().partition(|file_entry| {
+                file_entry.file_type == FileType::Drawer
+                    && file_entry
+                        .filename
+                        .strip_prefix(DRAWER_PREFIX)
+                        .is_some_and(|screen| {
+                            val.file
+                                .activities
+                                .iter()
+                                .any(|activity| activity.filename == screen)
+                        })
+            });
+
         // get the activities
         let activities =
             val.file
@@ -424,6 +576,18 @@ impl TryFrom<ParsedSketchwareProject> for SketchwareProject {
                     // get our fab (if we have one)
                     let fab = val.view.fabs.remove(name.as_str()).map(View::from);
 
+                    // and our drawer (if we have one)
+                    let drawer_name = format!("{}{}", DRAWER_PREFIX, name);
+                    let drawer = drawers
+                        .iter()
+                        .any(|drawer| drawer.filename == drawer_name)
+                        .then(|| {
+                            val.view
+                                .layouts
+                                .remove(drawer_name.as_str())
+                                .unwrap_or_else(|| Layout(vec![]))
+                        });
+
                     acc.push(
                         Screen::from_parsed(
                             file_entry.filename.to_owned(),
@@ -432,6 +596,7 @@ impl TryFrom<ParsedSketchwareProject> for SketchwareProject {
                             layout,
                             logic,
                             fab,
+                            drawer,
                         )
                         .map_err(|err| {
                             APISketchwareProjectConversionError::ScreenConstructionError {
@@ -447,8 +612,7 @@ impl TryFrom<ParsedSketchwareProject> for SketchwareProject {
 
         // and get the custom views
         let custom_views =
-            val.file
-                .custom_views
+            file_custom_views
                 .into_iter()
                 .try_fold(Vec::new(), |mut acc, file_entry| {
                     // retrieve the layout of this custom view
@@ -630,9 +794,16 @@ impl From<SketchwareProject> for ParsedSketchwareProject {
                     .collect(),
                 val.custom_views
                     .iter()
-                    .map(|custom_view| FileItem {
-                        filename: custom_view.res_name.to_owned(),
-                        file_type: FileType::CustomView,
+                    .map(|custom_view| (custom_view.res_name.to_owned(), FileType::CustomView))
+                    .chain(
+                        val.screens
+                            .iter()
+                            .filter(|screen| screen.drawer.is_some())
+                            .map(|screen| (screen.drawer_layout_name(), FileType::Drawer)),
+                    )
+                    .map(|(filename, file_type)| FileItem {
+                        filename,
+                        file_type,
                         keyboard_setting: KeyboardSetting::Unspecified,
                         options: ActivityOptions {
                             toolbar_enabled: false,
@@ -716,6 +887,8 @@ impl From<SketchwareProject> for ParsedSketchwareProject {
             let mut fabs = LinkedHashMap::new();
 
             for screen in val.screens {
+                let drawer_name = screen.drawer_layout_name();
+
                 if let Some(fab) = screen.fab {
                     let mut fab_view = flatten_views(vec![fab], None, None).remove(0);
 
@@ -742,6 +915,14 @@ impl From<SketchwareProject> for ParsedSketchwareProject {
                     screen.layout_name,
                     Layout(view::flatten_views(screen.layout, None, None)),
                 );
+
+                // the drawer is stored as its own layout right after its screen
+                if let Some(drawer) = screen.drawer {
+                    layouts.insert(
+                        drawer_name,
+                        Layout(view::flatten_views(drawer.layout, None, None)),
+                    );
+                }
             }
 
             // don't forget about the custom views
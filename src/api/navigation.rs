@@ -0,0 +1,349 @@
+//! Navigation between screens. [`NavigationGraph::build`] finds every `startActivity` block of a
+//! project, resolves the screens their intents are set to (with `intentSetScreen`) and which
+//! events trigger them. The graph can be exported as DOT ([`NavigationGraph::to_dot`]), Mermaid
+//! ([`NavigationGraph::to_mermaid`]) or JSON ([`NavigationGraph::to_json`]).
+
+use crate::api::block::{ArgValue, Argument, Block, BlockPath, Blocks};
+use crate::api::callgraph::{CallGraph, Node};
+use crate::api::lifecycle::MAIN_SCREEN;
+use crate::api::screen::Screen;
+use crate::api::SketchwareProject;
+use crate::util::{escape_dot, escape_mermaid};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::Write;
+
+/// Where a navigation goes
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "name", rename_all = "snake_case")]
+pub enum Target {
+    /// A screen of the project, identified by its layout name
+    Screen(String),
+
+    /// An activity that isn't a screen of the project, with the name used in the block
+    Unknown(String),
+}
+
+/// A `startActivity` block that goes from a screen to another
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Navigation {
+    /// The layout name of the screen this navigation starts from
+    pub from: String,
+    pub to: Target,
+
+    /// The id of the intent component
+    pub intent: String,
+
+    /// The block container id of the event or the moreblock that has the `startActivity` block
+    pub container: String,
+    pub path: BlockPath,
+
+    /// Block container ids of the events that trigger this navigation: the event that has the
+    /// block, or the events that (indirectly) call the moreblock that has it
+    pub events: Vec<String>,
+}
+
+/// Navigations between the screens of a project
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NavigationGraph {
+    /// Layout names of every screen of the project
+    pub screens: Vec<String>,
+    pub navigations: Vec<Navigation>,
+}
+
+impl NavigationGraph {
+    /// Builds the navigation graph of a project. The screen a `startActivity` block goes to is
+    /// the last screen its intent is set to before it in the same event or moreblock, if there
+    /// isn't any, it goes to every screen the intent is set to elsewhere in the screen.
+    pub fn build(project: &SketchwareProject) -> NavigationGraph {
+        NavigationGraph {
+            screens: project
+                .screens
+                .iter()
+                .map(|screen| screen.layout_name.to_owned())
+                .collect(),
+            navigations: project
+                .screens
+                .iter()
+                .flat_map(|screen| navigations(project, screen))
+                .collect(),
+        }
+    }
+
+    /// Retrieves the navigations that start from a screen
+    pub fn from_screen<'a>(&'a self, layout_name: &'a str) -> impl Iterator<Item = &'a Navigation> {
+        self.navigations
+            .iter()
+            .filter(move |navigation| navigation.from == layout_name)
+    }
+
+    /// Retrieves the screens no navigation goes to, except the main screen as it's the one the app
+    /// is launched into
+    pub fn unreachable_screens(&self) -> Vec<&str> {
+        self.screens
+            .iter()
+            .filter(|screen| *screen != MAIN_SCREEN)
+            .filter(|screen| {
+                !self
+                    .navigations
+                    .iter()
+                    .any(|navigation| matches!(&navigation.to, Target::Screen(to) if to == *screen))
+            })
+            .map(String::as_str)
+            .collect()
+    }
+
+    /// Exports this graph in the DOT language, edges are labelled with the triggering events
+    pub fn to_dot(&self) -> String {
+        let mut result = String::from("digraph navigation {\n");
+
+        for screen in &self.screens {
+            writeln!(result, "    \"{}\" [shape=box];", escape_dot(screen)).unwrap();
+        }
+
+        for name in self.unknown_targets() {
+            writeln!(
+                result,
+                "    \"{}\" [shape=box, style=dashed];",
+                escape_dot(name)
+            )
+            .unwrap();
+        }
+
+        for (from, to, event) in self.edges() {
+            writeln!(
+                result,
+                "    \"{}\" -> \"{}\" [label=\"{}\"];",
+                escape_dot(from),
+                escape_dot(target_name(to)),
+                escape_dot(event)
+            )
+            .unwrap();
+        }
+
+        result.push_str("}\n");
+        result
+    }
+
+    /// Exports this graph as a Mermaid flowchart, edges are labelled with the triggering events
+    pub fn to_mermaid(&self) -> String {
+        let mut result = String::from("flowchart LR\n");
+
+        for screen in &self.screens {
+            let target = Target::Screen(screen.to_owned());
+            writeln!(
+                result,
+                "    {}[\"{}\"]",
+                mermaid_id(&target),
+                escape_mermaid(screen)
+            )
+            .unwrap();
+        }
+
+        for name in self.unknown_targets() {
+            let target = Target::Unknown(name.to_string());
+            writeln!(
+                result,
+                "    {}[/\"{}\"/]",
+                mermaid_id(&target),
+                escape_mermaid(name)
+            )
+            .unwrap();
+        }
+
+        for (from, to, event) in self.edges() {
+            writeln!(
+                result,
+                "    {} -->|\"{}\"| {}",
+                mermaid_id(&Target::Screen(from.to_string())),
+                escape_mermaid(event),
+                mermaid_id(to)
+            )
+            .unwrap();
+        }
+
+        result
+    }
+
+    /// Exports this graph as pretty printed JSON
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("a navigation graph is always serializable")
+    }
+
+    /// Retrieves the names of the targets that aren't screens of the project, without duplicates
+    fn unknown_targets(&self) -> Vec<&str> {
+        let mut names = Vec::new();
+
+        for navigation in &self.navigations {
+            if let Target::Unknown(name) = &navigation.to {
+                if !names.contains(&name.as_str()) {
+                    names.push(name.as_str());
+                }
+            }
+        }
+
+        names
+    }
+
+    /// Retrieves the navigations as (from, to, event) edges without duplicates, navigations that
+    /// no event triggers are labelled with their block container ids
+    fn edges(&self) -> Vec<(&str, &Target, &str)> {
+        let mut edges = Vec::new();
+
+        for navigation in &self.navigations {
+            let events = if navigation.events.is_empty() {
+                std::slice::from_ref(&navigation.container)
+            } else {
+                navigation.events.as_slice()
+            };
+
+            for event in events {
+                let edge = (navigation.from.as_str(), &navigation.to, event.as_str());
+                if !edges.contains(&edge) {
+                    edges.push(edge);
+                }
+            }
+        }
+
+        edges
+    }
+}
+
+/// Finds the navigations of a screen
+fn navigations(project: &SketchwareProject, screen: &Screen) -> Vec<Navigation> {
+    let containers: Vec<(Node, &Blocks)> = screen
+        .block_containers()
+        .into_iter()
+        .map(
+            |(container, blocks)| match container.strip_suffix("_moreBlock") {
+                Some(name) => (Node::MoreBlock(name.to_owned()), blocks),
+                None => (Node::Event(container), blocks),
+            },
+        )
+        .collect();
+
+    // every screen each intent is set to, used when a startActivity isn't preceded by a
+    // setScreen
+    let mut all_targets: HashMap<String, Vec<String>> = HashMap::new();
+    for (_, blocks) in &containers {
+        blocks.visit(|_, block| {
+            if let Some((intent, target)) = set_screen(block) {
+                let targets = all_targets.entry(intent.to_string()).or_default();
+                if !targets.iter().any(|existing| existing == target) {
+                    targets.push(target.to_string());
+                }
+            }
+        });
+    }
+
+    let call_graph = CallGraph::build(screen);
+    let mut result = Vec::new();
+
+    for (node, blocks) in &containers {
+        let events: Vec<String> = match node {
+            Node::Event(container) => vec![container.to_owned()],
+            Node::MoreBlock(name) => call_graph
+                .nodes
+                .iter()
+                .filter(|caller| {
+                    matches!(caller, Node::Event(_))
+                        && call_graph.reachable_from(caller).contains(&name.as_str())
+                })
+                .map(ToString::to_string)
+                .collect(),
+        };
+
+        let container = match node {
+            Node::Event(container) => container.to_owned(),
+            Node::MoreBlock(name) => format!("{}_moreBlock", name),
+        };
+
+        let mut current_targets: HashMap<String, String> = HashMap::new();
+
+        blocks.visit(|path, block| {
+            if let Some((intent, target)) = set_screen(block) {
+                current_targets.insert(intent.to_string(), target.to_string());
+                return;
+            }
+
+            let intent = match (block.op_code.as_str(), menu_value(block, 0)) {
+                ("startActivity", Some(intent)) => intent,
+                _ => return,
+            };
+
+            let targets = match current_targets.get(intent) {
+                Some(target) => vec![target.to_owned()],
+                None => all_targets.get(intent).cloned().unwrap_or_default(),
+            };
+
+            for target in targets {
+                result.push(Navigation {
+                    from: screen.layout_name.to_owned(),
+                    to: resolve(project, &target),
+                    intent: intent.to_string(),
+                    container: container.to_owned(),
+                    path: path.to_owned(),
+                    events: events.to_owned(),
+                });
+            }
+        });
+    }
+
+    result
+}
+
+/// Retrieves the intent and the activity name of an `intentSetScreen` block
+fn set_screen(block: &Block) -> Option<(&str, &str)> {
+    if block.op_code != "intentSetScreen" {
+        return None;
+    }
+
+    Some((menu_value(block, 0)?, menu_value(block, 1)?))
+}
+
+fn menu_value(block: &Block, index: usize) -> Option<&str> {
+    match block.content.get_args().get(index)? {
+        Argument::Menu {
+            value: ArgValue::Value(value),
+            ..
+        } if !value.is_empty() => Some(value.as_str()),
+        _ => None,
+    }
+}
+
+/// Resolves an activity name (like `MainActivity`) into a screen of the project
+fn resolve(project: &SketchwareProject, activity: &str) -> Target {
+    project
+        .screens
+        .iter()
+        .find(|screen| {
+            screen.java_name.trim_end_matches(".java") == activity || screen.layout_name == activity
+        })
+        .map(|screen| Target::Screen(screen.layout_name.to_owned()))
+        .unwrap_or_else(|| Target::Unknown(activity.to_string()))
+}
+
+fn target_name(target: &Target) -> &str {
+    match target {
+        Target::Screen(name) | Target::Unknown(name) => name,
+    }
+}
+
+/// Mermaid ids can't have other characters than alphanumerics and underscores
+fn mermaid_id(target: &Target) -> String {
+    let (prefix, name) = match target {
+        Target::Screen(name) => ("s_", name),
+        Target::Unknown(name) => ("u_", name),
+    };
+
+    prefix
+        .chars()
+        .chain(name.chars().map(|char| {
+            if char.is_ascii_alphanumeric() {
+                char
+            } else {
+                '_'
+            }
+        }))
+        .collect()
+}
@@ -0,0 +1,1302 @@
+//! Serializable patches of projects. A [`Patch`] is a list of [`Operation`]s that can be computed
+//! from two versions of a project using [`Patch::between`], stored as JSON with serde, then
+//! applied to other projects using [`Patch::apply_patch`].
+//!
+//! Operations carry the values they expect to replace, applying a patch fails with the index of
+//! the failing operation when the target project no longer matches.
+//!
+//! Views of drawers are identified by their ids prefixed with [`DRAWER_PREFIX`], along with the
+//! ids of their parents and siblings.
+
+use crate::api::block::{
+    ArgValue, Argument, Block, BlockContent, BlockContentParseError, BlockConversionError,
+    BlockPath, Blocks,
+};
+use crate::api::component::ComponentKind;
+use crate::api::diff::{
+    collect_views, diff_blocks, diff_screen, drawer_locations, BlockEdit, ScreenChange,
+};
+use crate::api::merge::ScreenOption;
+use crate::api::screen::{Drawer, Event, MoreBlock, Screen, UnknownEventType, DRAWER_PREFIX};
+use crate::api::view::{take_view, SetAttributeError, View};
+use crate::api::{Colors, Metadata, ResourceId, SketchwareProject};
+use crate::color::Color;
+use crate::parser::logic::component::Component as ParserComponent;
+use crate::parser::logic::event::Event as ParserEvent;
+use crate::parser::logic::list_variable::ListVariable;
+use crate::parser::logic::variable::{Variable, VariableType};
+use crate::parser::logic::{Block as ParserBlock, BlockContainer};
+use crate::parser::view::models::AndroidView;
+use crate::parser::{ResourceFileWrapper, ResourceType};
+use crate::LinkedHashMap;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+/// A list of operations that are applied in order
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct Patch {
+    pub operations: Vec<Operation>,
+}
+
+/// An operation of a [`Patch`], screens are identified by their layout names and block
+/// containers are identified by their ids (see [`Event::get_block_container_id`], moreblocks use
+/// `{name}_moreBlock`)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "kebab-case")]
+pub enum Operation {
+    /// Sets a field of [`crate::api::Metadata`], like `version_code`
+    SetMetadata {
+        field: String,
+        old: String,
+        new: String,
+    },
+
+    /// Sets a field of [`crate::api::Colors`], like `color_accent`
+    SetColor {
+        field: String,
+        old: Color,
+        new: Color,
+    },
+
+    AddResource {
+        res_type: ResourceType,
+        id: ResourceId,
+        file: ResourceFileWrapper,
+    },
+    RemoveResource {
+        res_type: ResourceType,
+        id: ResourceId,
+    },
+
+    /// Adds an empty screen
+    AddScreen {
+        screen: String,
+        java_name: String,
+    },
+    RemoveScreen {
+        screen: String,
+    },
+    SetScreenOption {
+        screen: String,
+        old: ScreenOption,
+        new: ScreenOption,
+    },
+
+    /// Adds a view without children. The view is put right after the sibling `after`, or as the
+    /// first child when it's None. `parent` is None for the root of the layout
+    AddView {
+        screen: String,
+        parent: Option<String>,
+        after: Option<String>,
+        view: Box<AndroidView>,
+    },
+
+    /// Removes a view along with its children
+    RemoveView {
+        screen: String,
+        id: String,
+    },
+
+    /// Moves a view along with its children, see [`Operation::AddView`]
+    MoveView {
+        screen: String,
+        id: String,
+        parent: Option<String>,
+        after: Option<String>,
+    },
+
+    /// Sets an attribute of a view, attributes are named after their raw names (see
+    /// [`View::attributes`])
+    SetAttribute {
+        screen: String,
+        id: String,
+        attribute: String,
+        old: serde_json::Value,
+        new: serde_json::Value,
+    },
+    SetFab {
+        screen: String,
+        old: Option<Box<AndroidView>>,
+        fab: Option<Box<AndroidView>>,
+    },
+
+    /// Adds an empty drawer to a screen, its views are then added with [`Operation::AddView`]
+    AddDrawer {
+        screen: String,
+    },
+
+    /// Removes the drawer of a screen along with its views
+    RemoveDrawer {
+        screen: String,
+    },
+
+    AddVariable {
+        screen: String,
+        name: String,
+        r#type: VariableType,
+    },
+    RemoveVariable {
+        screen: String,
+        name: String,
+    },
+    AddListVariable {
+        screen: String,
+        name: String,
+        r#type: VariableType,
+    },
+    RemoveListVariable {
+        screen: String,
+        name: String,
+    },
+
+    AddComponent {
+        screen: String,
+        component: ParserComponent,
+    },
+    RemoveComponent {
+        screen: String,
+        id: String,
+    },
+
+    /// Adds a moreblock without any code
+    AddMoreBlock {
+        screen: String,
+        name: String,
+        spec: String,
+    },
+    RemoveMoreBlock {
+        screen: String,
+        name: String,
+    },
+    SetMoreBlockSpec {
+        screen: String,
+        name: String,
+        old: String,
+        new: String,
+    },
+
+    /// Adds an event without any code
+    AddEvent {
+        screen: String,
+        event: ParserEvent,
+    },
+    RemoveEvent {
+        screen: String,
+        container: String,
+    },
+
+    /// Inserts a block into the position the path points to, `block` contains the raw blocks of
+    /// the block (along with its arguments and substacks) with the block itself as the first one
+    InsertBlock {
+        screen: String,
+        container: String,
+        path: BlockPath,
+        block: Vec<ParserBlock>,
+    },
+    RemoveBlock {
+        screen: String,
+        container: String,
+        path: BlockPath,
+        op_code: String,
+    },
+    SetArgument {
+        screen: String,
+        container: String,
+        path: BlockPath,
+        argument: usize,
+        old: PatchArgument,
+        new: PatchArgument,
+    },
+}
+
+/// The value of a block argument in a patch
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PatchArgument {
+    /// A literal value, numbers and booleans are stored as strings
+    Value(String),
+
+    /// Raw blocks of a block put on the argument, with the block itself as the first one
+    Block(Vec<ParserBlock>),
+    Empty,
+}
+
+impl Patch {
+    /// Computes a patch that turns `old` into `new`
+    pub fn between(old: &SketchwareProject, new: &SketchwareProject) -> Patch {
+        let mut operations = Vec::new();
+
+        for field in Metadata::FIELDS {
+            let (old_value, new_value) = (
+                old.metadata.get_field(field).unwrap_or_default(),
+                new.metadata.get_field(field).unwrap_or_default(),
+            );
+
+            if old_value != new_value {
+                operations.push(Operation::SetMetadata {
+                    field: field.to_string(),
+                    old: old_value,
+                    new: new_value,
+                });
+            }
+        }
+
+        for field in Colors::FIELDS {
+            let (old_color, new_color) = (old.colors.get_field(field), new.colors.get_field(field));
+
+            if let (Some(old_color), Some(new_color)) = (old_color, new_color) {
+                if old_color != new_color {
+                    operations.push(Operation::SetColor {
+                        field: field.to_string(),
+                        old: old_color,
+                        new: new_color,
+                    });
+                }
+            }
+        }
+
+        // resources, changed resources are removed then re-added
+        for (res_type, old_resources, new_resources) in [
+            (
+                ResourceType::Image,
+                &old.resources.images,
+                &new.resources.images,
+            ),
+            (
+                ResourceType::Sound,
+                &old.resources.sounds,
+                &new.resources.sounds,
+            ),
+            (
+                ResourceType::Font,
+                &old.resources.fonts,
+                &new.resources.fonts,
+            ),
+        ] {
+            for (id, file) in old_resources {
+                if new_resources.get(id) != Some(file) {
+                    operations.push(Operation::RemoveResource {
+                        res_type,
+                        id: id.to_owned(),
+                    });
+                }
+            }
+
+            for (id, file) in new_resources {
+                if old_resources.get(id) != Some(file) {
+                    operations.push(Operation::AddResource {
+                        res_type,
+                        id: id.to_owned(),
+                        file: file.clone(),
+                    });
+                }
+            }
+        }
+
+        // screens
+        for screen in &old.screens {
+            if !new
+                .screens
+                .iter()
+                .any(|s| s.layout_name == screen.layout_name)
+            {
+                operations.push(Operation::RemoveScreen {
+                    screen: screen.layout_name.to_owned(),
+                });
+            }
+        }
+
+        for screen in &new.screens {
+            match old
+                .screens
+                .iter()
+                .find(|s| s.layout_name == screen.layout_name)
+            {
+                Some(old_screen) => screen_operations(old_screen, screen, &mut operations),
+                None => {
+                    operations.push(Operation::AddScreen {
+                        screen: screen.layout_name.to_owned(),
+                        java_name: screen.java_name.to_owned(),
+                    });
+
+                    let empty = Screen::new_empty(
+                        screen.layout_name.to_owned(),
+                        screen.java_name.to_owned(),
+                    );
+                    screen_operations(&empty, screen, &mut operations);
+                }
+            }
+        }
+
+        Patch { operations }
+    }
+
+    /// Checks whether this patch doesn't do anything
+    pub fn is_empty(&self) -> bool {
+        self.operations.is_empty()
+    }
+
+    /// Applies this patch on a project. The project is left untouched when any of the operations
+    /// fails.
+    pub fn apply_patch(&self, project: &mut SketchwareProject) -> Result<(), PatchError> {
+        let mut patched = project.clone();
+
+        for (index, operation) in self.operations.iter().enumerate() {
+            operation.apply(&mut patched).map_err(|source| PatchError {
+                index,
+                operation: operation.name(),
+                source,
+            })?;
+        }
+
+        *project = patched;
+        Ok(())
+    }
+}
+
+/// Computes the operations that turn the screen `old` into `new`
+fn screen_operations(old: &Screen, new: &Screen, operations: &mut Vec<Operation>) {
+    let screen = &new.layout_name;
+
+    for (old_option, new_option) in ScreenOption::all(old)
+        .into_iter()
+        .zip(ScreenOption::all(new))
+    {
+        if old_option != new_option {
+            operations.push(Operation::SetScreenOption {
+                screen: screen.to_owned(),
+                old: old_option,
+                new: new_option,
+            });
+        }
+    }
+
+    let changes = diff_screen(old, new);
+    let fab_ids = [&old.fab, &new.fab]
+        .into_iter()
+        .flatten()
+        .map(|fab| fab.id.as_str())
+        .collect::<Vec<_>>();
+
+    // views are added and moved in the order they are in the new layout so that their previous
+    // siblings always exist, then views that are gone are removed
+    let mut added = Vec::new();
+    let mut moved = Vec::new();
+    let mut removed = Vec::new();
+
+    for change in &changes {
+        match change {
+            ScreenChange::ViewAdded { id, .. } => added.push(id.as_str()),
+            ScreenChange::ViewMoved { id, .. } => moved.push(id.as_str()),
+            ScreenChange::ViewRemoved { id, parent } => removed.push((id.as_str(), parent)),
+            _ => {}
+        }
+    }
+
+    if old.drawer.is_none() && new.drawer.is_some() {
+        operations.push(Operation::AddDrawer {
+            screen: screen.to_owned(),
+        });
+    }
+
+    let mut views = LinkedHashMap::new();
+    collect_views(&new.layout, None, &mut views);
+
+    // the drawer has its own root, so siblings are tracked separately from the layout's
+    for views in [views, drawer_locations(new)] {
+        let mut previous = LinkedHashMap::<Option<String>, String>::new();
+
+        for (id, location) in &views {
+            let after = previous.insert(location.parent.to_owned(), id.to_owned());
+
+            if added.contains(&id.as_str()) {
+                let mut view = location.view.clone();
+                view.id = id.to_owned();
+                view.children.clear();
+
+                operations.push(Operation::AddView {
+                    screen: screen.to_owned(),
+                    parent: location.parent.to_owned(),
+                    after,
+                    view: Box::new(view.to_raw()),
+                });
+            } else if moved.contains(&id.as_str()) {
+                operations.push(Operation::MoveView {
+                    screen: screen.to_owned(),
+                    id: id.to_owned(),
+                    parent: location.parent.to_owned(),
+                    after,
+                });
+            }
+        }
+    }
+
+    // views of a drawer that's removed go along with it
+    let drawer_removed = old.drawer.is_some() && new.drawer.is_none();
+
+    let removed_ids = removed.iter().map(|(id, _)| *id).collect::<Vec<_>>();
+    for (id, parent) in &removed {
+        let parent_removed = parent
+            .as_ref()
+            .map(|parent| removed_ids.contains(&parent.as_str()))
+            .unwrap_or(false);
+
+        let in_removed_drawer = drawer_removed && id.starts_with(DRAWER_PREFIX);
+
+        if !parent_removed && !fab_ids.contains(id) && !in_removed_drawer {
+            operations.push(Operation::RemoveView {
+                screen: screen.to_owned(),
+                id: id.to_string(),
+            });
+        }
+    }
+
+    if drawer_removed {
+        operations.push(Operation::RemoveDrawer {
+            screen: screen.to_owned(),
+        });
+    }
+
+    if old.fab.as_ref().map(|fab| &fab.id) != new.fab.as_ref().map(|fab| &fab.id) {
+        operations.push(Operation::SetFab {
+            screen: screen.to_owned(),
+            old: old.fab.as_ref().map(|fab| Box::new(fab.to_raw())),
+            fab: new.fab.as_ref().map(|fab| Box::new(fab.to_raw())),
+        });
+    }
+
+    for change in changes {
+        match change {
+            ScreenChange::ViewAttributeChanged {
+                id,
+                attribute,
+                old,
+                new,
+            } => operations.push(Operation::SetAttribute {
+                screen: screen.to_owned(),
+                id,
+                attribute,
+                old,
+                new,
+            }),
+
+            ScreenChange::VariableAdded { name, r#type } => {
+                operations.push(Operation::AddVariable {
+                    screen: screen.to_owned(),
+                    name,
+                    r#type,
+                })
+            }
+            ScreenChange::VariableRemoved { name, .. } => {
+                operations.push(Operation::RemoveVariable {
+                    screen: screen.to_owned(),
+                    name,
+                })
+            }
+            ScreenChange::VariableTypeChanged { name, new, .. } => {
+                operations.push(Operation::RemoveVariable {
+                    screen: screen.to_owned(),
+                    name: name.to_owned(),
+                });
+                operations.push(Operation::AddVariable {
+                    screen: screen.to_owned(),
+                    name,
+                    r#type: new,
+                });
+            }
+
+            ScreenChange::ListVariableAdded { name, r#type } => {
+                operations.push(Operation::AddListVariable {
+                    screen: screen.to_owned(),
+                    name,
+                    r#type,
+                })
+            }
+            ScreenChange::ListVariableRemoved { name, .. } => {
+                operations.push(Operation::RemoveListVariable {
+                    screen: screen.to_owned(),
+                    name,
+                })
+            }
+            ScreenChange::ListVariableTypeChanged { name, new, .. } => {
+                operations.push(Operation::RemoveListVariable {
+                    screen: screen.to_owned(),
+                    name: name.to_owned(),
+                });
+                operations.push(Operation::AddListVariable {
+                    screen: screen.to_owned(),
+                    name,
+                    r#type: new,
+                });
+            }
+
+            ScreenChange::ComponentAdded { id, component } => {
+                operations.push(Operation::AddComponent {
+                    screen: screen.to_owned(),
+                    component: component.into_parser_component(id),
+                })
+            }
+            ScreenChange::ComponentRemoved { id, .. } => {
+                operations.push(Operation::RemoveComponent {
+                    screen: screen.to_owned(),
+                    id,
+                })
+            }
+            ScreenChange::ComponentChanged { id, new, .. } => {
+                operations.push(Operation::RemoveComponent {
+                    screen: screen.to_owned(),
+                    id: id.to_owned(),
+                });
+                operations.push(Operation::AddComponent {
+                    screen: screen.to_owned(),
+                    component: new.into_parser_component(id),
+                });
+            }
+
+            ScreenChange::MoreBlockAdded { name } => {
+                let more_block = &new.more_blocks[&name];
+
+                operations.push(Operation::AddMoreBlock {
+                    screen: screen.to_owned(),
+                    name: name.to_owned(),
+                    spec: more_block.spec.to_string(),
+                });
+
+                block_operations(
+                    screen,
+                    &format!("{}_moreBlock", name),
+                    diff_blocks(&Blocks::new(), &more_block.code),
+                    operations,
+                );
+            }
+            ScreenChange::MoreBlockRemoved { name } => {
+                operations.push(Operation::RemoveMoreBlock {
+                    screen: screen.to_owned(),
+                    name,
+                })
+            }
+            ScreenChange::MoreBlockSpecChanged { name, old, new } => {
+                operations.push(Operation::SetMoreBlockSpec {
+                    screen: screen.to_owned(),
+                    name,
+                    old,
+                    new,
+                })
+            }
+            ScreenChange::MoreBlockCodeChanged { name, edits } => {
+                block_operations(screen, &format!("{}_moreBlock", name), edits, operations)
+            }
+
+            ScreenChange::EventAdded { container } => {
+                let event = new
+                    .events
+                    .iter()
+                    .find(|event| event.get_block_container_id() == container)
+                    .expect("an added event must exist in the new screen");
+
+                operations.push(Operation::AddEvent {
+                    screen: screen.to_owned(),
+                    event: event.clone().into_parser_event().0,
+                });
+
+                block_operations(
+                    screen,
+                    &container,
+                    diff_blocks(&Blocks::new(), &event.code),
+                    operations,
+                );
+            }
+            ScreenChange::EventRemoved { container } => operations.push(Operation::RemoveEvent {
+                screen: screen.to_owned(),
+                container,
+            }),
+            ScreenChange::EventCodeChanged { container, edits } => {
+                block_operations(screen, &container, edits, operations)
+            }
+
+            // options are compared using ScreenOption, views are handled above
+            ScreenChange::OptionChanged { .. }
+            | ScreenChange::ViewAdded { .. }
+            | ScreenChange::ViewRemoved { .. }
+            | ScreenChange::ViewMoved { .. } => {}
+        }
+    }
+}
+
+/// Turns block edits into operations. Paths of removed blocks point to the old blocks while the
+/// others point to the new ones, so removals are done first from the last one, then insertions,
+/// then arguments changes
+fn block_operations(
+    screen: &str,
+    container: &str,
+    edits: Vec<BlockEdit>,
+    operations: &mut Vec<Operation>,
+) {
+    let (mut removals, mut others): (Vec<_>, Vec<_>) = edits
+        .into_iter()
+        .partition(|edit| matches!(edit, BlockEdit::Removed { .. }));
+
+    removals.reverse();
+    others.sort_by_key(|edit| matches!(edit, BlockEdit::ArgumentChanged { .. }));
+
+    for edit in removals.into_iter().chain(others) {
+        operations.push(match edit {
+            BlockEdit::Removed { path, block } => Operation::RemoveBlock {
+                screen: screen.to_string(),
+                container: container.to_string(),
+                path,
+                op_code: block.op_code,
+            },
+            BlockEdit::Inserted { path, block } => Operation::InsertBlock {
+                screen: screen.to_string(),
+                container: container.to_string(),
+                path,
+                block: raw_blocks(block),
+            },
+            BlockEdit::ArgumentChanged {
+                path,
+                argument,
+                old,
+                new,
+                ..
+            } => Operation::SetArgument {
+                screen: screen.to_string(),
+                container: container.to_string(),
+                path,
+                argument,
+                old: PatchArgument::from(old.as_ref()),
+                new: PatchArgument::from(new.as_ref()),
+            },
+        });
+    }
+}
+
+fn raw_blocks(block: Block) -> Vec<ParserBlock> {
+    let container: BlockContainer = Blocks(vec![block]).into();
+    container.0
+}
+
+fn from_raw_blocks(raw: &[ParserBlock]) -> Result<Block, PatchOperationError> {
+    let mut blocks = Blocks::try_from(BlockContainer(raw.to_vec()))?;
+
+    if blocks.0.len() != 1 {
+        return Err(PatchOperationError::NotASingleBlock {
+            count: blocks.0.len(),
+        });
+    }
+
+    Ok(blocks.0.remove(0))
+}
+
+impl From<&Argument> for PatchArgument {
+    fn from(argument: &Argument) -> Self {
+        fn convert<T, F>(value: &ArgValue<T>, to_string: F) -> PatchArgument
+        where
+            T: std::fmt::Debug + Clone + PartialEq,
+            F: Fn(&T) -> String,
+        {
+            match value {
+                ArgValue::Value(value) => PatchArgument::Value(to_string(value)),
+                ArgValue::Block(block) => PatchArgument::Block(raw_blocks(block.clone())),
+                ArgValue::BlockPlaceholder { .. } | ArgValue::Empty => PatchArgument::Empty,
+            }
+        }
+
+        match argument {
+            Argument::String { value, .. } | Argument::Menu { value, .. } => {
+                convert(value, ToString::to_string)
+            }
+            Argument::Number { value, .. } => convert(value, ToString::to_string),
+            Argument::Boolean { value, .. } => convert(value, ToString::to_string),
+        }
+    }
+}
+
+impl PatchArgument {
+    /// Sets the value of an argument to this, keeping its type and name
+    fn apply_to(&self, argument: &mut Argument) -> Result<(), PatchOperationError> {
+        fn convert<T, F>(
+            patch: &PatchArgument,
+            parse: F,
+        ) -> Result<ArgValue<T>, PatchOperationError>
+        where
+            T: std::fmt::Debug + Clone + PartialEq,
+            F: Fn(&str) -> Option<T>,
+        {
+            Ok(match patch {
+                PatchArgument::Value(value) => ArgValue::Value(parse(value).ok_or_else(|| {
+                    PatchOperationError::InvalidArgument {
+                        value: value.to_owned(),
+                    }
+                })?),
+                PatchArgument::Block(raw) => ArgValue::Block(from_raw_blocks(raw)?),
+                PatchArgument::Empty => ArgValue::Empty,
+            })
+        }
+
+        match argument {
+            Argument::String { value, .. } | Argument::Menu { value, .. } => {
+                *value = convert(self, |value| Some(value.to_string()))?
+            }
+            Argument::Number { value, .. } => *value = convert(self, |value| value.parse().ok())?,
+            Argument::Boolean { value, .. } => *value = convert(self, |value| value.parse().ok())?,
+        }
+
+        Ok(())
+    }
+}
+
+impl Operation {
+    /// Retrieves the name of this operation as it's named in its serialized form
+    pub fn name(&self) -> &'static str {
+        match self {
+            Operation::SetMetadata { .. } => "set-metadata",
+            Operation::SetColor { .. } => "set-color",
+            Operation::AddResource { .. } => "add-resource",
+            Operation::RemoveResource { .. } => "remove-resource",
+            Operation::AddScreen { .. } => "add-screen",
+            Operation::RemoveScreen { .. } => "remove-screen",
+            Operation::SetScreenOption { .. } => "set-screen-option",
+            Operation::AddView { .. } => "add-view",
+            Operation::RemoveView { .. } => "remove-view",
+            Operation::MoveView { .. } => "move-view",
+            Operation::SetAttribute { .. } => "set-attribute",
+            Operation::SetFab { .. } => "set-fab",
+            Operation::AddDrawer { .. } => "add-drawer",
+            Operation::RemoveDrawer { .. } => "remove-drawer",
+            Operation::AddVariable { .. } => "add-variable",
+            Operation::RemoveVariable { .. } => "remove-variable",
+            Operation::AddListVariable { .. } => "add-list-variable",
+            Operation::RemoveListVariable { .. } => "remove-list-variable",
+            Operation::AddComponent { .. } => "add-component",
+            Operation::RemoveComponent { .. } => "remove-component",
+            Operation::AddMoreBlock { .. } => "add-more-block",
+            Operation::RemoveMoreBlock { .. } => "remove-more-block",
+            Operation::SetMoreBlockSpec { .. } => "set-more-block-spec",
+            Operation::AddEvent { .. } => "add-event",
+            Operation::RemoveEvent { .. } => "remove-event",
+            Operation::InsertBlock { .. } => "insert-block",
+            Operation::RemoveBlock { .. } => "remove-block",
+            Operation::SetArgument { .. } => "set-argument",
+        }
+    }
+
+    /// Applies this operation on a project
+    pub fn apply(&self, project: &mut SketchwareProject) -> Result<(), PatchOperationError> {
+        use PatchOperationError as E;
+
+        fn screen_mut<'a>(
+            project: &'a mut SketchwareProject,
+            name: &str,
+        ) -> Result<&'a mut Screen, PatchOperationError> {
+            project
+                .screens
+                .iter_mut()
+                .find(|screen| screen.layout_name == name)
+                .ok_or_else(|| not_found("screen", name))
+        }
+
+        fn container_mut<'a>(
+            screen: &'a mut Screen,
+            container: &str,
+        ) -> Result<&'a mut Blocks, PatchOperationError> {
+            let blocks = match container.strip_suffix("_moreBlock") {
+                Some(name) => screen
+                    .more_blocks
+                    .get_mut(name)
+                    .map(|more_block| &mut more_block.code),
+                None => screen
+                    .events
+                    .iter_mut()
+                    .find(|event| event.get_block_container_id() == container)
+                    .map(|event| &mut event.code),
+            };
+
+            blocks.ok_or_else(|| not_found("block container", container))
+        }
+
+        /// Puts a view inside `views` right after `after`, or at the start if it's None
+        fn put_view(
+            views: &mut Vec<View>,
+            after: &Option<String>,
+            view: View,
+        ) -> Result<(), PatchOperationError> {
+            let index = match after {
+                None => 0,
+                Some(after) => {
+                    let (_, id) = split_id(after);
+
+                    views
+                        .iter()
+                        .position(|view| view.id == id)
+                        .ok_or_else(|| not_found("view", after))?
+                        + 1
+                }
+            };
+
+            views.insert(index, view);
+            Ok(())
+        }
+
+        /// Splits the id of a view into whether it's in the drawer and its id in its layout
+        fn split_id(id: &str) -> (bool, &str) {
+            match id.strip_prefix(DRAWER_PREFIX) {
+                Some(id) => (true, id),
+                None => (false, id),
+            }
+        }
+
+        /// Retrieves the root views of the layout or the drawer of a screen
+        fn root_mut(
+            screen: &mut Screen,
+            drawer: bool,
+        ) -> Result<&mut Vec<View>, PatchOperationError> {
+            if !drawer {
+                return Ok(&mut screen.layout);
+            }
+
+            let name = screen.layout_name.to_owned();
+            screen
+                .drawer
+                .as_mut()
+                .map(|drawer| &mut drawer.layout)
+                .ok_or_else(|| not_found("drawer", &name))
+        }
+
+        fn children_mut<'a>(
+            screen: &'a mut Screen,
+            parent: &Option<String>,
+            drawer: bool,
+        ) -> Result<&'a mut Vec<View>, PatchOperationError> {
+            match parent {
+                None => root_mut(screen, drawer),
+                Some(parent) => screen
+                    .find_view_mut(parent)
+                    .map(|view| &mut view.children)
+                    .ok_or_else(|| not_found("view", parent)),
+            }
+        }
+
+        match self {
+            Operation::SetMetadata { field, old, new } => {
+                let current = project
+                    .metadata
+                    .get_field(field)
+                    .ok_or_else(|| not_found("metadata field", field))?;
+
+                check(field, old, &current)?;
+
+                if !project.metadata.set_field(field, new) {
+                    return Err(E::InvalidValue {
+                        target: field.to_owned(),
+                        value: new.to_owned(),
+                    });
+                }
+            }
+            Operation::SetColor { field, old, new } => {
+                let current = project
+                    .colors
+                    .get_field(field)
+                    .ok_or_else(|| not_found("color", field))?;
+
+                check(field, old, &current)?;
+                project.colors.set_field(field, *new);
+            }
+
+            Operation::AddResource { res_type, id, file } => {
+                let resources = resources_mut(project, *res_type)?;
+                if resources.contains_key(id) {
+                    return Err(already_exists("resource", &id.0));
+                }
+
+                resources.insert(id.to_owned(), file.clone());
+            }
+            Operation::RemoveResource { res_type, id } => {
+                resources_mut(project, *res_type)?
+                    .remove(id)
+                    .ok_or_else(|| not_found("resource", &id.0))?;
+            }
+
+            Operation::AddScreen { screen, java_name } => {
+                if project.screens.iter().any(|s| &s.layout_name == screen) {
+                    return Err(already_exists("screen", screen));
+                }
+
+                project
+                    .screens
+                    .push(Screen::new_empty(screen.to_owned(), java_name.to_owned()));
+            }
+            Operation::RemoveScreen { screen } => {
+                let index = project
+                    .screens
+                    .iter()
+                    .position(|s| &s.layout_name == screen)
+                    .ok_or_else(|| not_found("screen", screen))?;
+
+                project.screens.remove(index);
+            }
+            Operation::SetScreenOption { screen, old, new } => {
+                let screen = screen_mut(project, screen)?;
+                let current = ScreenOption::all(screen)
+                    .into_iter()
+                    .find(|option| std::mem::discriminant(option) == std::mem::discriminant(old))
+                    .expect("every option of a screen is listed");
+
+                check(&screen.layout_name, old, &current)?;
+                new.apply(screen);
+            }
+
+            Operation::AddView {
+                screen,
+                parent,
+                after,
+                view,
+            } => {
+                let screen = screen_mut(project, screen)?;
+                if screen.find_view(&view.id).is_some() {
+                    return Err(already_exists("view", &view.id));
+                }
+
+                let (drawer, id) = split_id(&view.id);
+                let mut view = View::from(view.as_ref().clone());
+                view.id = id.to_owned();
+                view.children.clear();
+
+                put_view(children_mut(screen, parent, drawer)?, after, view)?;
+            }
+            Operation::RemoveView { screen, id } => {
+                let (drawer, layout_id) = split_id(id);
+
+                take_view(root_mut(screen_mut(project, screen)?, drawer)?, layout_id)
+                    .ok_or_else(|| not_found("view", id))?;
+            }
+            Operation::MoveView {
+                screen,
+                id,
+                parent,
+                after,
+            } => {
+                let screen = screen_mut(project, screen)?;
+                let (drawer, layout_id) = split_id(id);
+                let view = take_view(root_mut(screen, drawer)?, layout_id)
+                    .ok_or_else(|| not_found("view", id))?;
+
+                put_view(children_mut(screen, parent, drawer)?, after, view)?;
+            }
+            Operation::SetAttribute {
+                screen,
+                id,
+                attribute,
+                old,
+                new,
+            } => {
+                let view = screen_mut(project, screen)?
+                    .find_view_mut(id)
+                    .ok_or_else(|| not_found("view", id))?;
+
+                let current = view
+                    .attributes()
+                    .get(attribute)
+                    .cloned()
+                    .unwrap_or(serde_json::Value::Null);
+
+                check(&format!("{}.{}", id, attribute), old, &current)?;
+                view.set_attribute(attribute, new.clone())?;
+            }
+            Operation::SetFab { screen, old, fab } => {
+                let screen = screen_mut(project, screen)?;
+                let current = screen.fab.as_ref().map(|fab| Box::new(fab.to_raw()));
+
+                check(&format!("{}.fab", screen.layout_name), old, &current)?;
+                screen.fab = fab.as_ref().map(|fab| View::from(fab.as_ref().clone()));
+            }
+            Operation::AddDrawer { screen } => {
+                let screen = screen_mut(project, screen)?;
+                if screen.drawer.is_some() {
+                    return Err(already_exists("drawer", &screen.layout_name));
+                }
+
+                screen.drawer = Some(Drawer::default());
+            }
+            Operation::RemoveDrawer { screen } => {
+                let screen = screen_mut(project, screen)?;
+                if screen.drawer.take().is_none() {
+                    return Err(not_found("drawer", &screen.layout_name));
+                }
+            }
+
+            Operation::AddVariable {
+                screen,
+                name,
+                r#type,
+            } => {
+                let variables = &mut screen_mut(project, screen)?.variables;
+                if variables.contains_key(name) {
+                    return Err(already_exists("variable", name));
+                }
+
+                variables.insert(
+                    name.to_owned(),
+                    Variable {
+                        name: name.to_owned(),
+                        r#type: *r#type,
+                    },
+                );
+            }
+            Operation::RemoveVariable { screen, name } => {
+                screen_mut(project, screen)?
+                    .variables
+                    .remove(name)
+                    .ok_or_else(|| not_found("variable", name))?;
+            }
+            Operation::AddListVariable {
+                screen,
+                name,
+                r#type,
+            } => {
+                let lists = &mut screen_mut(project, screen)?.list_variables;
+                if lists.contains_key(name) {
+                    return Err(already_exists("list variable", name));
+                }
+
+                lists.insert(
+                    name.to_owned(),
+                    ListVariable {
+                        name: name.to_owned(),
+                        r#type: *r#type,
+                    },
+                );
+            }
+            Operation::RemoveListVariable { screen, name } => {
+                screen_mut(project, screen)?
+                    .list_variables
+                    .remove(name)
+                    .ok_or_else(|| not_found("list variable", name))?;
+            }
+
+            Operation::AddComponent { screen, component } => {
+                let components = &mut screen_mut(project, screen)?.components;
+                if components.contains_key(&component.id) {
+                    return Err(already_exists("component", &component.id));
+                }
+
+                components.insert(
+                    component.id.to_owned(),
+                    ComponentKind::from_parser_component(component),
+                );
+            }
+            Operation::RemoveComponent { screen, id } => {
+                screen_mut(project, screen)?
+                    .components
+                    .remove(id)
+                    .ok_or_else(|| not_found("component", id))?;
+            }
+
+            Operation::AddMoreBlock { screen, name, spec } => {
+                let more_blocks = &mut screen_mut(project, screen)?.more_blocks;
+                if more_blocks.contains_key(name) {
+                    return Err(already_exists("moreblock", name));
+                }
+
+                more_blocks.insert(
+                    name.to_owned(),
+                    MoreBlock {
+                        name: name.to_owned(),
+                        spec: BlockContent::parse_wo_params(spec)?,
+                        code: Blocks::new(),
+                    },
+                );
+            }
+            Operation::RemoveMoreBlock { screen, name } => {
+                screen_mut(project, screen)?
+                    .more_blocks
+                    .remove(name)
+                    .ok_or_else(|| not_found("moreblock", name))?;
+            }
+            Operation::SetMoreBlockSpec {
+                screen,
+                name,
+                old,
+                new,
+            } => {
+                let more_block = screen_mut(project, screen)?
+                    .more_blocks
+                    .get_mut(name)
+                    .ok_or_else(|| not_found("moreblock", name))?;
+
+                check(name, old, &more_block.spec.to_string())?;
+                more_block.spec = BlockContent::parse_wo_params(new)?;
+            }
+
+            Operation::AddEvent { screen, event } => {
+                let event = Event::try_from(event.clone())?;
+                let container = event.get_block_container_id();
+                let events = &mut screen_mut(project, screen)?.events;
+
+                if events
+                    .iter()
+                    .any(|event| event.get_block_container_id() == container)
+                {
+                    return Err(already_exists("event", &container));
+                }
+
+                events.push(event);
+            }
+            Operation::RemoveEvent { screen, container } => {
+                let events = &mut screen_mut(project, screen)?.events;
+                let index = events
+                    .iter()
+                    .position(|event| &event.get_block_container_id() == container)
+                    .ok_or_else(|| not_found("event", container))?;
+
+                events.remove(index);
+            }
+
+            Operation::InsertBlock {
+                screen,
+                container,
+                path,
+                block,
+            } => {
+                let block = from_raw_blocks(block)?;
+
+                if !container_mut(screen_mut(project, screen)?, container)?.insert(path, block) {
+                    return Err(not_found("block position", &path.to_string()));
+                }
+            }
+            Operation::RemoveBlock {
+                screen,
+                container,
+                path,
+                op_code,
+            } => {
+                let blocks = container_mut(screen_mut(project, screen)?, container)?;
+                let block = blocks
+                    .get(path)
+                    .ok_or_else(|| not_found("block", &path.to_string()))?;
+
+                check(&path.to_string(), op_code, &block.op_code)?;
+                blocks.remove(path);
+            }
+            Operation::SetArgument {
+                screen,
+                container,
+                path,
+                argument,
+                old,
+                new,
+            } => {
+                let target = format!("{}#{}", path, argument);
+                let block = container_mut(screen_mut(project, screen)?, container)?
+                    .get_mut(path)
+                    .ok_or_else(|| not_found("block", &path.to_string()))?;
+
+                let argument = block
+                    .content
+                    .get_args_mut()
+                    .into_iter()
+                    .nth(*argument)
+                    .ok_or_else(|| not_found("argument", &target))?;
+
+                check(&target, old, &PatchArgument::from(&*argument))?;
+                new.apply_to(argument)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn resources_mut(
+    project: &mut SketchwareProject,
+    res_type: ResourceType,
+) -> Result<&mut LinkedHashMap<ResourceId, ResourceFileWrapper>, PatchOperationError> {
+    match res_type {
+        ResourceType::Image => Ok(&mut project.resources.images),
+        ResourceType::Sound => Ok(&mut project.resources.sounds),
+        ResourceType::Font => Ok(&mut project.resources.fonts),
+        ResourceType::CustomIcon => Err(PatchOperationError::UnsupportedResourceType { res_type }),
+    }
+}
+
+fn not_found(kind: &'static str, name: &str) -> PatchOperationError {
+    PatchOperationError::NotFound {
+        kind,
+        name: name.to_string(),
+    }
+}
+
+fn already_exists(kind: &'static str, name: &str) -> PatchOperationError {
+    PatchOperationError::AlreadyExists {
+        kind,
+        name: name.to_string(),
+    }
+}
+
+/// Checks whether the current value of the target is the value the operation expects
+fn check<T: PartialEq + std::fmt::Debug>(
+    target: &str,
+    expected: &T,
+    found: &T,
+) -> Result<(), PatchOperationError> {
+    if expected == found {
+        Ok(())
+    } else {
+        Err(PatchOperationError::Mismatch {
+            target: target.to_string(),
+            expected: format!("{:?}", expected),
+            found: format!("{:?}", found),
+        })
+    }
+}
+
+#[derive(Error, Debug)]
+#[error("couldn't apply operation #{index} ({operation}) of the patch")]
+pub struct PatchError {
+    /// The index of the operation that failed
+    pub index: usize,
+    pub operation: &'static str,
+
+    #[source]
+    pub source: PatchOperationError,
+}
+
+#[derive(Error, Debug)]
+pub enum PatchOperationError {
+    #[error("there is no {kind} `{name}`")]
+    NotFound { kind: &'static str, name: String },
+
+    #[error("{kind} `{name}` already exists")]
+    AlreadyExists { kind: &'static str, name: String },
+
+    #[error("expected `{target}` to be {expected}, but found {found}")]
+    Mismatch {
+        target: String,
+        expected: String,
+        found: String,
+    },
+
+    #[error("`{value}` is not a valid value of `{target}`")]
+    InvalidValue { target: String, value: String },
+
+    #[error("`{value}` is not a valid value for the argument")]
+    InvalidArgument { value: String },
+
+    #[error("expected a single block, found {count} blocks")]
+    NotASingleBlock { count: usize },
+
+    #[error("resources of type {res_type:?} can't be patched")]
+    UnsupportedResourceType { res_type: ResourceType },
+
+    #[error("invalid blocks")]
+    InvalidBlocks(#[from] BlockConversionError),
+
+    #[error("invalid moreblock spec")]
+    InvalidSpec(#[from] BlockContentParseError),
+
+    #[error("invalid event")]
+    InvalidEvent(#[from] UnknownEventType),
+
+    #[error("couldn't set the attribute of a view")]
+    SetAttribute(#[from] SetAttributeError),
+}
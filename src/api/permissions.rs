@@ -0,0 +1,260 @@
+//! Infers the android permissions a project needs. [`PermissionReport::infer`] goes through the
+//! components, the views and the blocks of every screen along with the views of every custom view,
+//! and records what caused each permission so users can find out why their app asks for it. The
+//! result can be emitted as the `<uses-permission>` entries of a manifest with
+//! [`PermissionReport::to_manifest`].
+
+use crate::api::block::BlockPath;
+use crate::api::view::{walk, ViewType};
+use crate::api::SketchwareProject;
+use std::fmt::{Display, Formatter};
+
+const INTERNET: &str = "android.permission.INTERNET";
+const ACCESS_NETWORK_STATE: &str = "android.permission.ACCESS_NETWORK_STATE";
+const READ_EXTERNAL_STORAGE: &str = "android.permission.READ_EXTERNAL_STORAGE";
+const WRITE_EXTERNAL_STORAGE: &str = "android.permission.WRITE_EXTERNAL_STORAGE";
+
+/// What caused a permission to be needed, screens are identified by their layout names
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Reason {
+    Component {
+        screen: String,
+        id: String,
+
+        /// The name of the component kind
+        kind: &'static str,
+    },
+
+    View {
+        screen: String,
+
+        /// The id of the view, views of the drawer have their ids prefixed with
+        /// [`DRAWER_PREFIX`](crate::api::screen::DRAWER_PREFIX)
+        id: String,
+
+        /// The name of the view type
+        view_type: &'static str,
+    },
+
+    /// A view inside of a custom view, like the layout of a ListView item
+    CustomView {
+        /// The name of the custom view
+        custom_view: String,
+        id: String,
+
+        /// The name of the view type
+        view_type: &'static str,
+    },
+
+    Block {
+        screen: String,
+
+        /// The block container id of the event or the moreblock the block is in
+        container: String,
+        path: BlockPath,
+        op_code: String,
+    },
+}
+
+impl Display for Reason {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Reason::Component { screen, id, kind } => {
+                write!(f, "{}, {} component `{}`", screen, kind, id)
+            }
+            Reason::View {
+                screen,
+                id,
+                view_type,
+            } => write!(f, "{}, {} `{}`", screen, view_type, id),
+            Reason::CustomView {
+                custom_view,
+                id,
+                view_type,
+            } => write!(f, "custom view {}, {} `{}`", custom_view, view_type, id),
+            Reason::Block {
+                screen,
+                container,
+                path,
+                op_code,
+            } => write!(
+                f,
+                "{}, block `{}` in {} at {}",
+                screen, op_code, container, path
+            ),
+        }
+    }
+}
+
+/// A permission along with everything that causes it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Permission {
+    /// The full name of the permission, like `android.permission.INTERNET`
+    pub name: String,
+    pub reasons: Vec<Reason>,
+}
+
+/// The permissions a project needs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PermissionReport {
+    /// Permissions in the order they're first found
+    pub permissions: Vec<Permission>,
+}
+
+impl PermissionReport {
+    /// Infers the permissions of a project from its components (see
+    /// [`ComponentKind::permissions`](crate::api::component::ComponentKind::permissions)), its
+    /// views including the ones in drawers and custom views (MapView, AdView and WebView need
+    /// internet access) and its blocks (like file and network blocks)
+    pub fn infer(project: &SketchwareProject) -> PermissionReport {
+        let mut report = PermissionReport {
+            permissions: vec![],
+        };
+
+        for screen in &project.screens {
+            for (id, component) in &screen.components {
+                for permission in component.permissions() {
+                    report.add(
+                        permission,
+                        Reason::Component {
+                            screen: screen.layout_name.to_owned(),
+                            id: id.to_owned(),
+                            kind: component.name(),
+                        },
+                    );
+                }
+            }
+
+            for (id, view) in screen.all_views() {
+                let view_type = match &view.view {
+                    Ok(view_type) => view_type,
+                    Err(_) => continue,
+                };
+
+                for permission in view_permissions(view_type) {
+                    report.add(
+                        permission,
+                        Reason::View {
+                            screen: screen.layout_name.to_owned(),
+                            id: id.to_owned(),
+                            view_type: view_type.name(),
+                        },
+                    );
+                }
+            }
+
+            for (container, blocks) in screen.block_containers() {
+                blocks.visit(|path, block| {
+                    for permission in block_permissions(&block.op_code) {
+                        report.add(
+                            permission,
+                            Reason::Block {
+                                screen: screen.layout_name.to_owned(),
+                                container: container.to_owned(),
+                                path: path.to_owned(),
+                                op_code: block.op_code.to_owned(),
+                            },
+                        );
+                    }
+                });
+            }
+        }
+
+        for custom_view in &project.custom_views {
+            for view in walk(&custom_view.layout) {
+                let view_type = match &view.view {
+                    Ok(view_type) => view_type,
+                    Err(_) => continue,
+                };
+
+                for permission in view_permissions(view_type) {
+                    report.add(
+                        permission,
+                        Reason::CustomView {
+                            custom_view: custom_view.res_name.to_owned(),
+                            id: view.id.to_owned(),
+                            view_type: view_type.name(),
+                        },
+                    );
+                }
+            }
+        }
+
+        report
+    }
+
+    /// Retrieves a permission using its full name
+    pub fn get(&self, name: &str) -> Option<&Permission> {
+        self.permissions
+            .iter()
+            .find(|permission| permission.name == name)
+    }
+
+    /// Retrieves the full names of every permission
+    pub fn names(&self) -> Vec<&str> {
+        self.permissions
+            .iter()
+            .map(|permission| permission.name.as_str())
+            .collect()
+    }
+
+    /// Generates the `<uses-permission>` entries of an android manifest
+    pub fn to_manifest(&self) -> String {
+        self.permissions
+            .iter()
+            .map(|permission| format!("<uses-permission android:name=\"{}\" />\n", permission.name))
+            .collect()
+    }
+
+    /// Generates a human readable explanation of why each permission is needed
+    pub fn explain(&self) -> String {
+        let mut result = String::new();
+
+        for permission in &self.permissions {
+            result.push_str(&permission.name);
+            result.push('\n');
+
+            for reason in &permission.reasons {
+                result.push_str(&format!("  - {}\n", reason));
+            }
+        }
+
+        result
+    }
+
+    fn add(&mut self, name: &str, reason: Reason) {
+        match self
+            .permissions
+            .iter_mut()
+            .find(|permission| permission.name == name)
+        {
+            Some(permission) => permission.reasons.push(reason),
+            None => self.permissions.push(Permission {
+                name: name.to_string(),
+                reasons: vec![reason],
+            }),
+        }
+    }
+}
+
+/// Retrieves the permissions a view type needs
+fn view_permissions(view_type: &ViewType) -> &'static [&'static str] {
+    match view_type {
+        ViewType::MapView | ViewType::AdView { .. } => &[INTERNET, ACCESS_NETWORK_STATE],
+        ViewType::WebView => &[INTERNET],
+        _ => &[],
+    }
+}
+
+/// Retrieves the permissions a block needs on its own, blocks of components aren't included as
+/// the components themselves already need them
+fn block_permissions(op_code: &str) -> &'static [&'static str] {
+    match op_code {
+        "setImageUrl" | "webViewLoadUrl" => &[INTERNET],
+        "setImageFilePath" | "fileutilread" | "fileutilisexist" | "fileutillistdir"
+        | "fileutilisdir" | "fileutilisfile" | "fileutillength" => &[READ_EXTERNAL_STORAGE],
+        "fileutilwrite" | "fileutilcopy" | "fileutilmove" | "fileutildelete"
+        | "fileutilmakedir" => &[READ_EXTERNAL_STORAGE, WRITE_EXTERNAL_STORAGE],
+        _ => &[],
+    }
+}
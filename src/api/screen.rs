@@ -1,6 +1,6 @@
 use crate::api::block::{BlockContent, BlockContentParseError, BlockConversionError, Blocks};
-use crate::api::component::{ComponentKind, UnknownComponentType};
-use crate::api::view::{parse_raw_layout, ParseLayoutError, View};
+use crate::api::component::ComponentKind;
+use crate::api::view::{parse_raw_layout, walk, ParseLayoutError, View};
 use crate::parser::file::{FileItem, KeyboardSetting, Orientation, Theme};
 use crate::parser::logic::event::EventPool;
 use crate::parser::logic::list_variable::ListVariable;
@@ -42,6 +42,9 @@ pub struct Screen {
     /// The fab of this view (if exists)
     pub fab: Option<View>,
 
+    /// The navigation drawer of this screen (if exists), see [`Drawer`]
+    pub drawer: Option<Drawer>,
+
     pub fullscreen_enabled: bool,
     pub toolbar_enabled: bool,
     pub drawer_enabled: bool,
@@ -52,6 +55,37 @@ pub struct Screen {
     pub keyboard_setting: KeyboardSetting,
 }
 
+/// The prefix of the ids of drawer views when they're referred to by the logic of their screen,
+/// and of the layout name of the drawer itself (`_drawer_main` for the screen `main`)
+pub const DRAWER_PREFIX: &str = "_drawer_";
+
+/// A model that represents the navigation drawer of a screen. Sketchware stores it as a separate
+/// layout named `_drawer_<screen>`, but its events are stored along with the other events of its
+/// screen, where the ids of its views are prefixed with [`DRAWER_PREFIX`]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Drawer {
+    /// The root views of the drawer layout
+    pub layout: Vec<View>,
+}
+
+impl Drawer {
+    /// Finds a view with the given id (without the `_drawer_` prefix) in this drawer
+    pub fn find_view(&self, id: &str) -> Option<&View> {
+        self.layout.iter().find_map(|view| view.find_id(id))
+    }
+
+    /// Finds a view with the given id (without the `_drawer_` prefix) in this drawer as a mutable
+    /// reference
+    pub fn find_view_mut(&mut self, id: &str) -> Option<&mut View> {
+        self.layout.iter_mut().find_map(|view| view.find_id_mut(id))
+    }
+
+    /// Retrieves every view of this drawer in pre-order
+    pub fn views(&self) -> Vec<&View> {
+        walk(&self.layout)
+    }
+}
+
 /// A model that represents a moreblock
 #[derive(Debug, Clone, PartialEq)]
 pub struct MoreBlock {
@@ -181,6 +215,29 @@ pub struct UnknownEventType {
 }
 
 impl Screen {
+    /// Creates an empty screen with the default options of a newly created screen in sketchware
+    pub fn new_empty(layout_name: String, java_name: String) -> Self {
+        Screen {
+            layout_name,
+            java_name,
+            layout: vec![],
+            variables: LinkedHashMap::new(),
+            list_variables: LinkedHashMap::new(),
+            more_blocks: LinkedHashMap::new(),
+            components: LinkedHashMap::new(),
+            events: vec![],
+            fab: None,
+            drawer: None,
+            fullscreen_enabled: false,
+            toolbar_enabled: true,
+            drawer_enabled: false,
+            fab_enabled: false,
+            orientation: Orientation::Portrait,
+            theme: Theme::None,
+            keyboard_setting: KeyboardSetting::Unspecified,
+        }
+    }
+
     pub fn from_parsed(
         layout_name: String,
         logic_name: String,
@@ -188,6 +245,7 @@ impl Screen {
         view_entry: ViewScreen,
         mut logic_entry: ScreenLogic,
         fab: Option<View>,
+        drawer: Option<ViewScreen>,
     ) -> Result<Self, ScreenConstructionError> {
         // onCreate is special (i hate this), its not defined in events, but can appear as a block
         // container.
@@ -266,12 +324,8 @@ impl Screen {
                 .unwrap_or_default()
                 .0
                 .into_iter()
-                .map(|cmp| {
-                    let id = cmp.id.clone();
-                    ComponentKind::from_parser_component(&cmp).map(|cmp| ((id, cmp)))
-                })
-                .collect::<Result<LinkedHashMap<String, ComponentKind>, UnknownComponentType>>()
-                .map_err(ScreenConstructionError::UnknownComponentType)?,
+                .map(|cmp| (cmp.id.clone(), ComponentKind::from_parser_component(&cmp)))
+                .collect(),
 
             events: logic_entry
                 .events
@@ -299,6 +353,14 @@ impl Screen {
                 .collect::<Result<Vec<Event>, ScreenConstructionError>>()?,
 
             fab,
+            drawer: drawer
+                .map(|layout| {
+                    Ok::<_, ScreenConstructionError>(Drawer {
+                        layout: parse_raw_layout(layout)
+                            .map_err(ScreenConstructionError::DrawerLayoutParseError)?,
+                    })
+                })
+                .transpose()?,
             fullscreen_enabled: file_entry.options.fullscreen_enabled,
             toolbar_enabled: file_entry.options.toolbar_enabled,
             drawer_enabled: file_entry.options.drawer_enabled,
@@ -308,6 +370,97 @@ impl Screen {
             keyboard_setting: file_entry.keyboard_setting,
         })
     }
+
+    /// Finds a view with the given id in the layout of this screen (including its fab). Ids that
+    /// start with [`DRAWER_PREFIX`] are looked up in the drawer, like how events refer to them
+    pub fn find_view(&self, id: &str) -> Option<&View> {
+        if let Some(id) = id.strip_prefix(DRAWER_PREFIX) {
+            return self.drawer.as_ref()?.find_view(id);
+        }
+
+        self.layout
+            .iter()
+            .chain(self.fab.iter())
+            .find_map(|view| view.find_id(id))
+    }
+
+    /// Finds a view with the given id in the layout of this screen (including its fab) as a
+    /// mutable reference. Ids that start with [`DRAWER_PREFIX`] are looked up in the drawer
+    pub fn find_view_mut(&mut self, id: &str) -> Option<&mut View> {
+        if let Some(id) = id.strip_prefix(DRAWER_PREFIX) {
+            return self.drawer.as_mut()?.find_view_mut(id);
+        }
+
+        self.layout
+            .iter_mut()
+            .chain(self.fab.iter_mut())
+            .find_map(|view| view.find_id_mut(id))
+    }
+
+    /// Retrieves every view of this screen in pre-order, including its fab. Views of the drawer
+    /// aren't included, see [`Screen::all_views`]
+    pub fn views(&self) -> Vec<&View> {
+        let mut result = walk(&self.layout);
+        result.extend(self.fab.iter());
+        result
+    }
+
+    /// Retrieves every view of this screen (see [`Screen::views`]) followed by the views of its
+    /// drawer, along with the ids the logic of this screen refers to them with. The ids of the
+    /// drawer views are prefixed with [`DRAWER_PREFIX`].
+    pub fn all_views(&self) -> Vec<(String, &View)> {
+        let drawer = self.drawer.iter().flat_map(|drawer| drawer.views());
+
+        self.views()
+            .into_iter()
+            .map(|view| (view.id.to_owned(), view))
+            .chain(drawer.map(|view| (format!("{}{}", DRAWER_PREFIX, view.id), view)))
+            .collect()
+    }
+
+    /// Retrieves the blocks of every event followed by every moreblock of this screen, along with
+    /// their block container ids (like `button1_onClick` or `{name}_moreBlock`)
+    pub fn block_containers(&self) -> Vec<(String, &Blocks)> {
+        self.events
+            .iter()
+            .map(|event| (event.get_block_container_id(), &event.code))
+            .chain(
+                self.more_blocks
+                    .iter()
+                    .map(|(name, more_block)| (format!("{}_moreBlock", name), &more_block.code)),
+            )
+            .collect()
+    }
+
+    /// The layout name of the drawer of this screen, like `_drawer_main`
+    pub fn drawer_layout_name(&self) -> String {
+        format!("{}{}", DRAWER_PREFIX, self.layout_name)
+    }
+
+    /// Retrieves the events of the views inside the drawer of this screen
+    pub fn drawer_events(&self) -> Vec<&Event> {
+        self.events
+            .iter()
+            .filter(|event| is_drawer_event(event))
+            .collect()
+    }
+
+    /// Enables or disables the drawer of this screen. Enabling it creates an empty drawer if
+    /// there isn't one already, disabling it removes the drawer along with its events
+    pub fn set_drawer_enabled(&mut self, enabled: bool) {
+        self.drawer_enabled = enabled;
+
+        if enabled {
+            self.drawer.get_or_insert_with(Drawer::default);
+        } else {
+            self.drawer = None;
+            self.events.retain(|event| !is_drawer_event(event));
+        }
+    }
+}
+
+fn is_drawer_event(event: &Event) -> bool {
+    matches!(&event.event_type, EventType::ViewEvent { id } if id.starts_with(DRAWER_PREFIX))
 }
 
 #[derive(Error, Debug)]
@@ -327,9 +480,9 @@ pub enum ScreenConstructionError {
     #[error("{0}")]
     UnknownEventType(#[from] UnknownEventType),
 
-    #[error("{0}")]
-    UnknownComponentType(#[from] UnknownComponentType),
-
     #[error("error while parsing the layout: `{0:?}`")]
     LayoutParseError(#[from] ParseLayoutError),
+
+    #[error("error while parsing the drawer layout: `{0:?}`")]
+    DrawerLayoutParseError(ParseLayoutError),
 }
@@ -0,0 +1,406 @@
+//! Finds secrets in projects before they get shared. [`scan`] looks through the libraries of a
+//! project (Firebase, AdMob and Google Maps) and the string arguments of every block for things
+//! that look like api keys, ad unit ids and urls, [`anonymize`] replaces them with placeholders.
+//!
+//! Strings of blocks are searched for patterns rather than compared whole, so a key hard-coded
+//! inside an `addSourceDirectly` block is found too, and only the key gets replaced.
+
+use crate::api::block::{ArgValue, Argument, BlockPath, Blocks};
+use crate::api::SketchwareProject;
+use std::fmt::{Display, Formatter};
+use std::ops::Range;
+
+/// An AdMob ad unit id that Google provides for testing
+const TEST_AD_UNIT_ID: &str = "ca-app-pub-3940256099942544/6300978111";
+const API_KEY: &str = "YOUR_API_KEY";
+const APP_ID: &str = "1:000000000000:android:0000000000000000";
+const PROJECT_ID: &str = "your-project-id";
+const STORAGE_BUCKET: &str = "your-project-id.appspot.com";
+const TEST_DEVICE: &str = "YOUR_TEST_DEVICE_ID";
+const URL: &str = "https://example.com";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SecretKind {
+    /// A Google api key (like the Firebase or the Google Maps api key)
+    ApiKey,
+
+    /// A Firebase app id
+    AppId,
+
+    /// A Firebase project id
+    ProjectId,
+
+    /// A Firebase storage bucket
+    StorageBucket,
+
+    /// An AdMob ad unit or app id
+    AdUnitId,
+
+    /// An AdMob test device id
+    TestDevice,
+
+    /// An url, like the ones given to RequestNetwork
+    Url,
+}
+
+impl SecretKind {
+    /// The value a secret of this kind gets replaced with in [`anonymize`]
+    pub fn placeholder(&self) -> &'static str {
+        match self {
+            SecretKind::ApiKey => API_KEY,
+            SecretKind::AppId => APP_ID,
+            SecretKind::ProjectId => PROJECT_ID,
+            SecretKind::StorageBucket => STORAGE_BUCKET,
+            SecretKind::AdUnitId => TEST_AD_UNIT_ID,
+            SecretKind::TestDevice => TEST_DEVICE,
+            SecretKind::Url => URL,
+        }
+    }
+}
+
+impl Display for SecretKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            SecretKind::ApiKey => "api key",
+            SecretKind::AppId => "app id",
+            SecretKind::ProjectId => "project id",
+            SecretKind::StorageBucket => "storage bucket",
+            SecretKind::AdUnitId => "ad unit id",
+            SecretKind::TestDevice => "test device id",
+            SecretKind::Url => "url",
+        })
+    }
+}
+
+/// Where a secret is found
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SecretLocation {
+    /// A field of a library, like `firebase.api_key` or `ad_mob.ad_units[0]`
+    Library { field: String },
+
+    /// A string argument of a block
+    Block {
+        /// The layout name of the screen
+        screen: String,
+
+        /// The block container id of the event or the moreblock the block is in
+        container: String,
+        path: BlockPath,
+        argument: usize,
+    },
+}
+
+impl Display for SecretLocation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SecretLocation::Library { field } => write!(f, "library field `{}`", field),
+            SecretLocation::Block {
+                screen,
+                container,
+                path,
+                argument,
+            } => write!(
+                f,
+                "{}, {} at {} (argument {})",
+                screen, container, path, argument
+            ),
+        }
+    }
+}
+
+/// Something that looks like a secret
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Secret {
+    pub kind: SecretKind,
+    pub location: SecretLocation,
+    pub value: String,
+}
+
+/// What else [`anonymize`] should replace other than secrets
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AnonymizeOptions {
+    /// Replaces the package name with `com.example.app`
+    pub package_name: bool,
+
+    /// Replaces the app name with `App`
+    pub app_name: bool,
+
+    /// Replaces the workspace name with `App`
+    pub workspace_name: bool,
+}
+
+impl AnonymizeOptions {
+    /// Replaces everything
+    pub fn all() -> Self {
+        AnonymizeOptions {
+            package_name: true,
+            app_name: true,
+            workspace_name: true,
+        }
+    }
+}
+
+/// Finds the secrets of a project, in the libraries then in the blocks of every screen.
+/// Placeholders that [`anonymize`] puts aren't reported.
+pub fn scan(project: &SketchwareProject) -> Vec<Secret> {
+    let mut result: Vec<Secret> = library_fields(project)
+        .into_iter()
+        .filter(|(kind, _, value)| !value.is_empty() && *value != kind.placeholder())
+        .map(|(kind, field, value)| Secret {
+            kind,
+            location: SecretLocation::Library { field },
+            value: value.to_string(),
+        })
+        .collect();
+
+    for screen in &project.screens {
+        for (container, blocks) in screen.block_containers() {
+            blocks.visit(|path, block| {
+                for (index, arg) in block.content.get_args().into_iter().enumerate() {
+                    let value = match arg {
+                        Argument::String {
+                            value: ArgValue::Value(value),
+                            ..
+                        } => value,
+                        _ => continue,
+                    };
+
+                    for (kind, range) in find_secrets(value) {
+                        result.push(Secret {
+                            kind,
+                            location: SecretLocation::Block {
+                                screen: screen.layout_name.to_owned(),
+                                container: container.to_owned(),
+                                path: path.to_owned(),
+                                argument: index,
+                            },
+                            value: value[range].to_string(),
+                        });
+                    }
+                }
+            });
+        }
+    }
+
+    result
+}
+
+/// Creates a copy of a project with every secret [`scan`] finds replaced by a placeholder (see
+/// [`SecretKind::placeholder`]), the metadata is replaced according to the options.
+pub fn anonymize(project: &SketchwareProject, options: &AnonymizeOptions) -> SketchwareProject {
+    let mut project = project.clone();
+
+    if let Some(firebase) = &mut project.libraries.firebase {
+        replace_non_empty(&mut firebase.project_id, PROJECT_ID);
+        replace_non_empty(&mut firebase.app_id, APP_ID);
+        replace_non_empty(&mut firebase.api_key, API_KEY);
+        replace_non_empty(&mut firebase.storage_bucket, STORAGE_BUCKET);
+    }
+
+    if let Some(ad_mob) = &mut project.libraries.ad_mob {
+        for ad_unit in &mut ad_mob.ad_units {
+            replace_non_empty(&mut ad_unit.id, TEST_AD_UNIT_ID);
+        }
+
+        for test_device in &mut ad_mob.test_devices {
+            replace_non_empty(test_device, TEST_DEVICE);
+        }
+    }
+
+    if let Some(google_map) = &mut project.libraries.google_map {
+        replace_non_empty(&mut google_map.api_key, API_KEY);
+    }
+
+    for screen in &mut project.screens {
+        for event in &mut screen.events {
+            redact_blocks(&mut event.code);
+        }
+
+        for more_block in screen.more_blocks.values_mut() {
+            redact_blocks(&mut more_block.code);
+        }
+    }
+
+    let metadata = &mut project.metadata;
+    if options.package_name {
+        metadata.package_name = "com.example.app".to_string();
+    }
+    if options.app_name {
+        metadata.name = "App".to_string();
+    }
+    if options.workspace_name {
+        metadata.workspace_name = "App".to_string();
+    }
+
+    project
+}
+
+fn replace_non_empty(value: &mut String, placeholder: &str) {
+    if !value.is_empty() {
+        *value = placeholder.to_string();
+    }
+}
+
+/// Retrieves the fields of the libraries that are secrets along with their kinds and names
+fn library_fields(project: &SketchwareProject) -> Vec<(SecretKind, String, &str)> {
+    let mut fields = Vec::new();
+    let libraries = &project.libraries;
+
+    if let Some(firebase) = &libraries.firebase {
+        fields.extend([
+            (
+                SecretKind::ProjectId,
+                "firebase.project_id".to_string(),
+                firebase.project_id.as_str(),
+            ),
+            (
+                SecretKind::AppId,
+                "firebase.app_id".to_string(),
+                firebase.app_id.as_str(),
+            ),
+            (
+                SecretKind::ApiKey,
+                "firebase.api_key".to_string(),
+                firebase.api_key.as_str(),
+            ),
+            (
+                SecretKind::StorageBucket,
+                "firebase.storage_bucket".to_string(),
+                firebase.storage_bucket.as_str(),
+            ),
+        ]);
+    }
+
+    if let Some(ad_mob) = &libraries.ad_mob {
+        for (index, ad_unit) in ad_mob.ad_units.iter().enumerate() {
+            fields.push((
+                SecretKind::AdUnitId,
+                format!("ad_mob.ad_units[{}]", index),
+                ad_unit.id.as_str(),
+            ));
+        }
+
+        for (index, test_device) in ad_mob.test_devices.iter().enumerate() {
+            fields.push((
+                SecretKind::TestDevice,
+                format!("ad_mob.test_devices[{}]", index),
+                test_device.as_str(),
+            ));
+        }
+    }
+
+    if let Some(google_map) = &libraries.google_map {
+        fields.push((
+            SecretKind::ApiKey,
+            "google_map.api_key".to_string(),
+            google_map.api_key.as_str(),
+        ));
+    }
+
+    fields
+}
+
+/// Replaces the secrets in the string arguments of every block
+fn redact_blocks(blocks: &mut Blocks) {
+    let mut paths = Vec::new();
+    blocks.visit(|path, _| paths.push(path.to_owned()));
+
+    for path in paths {
+        let block = match blocks.get_mut(&path) {
+            Some(block) => block,
+            None => continue,
+        };
+
+        for arg in block.content.get_args_mut() {
+            if let Argument::String {
+                value: ArgValue::Value(value),
+                ..
+            } = arg
+            {
+                *value = redact(value);
+            }
+        }
+    }
+}
+
+/// Replaces every secret in a string with its placeholder
+fn redact(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+    let mut last = 0;
+
+    for (kind, range) in find_secrets(value) {
+        result.push_str(&value[last..range.start]);
+        result.push_str(kind.placeholder());
+        last = range.end;
+    }
+
+    result.push_str(&value[last..]);
+    result
+}
+
+/// Finds the ranges of things that look like secrets in a string, without overlaps
+fn find_secrets(value: &str) -> Vec<(SecretKind, Range<usize>)> {
+    let mut result = Vec::new();
+    let mut position = 0;
+
+    while position < value.len() {
+        let rest = &value[position..];
+
+        let found = url(rest)
+            .map(|length| (SecretKind::Url, length))
+            .or_else(|| api_key(rest).map(|length| (SecretKind::ApiKey, length)))
+            .or_else(|| ad_unit_id(rest).map(|length| (SecretKind::AdUnitId, length)));
+
+        match found {
+            Some((kind, length)) => {
+                let range = position..position + length;
+
+                if &value[range.to_owned()] != kind.placeholder() {
+                    result.push((kind, range));
+                }
+
+                position += length;
+            }
+            None => position += rest.chars().next().map(char::len_utf8).unwrap_or(1),
+        }
+    }
+
+    result
+}
+
+/// Retrieves the length of an url at the start of a string: `http://` or `https://` followed by
+/// anything up until a whitespace or a quote
+fn url(s: &str) -> Option<usize> {
+    let scheme = ["https://", "http://"]
+        .into_iter()
+        .find(|scheme| s.starts_with(scheme))?;
+
+    let end = s
+        .find(|char: char| char.is_whitespace() || matches!(char, '"' | '\'' | '<' | '>'))
+        .unwrap_or(s.len());
+
+    (end > scheme.len()).then_some(end)
+}
+
+/// Retrieves the length of a Google api key at the start of a string: `AIza` followed by 35
+/// characters
+fn api_key(s: &str) -> Option<usize> {
+    let rest = s.strip_prefix("AIza")?;
+    let length = rest
+        .chars()
+        .take_while(|char| char.is_ascii_alphanumeric() || matches!(char, '_' | '-'))
+        .count();
+
+    (length >= 35).then_some(4 + 35)
+}
+
+/// Retrieves the length of an AdMob id at the start of a string: `ca-app-pub-` followed by 16
+/// digits, a `/` (ad units) or a `~` (apps), and 10 digits
+fn ad_unit_id(s: &str) -> Option<usize> {
+    let rest = s.strip_prefix("ca-app-pub-")?;
+    let publisher = rest.chars().take_while(char::is_ascii_digit).count();
+    let rest = rest[publisher..].strip_prefix(['/', '~'])?;
+    let unit = rest.chars().take_while(char::is_ascii_digit).count();
+
+    (publisher == 16 && unit == 10).then_some("ca-app-pub-".len() + 16 + 1 + 10)
+}
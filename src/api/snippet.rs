@@ -0,0 +1,383 @@
+//! Copying blocks between screens and projects. [`Snippet::extract`] takes blocks along with the
+//! variables, lists, components and moreblocks they refer to, and [`Snippet::import`] brings them
+//! into another screen, creating what's missing and renaming what collides.
+//!
+//! Dependencies are found from the values of menu arguments (like `%m.varInt`), `getVar` blocks
+//! and moreblock calls (`definedFunc`), moreblocks are followed recursively. Views can't be
+//! copied, so views that don't exist in the target screen are reported instead.
+
+use crate::api::block::{ArgValue, Argument, Block, Blocks, SpecItem};
+use crate::api::component::ComponentKind;
+use crate::api::interpreter::block_text;
+use crate::api::screen::{MoreBlock, Screen};
+use crate::api::typecheck::{component_menu, list_menu, variable_menu, view_menu};
+use crate::parser::logic::list_variable::ListVariable;
+use crate::parser::logic::variable::Variable;
+use crate::LinkedHashMap;
+use std::collections::HashMap;
+
+/// Blocks along with everything they depend on
+#[derive(Debug, Clone, PartialEq)]
+pub struct Snippet {
+    pub blocks: Blocks,
+    pub variables: LinkedHashMap<String, Variable>,
+    pub list_variables: LinkedHashMap<String, ListVariable>,
+    pub components: LinkedHashMap<String, ComponentKind>,
+
+    /// Moreblocks called by the blocks, including moreblocks called by these moreblocks
+    pub more_blocks: LinkedHashMap<String, MoreBlock>,
+
+    /// Ids of the views the blocks refer to, views are not copied
+    pub views: Vec<String>,
+}
+
+/// The kind of a dependency of a [`Snippet`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DependencyKind {
+    Variable,
+    ListVariable,
+    Component,
+    MoreBlock,
+}
+
+/// A dependency that got renamed because its name is already used by something different in the
+/// target screen
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Renamed {
+    pub kind: DependencyKind,
+    pub from: String,
+    pub to: String,
+}
+
+/// The result of [`Snippet::import`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportResult {
+    /// The blocks of the snippet with their references renamed, ready to be put on the target
+    /// screen
+    pub blocks: Blocks,
+    pub renamed: Vec<Renamed>,
+
+    /// Ids of views the blocks refer to that don't exist in the target screen
+    pub missing_views: Vec<String>,
+}
+
+impl Snippet {
+    /// Extracts blocks of a screen along with their dependencies. References to things that
+    /// don't exist in the screen are ignored.
+    pub fn extract(screen: &Screen, blocks: &Blocks) -> Snippet {
+        let mut snippet = Snippet {
+            blocks: blocks.clone(),
+            variables: LinkedHashMap::new(),
+            list_variables: LinkedHashMap::new(),
+            components: LinkedHashMap::new(),
+            more_blocks: LinkedHashMap::new(),
+            views: vec![],
+        };
+
+        let mut pending = snippet.collect(screen, blocks);
+
+        while let Some(name) = pending.pop() {
+            if snippet.more_blocks.contains_key(&name) {
+                continue;
+            }
+
+            if let Some(more_block) = screen.more_blocks.get(&name) {
+                snippet.more_blocks.insert(name, more_block.clone());
+                pending.extend(snippet.collect(screen, &more_block.code));
+            }
+        }
+
+        snippet
+    }
+
+    /// Collects the dependencies of the given blocks, returns the names of the moreblocks they
+    /// call
+    fn collect(&mut self, screen: &Screen, blocks: &Blocks) -> Vec<String> {
+        let mut more_blocks = Vec::new();
+
+        blocks.visit(|_, block| {
+            match block.op_code.as_str() {
+                "getVar" => self.add_variable(screen, &block_text(block)),
+                "definedFunc" => more_blocks.extend(first_text(block).map(ToString::to_string)),
+                _ => {}
+            }
+
+            for arg in block.content.get_args() {
+                let (menu, value) = match arg {
+                    Argument::Menu {
+                        name,
+                        value: ArgValue::Value(value),
+                    } if !value.is_empty() => (name.as_str(), value),
+                    _ => continue,
+                };
+
+                if variable_menu(menu).is_some() || list_menu(menu).is_some() {
+                    self.add_variable(screen, value);
+                } else if component_menu(menu).is_some() {
+                    if let Some(component) = screen.components.get(value) {
+                        self.components
+                            .insert(value.to_owned(), component.to_owned());
+                    }
+                } else if view_menu(menu).is_some() && !self.views.contains(value) {
+                    self.views.push(value.to_owned());
+                }
+            }
+        });
+
+        more_blocks
+    }
+
+    /// Adds a variable or a list variable with the given name
+    fn add_variable(&mut self, screen: &Screen, name: &str) {
+        if let Some(variable) = screen.variables.get(name) {
+            self.variables.insert(name.to_owned(), variable.to_owned());
+        } else if let Some(list) = screen.list_variables.get(name) {
+            self.list_variables.insert(name.to_owned(), list.to_owned());
+        }
+    }
+
+    /// Imports this snippet into a screen. Dependencies that already exist in the screen are
+    /// reused when they are the same (same type, same component, or the same moreblock), they are
+    /// renamed otherwise. The returned blocks aren't put anywhere on the screen.
+    pub fn import(&self, screen: &mut Screen) -> ImportResult {
+        let mut renames = Renames::default();
+        let mut renamed = Vec::new();
+
+        let mut rename = |renames: &mut Renames, kind, from: &str, to: String| {
+            renamed.push(Renamed {
+                kind,
+                from: from.to_string(),
+                to: to.to_owned(),
+            });
+            renames.0.insert((kind, from.to_string()), to);
+        };
+
+        for (name, variable) in &self.variables {
+            match screen.variables.get(name) {
+                Some(existing) if existing.r#type == variable.r#type => continue,
+                None if !is_identifier_taken(screen, name) => {}
+                _ => {
+                    let new_name = fresh_name(name, |name| is_identifier_taken(screen, name));
+                    rename(&mut renames, DependencyKind::Variable, name, new_name);
+                }
+            }
+
+            let name = renames.get(DependencyKind::Variable, name);
+            screen.variables.insert(
+                name.to_owned(),
+                Variable {
+                    name,
+                    r#type: variable.r#type,
+                },
+            );
+        }
+
+        for (name, list) in &self.list_variables {
+            match screen.list_variables.get(name) {
+                Some(existing) if existing.r#type == list.r#type => continue,
+                None if !is_identifier_taken(screen, name) => {}
+                _ => {
+                    let new_name = fresh_name(name, |name| is_identifier_taken(screen, name));
+                    rename(&mut renames, DependencyKind::ListVariable, name, new_name);
+                }
+            }
+
+            let name = renames.get(DependencyKind::ListVariable, name);
+            screen.list_variables.insert(
+                name.to_owned(),
+                ListVariable {
+                    name,
+                    r#type: list.r#type,
+                },
+            );
+        }
+
+        for (id, component) in &self.components {
+            match screen.components.get(id) {
+                Some(existing) if existing == component => continue,
+                None if !is_identifier_taken(screen, id) => {}
+                _ => {
+                    let new_id = fresh_name(id, |id| is_identifier_taken(screen, id));
+                    rename(&mut renames, DependencyKind::Component, id, new_id);
+                }
+            }
+
+            screen.components.insert(
+                renames.get(DependencyKind::Component, id),
+                component.to_owned(),
+            );
+        }
+
+        // moreblocks are compared after the names they refer to got renamed, so their names are
+        // decided first
+        let mut more_blocks = Vec::new();
+        for (name, more_block) in &self.more_blocks {
+            let mut more_block = more_block.to_owned();
+            renames.apply(&mut more_block.code);
+
+            match screen.more_blocks.get(name) {
+                Some(existing)
+                    if existing.spec.to_string() == more_block.spec.to_string()
+                        && existing.code == more_block.code =>
+                {
+                    continue
+                }
+                None => {}
+                _ => {
+                    let new_name = fresh_name(name, |name| {
+                        screen.more_blocks.contains_key(name) || self.more_blocks.contains_key(name)
+                    });
+                    rename(&mut renames, DependencyKind::MoreBlock, name, new_name);
+                }
+            }
+
+            more_blocks.push((name, more_block));
+        }
+
+        for (name, mut more_block) in more_blocks {
+            let name = renames.get(DependencyKind::MoreBlock, name);
+
+            // calls to other moreblocks might have been renamed after this got renamed
+            more_block.code = self.more_blocks[&more_block.name].code.to_owned();
+            renames.apply(&mut more_block.code);
+
+            if let Some(SpecItem::Text(text)) = more_block
+                .spec
+                .items
+                .iter_mut()
+                .find(|item| matches!(item, SpecItem::Text(_)))
+            {
+                *text = name.to_owned();
+            }
+
+            more_block.name = name.to_owned();
+            screen.more_blocks.insert(name, more_block);
+        }
+
+        let mut blocks = self.blocks.to_owned();
+        renames.apply(&mut blocks);
+
+        ImportResult {
+            blocks,
+            renamed,
+            missing_views: self
+                .views
+                .iter()
+                .filter(|id| screen.find_view(id).is_none())
+                .cloned()
+                .collect(),
+        }
+    }
+}
+
+/// Names of dependencies that got renamed, keyed by their kinds and their old names
+#[derive(Default)]
+struct Renames(HashMap<(DependencyKind, String), String>);
+
+impl Renames {
+    /// Retrieves the new name of a dependency, or the name itself if it isn't renamed
+    fn get(&self, kind: DependencyKind, name: &str) -> String {
+        self.0
+            .get(&(kind, name.to_string()))
+            .cloned()
+            .unwrap_or_else(|| name.to_string())
+    }
+
+    fn apply(&self, blocks: &mut Blocks) {
+        if self.0.is_empty() {
+            return;
+        }
+
+        for block in &mut blocks.0 {
+            self.apply_block(block);
+        }
+    }
+
+    fn apply_block(&self, block: &mut Block) {
+        let rename = |kinds: &[DependencyKind], name: &mut String| {
+            if let Some(new_name) = kinds
+                .iter()
+                .find_map(|kind| self.0.get(&(*kind, name.to_owned())))
+            {
+                *name = new_name.to_owned();
+            }
+        };
+
+        match block.op_code.as_str() {
+            "getVar" => {
+                for item in &mut block.content.items {
+                    if let SpecItem::Text(text) = item {
+                        rename(
+                            &[DependencyKind::Variable, DependencyKind::ListVariable],
+                            text,
+                        );
+                    }
+                }
+            }
+            "definedFunc" => {
+                if let Some(SpecItem::Text(text)) = block
+                    .content
+                    .items
+                    .iter_mut()
+                    .find(|item| matches!(item, SpecItem::Text(_)))
+                {
+                    rename(&[DependencyKind::MoreBlock], text);
+                }
+            }
+            _ => {}
+        }
+
+        for arg in block.content.get_args_mut() {
+            if let Argument::Menu {
+                name,
+                value: ArgValue::Value(value),
+            } = arg
+            {
+                if variable_menu(name).is_some() || list_menu(name).is_some() {
+                    rename(
+                        &[DependencyKind::Variable, DependencyKind::ListVariable],
+                        value,
+                    );
+                } else if component_menu(name).is_some() {
+                    rename(&[DependencyKind::Component], value);
+                }
+            }
+
+            if let Some(arg_block) = arg.block_mut() {
+                self.apply_block(arg_block);
+            }
+        }
+
+        for stack in [&mut block.sub_stack1, &mut block.sub_stack2]
+            .into_iter()
+            .flatten()
+        {
+            self.apply(stack);
+        }
+    }
+}
+
+/// Checks whether a name is used by a variable, list, component or view of a screen, these are
+/// all fields of the same class in the generated code
+fn is_identifier_taken(screen: &Screen, name: &str) -> bool {
+    screen.variables.contains_key(name)
+        || screen.list_variables.contains_key(name)
+        || screen.components.contains_key(name)
+        || screen.find_view(name).is_some()
+}
+
+/// Generates a name that isn't taken by appending a number to it, starting from 2
+fn fresh_name<F: Fn(&str) -> bool>(name: &str, taken: F) -> String {
+    (2..)
+        .map(|number| format!("{}{}", name, number))
+        .find(|name| !taken(name))
+        .expect("there are infinitely many numbers")
+}
+
+/// Retrieves the first text of a block, which is the name of the moreblock on `definedFunc`
+pub(crate) fn first_text(block: &Block) -> Option<&str> {
+    block.content.items.iter().find_map(|item| match item {
+        SpecItem::Text(text) => Some(text.as_str()),
+        _ => None,
+    })
+}
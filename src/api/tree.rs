@@ -0,0 +1,318 @@
+//! Operations on the tree of views of a layout, like [`Screen::layout`] or [`Drawer::layout`].
+//! Views can only be put inside of views whose type accepts children (see
+//! [`ViewType::accepts_children`]), or at the root of the layout.
+//!
+//! The operations on [`Screen`] also keep the rest of the screen consistent: deleting a view
+//! removes the events of it and its children, and clears the blocks that refer to them.
+//!
+//! [`Drawer::layout`]: crate::api::screen::Drawer::layout
+//! [`ViewType::accepts_children`]: crate::api::view::ViewType::accepts_children
+
+use crate::api::block::{ArgValue, Argument, BlockPath, Blocks};
+use crate::api::screen::{EventType, Screen, DRAWER_PREFIX};
+use crate::api::view::{take_view, walk, View, ViewType};
+use std::collections::HashSet;
+use thiserror::Error;
+
+/// Where a view is located in a layout
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ViewPosition {
+    /// The id of the parent view, `None` if the view is at the root of the layout
+    pub parent: Option<String>,
+
+    /// The index of the view among its siblings
+    pub index: usize,
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum TreeError {
+    #[error("couldn't find the view `{id}`")]
+    ViewNotFound { id: String },
+
+    #[error("the view `{id}` is a {view_type}, which can't have children")]
+    NotAContainer { id: String, view_type: String },
+
+    #[error("the view `{id}` can't be moved inside of itself")]
+    IntoItself { id: String },
+
+    #[error("index {index} is out of bounds, there are only {len} views")]
+    IndexOutOfBounds { index: usize, len: usize },
+
+    #[error("the id `{id}` is already used by another view")]
+    DuplicateId { id: String },
+}
+
+/// Finds where the view with the given id is located in a layout
+pub fn position(layout: &[View], id: &str) -> Option<ViewPosition> {
+    if let Some(index) = layout.iter().position(|view| view.id == id) {
+        return Some(ViewPosition {
+            parent: None,
+            index,
+        });
+    }
+
+    layout.iter().find_map(|view| {
+        position(&view.children, id).map(|position| ViewPosition {
+            parent: position.parent.or_else(|| Some(view.id.to_owned())),
+            index: position.index,
+        })
+    })
+}
+
+/// Inserts a view (along with its children) into a layout, at the given index of the children of
+/// `parent` (or of the root if it's `None`)
+pub fn insert_view(
+    layout: &mut Vec<View>,
+    parent: Option<&str>,
+    index: usize,
+    view: View,
+) -> Result<(), TreeError> {
+    let existing = ids(layout);
+    if let Some(id) = ids(std::slice::from_ref(&view))
+        .into_iter()
+        .find(|id| existing.contains(id))
+    {
+        return Err(TreeError::DuplicateId { id });
+    }
+
+    let siblings = children_mut(layout, parent)?;
+    if index > siblings.len() {
+        return Err(TreeError::IndexOutOfBounds {
+            index,
+            len: siblings.len(),
+        });
+    }
+
+    siblings.insert(index, view);
+    Ok(())
+}
+
+/// Removes a view along with its children from a layout
+pub fn remove_view(layout: &mut Vec<View>, id: &str) -> Option<View> {
+    take_view(layout, id)
+}
+
+/// Moves a view into the children of `parent` (or the root if it's `None`) at the given index.
+/// The index is of where the view will be after it's moved.
+pub fn move_view(
+    layout: &mut Vec<View>,
+    id: &str,
+    parent: Option<&str>,
+    index: usize,
+) -> Result<(), TreeError> {
+    let view = find(layout, id)?;
+
+    // check everything first so the layout is left as is when it fails
+    if let Some(parent) = parent {
+        if view.find_id(parent).is_some() {
+            return Err(TreeError::IntoItself { id: id.to_string() });
+        }
+    }
+
+    let siblings = children(layout, parent)?;
+    let len = siblings.len() - siblings.iter().filter(|view| view.id == id).count();
+    if index > len {
+        return Err(TreeError::IndexOutOfBounds { index, len });
+    }
+
+    let view = remove_view(layout, id).expect("the view exists");
+    children_mut(layout, parent)?.insert(index, view);
+    Ok(())
+}
+
+/// Moves a view to another index among its siblings
+pub fn reorder_view(layout: &mut Vec<View>, id: &str, index: usize) -> Result<(), TreeError> {
+    let position =
+        position(layout, id).ok_or_else(|| TreeError::ViewNotFound { id: id.to_string() })?;
+
+    move_view(layout, id, position.parent.as_deref(), index)
+}
+
+/// Deep-clones a view, giving it and every one of its children a new id from `fresh_id`
+pub fn clone_view<F: FnMut(&View) -> String>(view: &View, fresh_id: &mut F) -> View {
+    let mut result = view.clone();
+    result.id = fresh_id(view);
+    result.children = view
+        .children
+        .iter()
+        .map(|child| clone_view(child, fresh_id))
+        .collect();
+
+    result
+}
+
+impl Screen {
+    /// Deletes a view (from the layout, the fab or the drawer when its id starts with
+    /// [`DRAWER_PREFIX`]) along with its children. Events of the deleted views are removed, and
+    /// blocks that refer to them get their references cleared.
+    pub fn delete_view(&mut self, id: &str) -> Result<View, TreeError> {
+        let not_found = || TreeError::ViewNotFound { id: id.to_string() };
+
+        let (view, prefix) = if let Some(drawer_id) = id.strip_prefix(DRAWER_PREFIX) {
+            let drawer = self.drawer.as_mut().ok_or_else(not_found)?;
+            let view = remove_view(&mut drawer.layout, drawer_id).ok_or_else(not_found)?;
+
+            (view, DRAWER_PREFIX)
+        } else if self.fab.as_ref().is_some_and(|fab| fab.id == id) {
+            self.fab_enabled = false;
+            (self.fab.take().unwrap(), "")
+        } else {
+            (remove_view(&mut self.layout, id).ok_or_else(not_found)?, "")
+        };
+
+        let deleted: HashSet<String> = ids(std::slice::from_ref(&view))
+            .into_iter()
+            .map(|id| format!("{}{}", prefix, id))
+            .collect();
+
+        self.events.retain(|event| {
+            !matches!(&event.event_type, EventType::ViewEvent { id } if deleted.contains(id))
+        });
+
+        let containers = self.events.iter_mut().map(|event| &mut event.code).chain(
+            self.more_blocks
+                .values_mut()
+                .map(|more_block| &mut more_block.code),
+        );
+
+        for blocks in containers {
+            clear_references(blocks, &deleted);
+        }
+
+        Ok(view)
+    }
+
+    /// Adds a new view of the given type into the layout of this screen, at the given index of
+    /// the children of `parent` (or of the root if it's `None`). Its id is allocated with
+    /// [`IdAllocator`](crate::api::id::IdAllocator), returns the id.
+    pub fn add_view(
+        &mut self,
+        parent: Option<&str>,
+        index: usize,
+        view_type: ViewType,
+    ) -> Result<String, TreeError> {
+        let view = self.new_view(view_type);
+        let id = view.id.to_owned();
+
+        insert_view(&mut self.layout, parent, index, view)?;
+        Ok(id)
+    }
+
+    /// Deep-clones a view with its children and places the copy right after it, the views of
+    /// the copy are given new ids allocated with [`IdAllocator`](crate::api::id::IdAllocator).
+    /// Returns the id of the copy.
+    pub fn duplicate_view(&mut self, id: &str) -> Result<String, TreeError> {
+        let mut allocator = self.id_allocator();
+
+        let (layout, id) = match id.strip_prefix(DRAWER_PREFIX) {
+            Some(drawer_id) => (
+                self.drawer.as_mut().map(|drawer| &mut drawer.layout),
+                drawer_id,
+            ),
+            None => (Some(&mut self.layout), id),
+        };
+
+        let not_found = || TreeError::ViewNotFound { id: id.to_string() };
+        let layout = layout.ok_or_else(not_found)?;
+        let position = position(layout, id).ok_or_else(not_found)?;
+
+        let copy = clone_view(find(layout, id)?, &mut |view: &View| match &view.view {
+            Ok(view_type) => allocator.view_id(view_type),
+            // use the id without its number as the prefix for views we don't know about
+            Err(_) => allocator.next(view.id.trim_end_matches(|ch: char| ch.is_ascii_digit())),
+        });
+
+        let copy_id = copy.id.to_owned();
+        children_mut(layout, position.parent.as_deref())?.insert(position.index + 1, copy);
+
+        Ok(copy_id)
+    }
+}
+
+fn find<'a>(layout: &'a [View], id: &str) -> Result<&'a View, TreeError> {
+    layout
+        .iter()
+        .find_map(|view| view.find_id(id))
+        .ok_or_else(|| TreeError::ViewNotFound { id: id.to_string() })
+}
+
+/// Checks whether a view can have children, returns the view that can
+fn container<'a>(view: Option<&'a View>, id: &str) -> Result<&'a View, TreeError> {
+    let view = view.ok_or_else(|| TreeError::ViewNotFound { id: id.to_string() })?;
+
+    match &view.view {
+        Ok(view_type) if view_type.accepts_children() => Ok(view),
+        Ok(view_type) => Err(TreeError::NotAContainer {
+            id: id.to_string(),
+            view_type: view_type.name().to_string(),
+        }),
+        Err(_) => Err(TreeError::NotAContainer {
+            id: id.to_string(),
+            view_type: "view of an unknown type".to_string(),
+        }),
+    }
+}
+
+/// Retrieves the children of `parent`, or the root views if it's `None`
+fn children<'a>(layout: &'a [View], parent: Option<&str>) -> Result<&'a [View], TreeError> {
+    match parent {
+        None => Ok(layout),
+        Some(parent) => Ok(&container(find(layout, parent).ok(), parent)?.children),
+    }
+}
+
+fn children_mut<'a>(
+    layout: &'a mut Vec<View>,
+    parent: Option<&str>,
+) -> Result<&'a mut Vec<View>, TreeError> {
+    let parent = match parent {
+        None => return Ok(layout),
+        Some(parent) => parent,
+    };
+
+    // check the type with an immutable borrow first
+    container(find(layout, parent).ok(), parent)?;
+
+    Ok(&mut layout
+        .iter_mut()
+        .find_map(|view| view.find_id_mut(parent))
+        .expect("the parent exists")
+        .children)
+}
+
+/// Collects the ids of every view in the given views
+fn ids(views: &[View]) -> HashSet<String> {
+    walk(views)
+        .into_iter()
+        .map(|view| view.id.to_owned())
+        .collect()
+}
+
+/// Resets menu arguments that refer to the given ids
+fn clear_references(blocks: &mut Blocks, ids: &HashSet<String>) {
+    let mut references: Vec<(BlockPath, usize)> = Vec::new();
+
+    blocks.visit(|path, block| {
+        for (index, arg) in block.content.get_args().into_iter().enumerate() {
+            if let Argument::Menu {
+                value: ArgValue::Value(value),
+                ..
+            } = arg
+            {
+                if ids.contains(value) {
+                    references.push((path.to_owned(), index));
+                }
+            }
+        }
+    });
+
+    for (path, index) in references {
+        let arg = blocks
+            .get_mut(&path)
+            .and_then(|block| block.content.get_args_mut().into_iter().nth(index));
+
+        if let Some(Argument::Menu { value, .. }) = arg {
+            *value = ArgValue::Value(String::new());
+        }
+    }
+}
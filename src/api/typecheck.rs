@@ -0,0 +1,430 @@
+//! A static type checker for the arguments of blocks. It checks whether the blocks put on
+//! arguments return what the arguments expect, and whether the values of menus refer to existing
+//! views, components, variables and lists of the right type.
+
+use crate::api::block::{
+    ArgValue, Argument, ArgumentBlockReturnType, Block, BlockPath, BlockType, Blocks,
+};
+use crate::api::screen::{EventType, Screen};
+use crate::api::view::{View, ViewType};
+use crate::api::CustomView;
+use crate::api::SketchwareProject;
+use crate::parser::logic::variable::VariableType;
+use std::collections::HashMap;
+use thiserror::Error;
+
+/// Holds the views, components, variables and lists that blocks can refer to
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TypeContext {
+    /// View ids mapped to the name of their view type (see [`ViewType::name`])
+    pub views: HashMap<String, &'static str>,
+
+    /// Component ids mapped to the name of their kind (see
+    /// [`crate::api::component::ComponentKind::name`])
+    pub components: HashMap<String, &'static str>,
+
+    pub variables: HashMap<String, VariableType>,
+    pub list_variables: HashMap<String, VariableType>,
+}
+
+impl TypeContext {
+    /// Creates a context that contains the views, components, variables and lists of a screen
+    pub fn from_screen(screen: &Screen) -> Self {
+        let mut context = TypeContext {
+            views: HashMap::new(),
+            components: screen
+                .components
+                .iter()
+                .map(|(id, component)| (id.to_owned(), component.name()))
+                .collect(),
+            variables: screen
+                .variables
+                .iter()
+                .map(|(name, variable)| (name.to_owned(), variable.r#type))
+                .collect(),
+            list_variables: screen
+                .list_variables
+                .iter()
+                .map(|(name, list)| (name.to_owned(), list.r#type))
+                .collect(),
+        };
+
+        // views of the drawer are referred to with their ids prefixed with `_drawer_`
+        for (id, view) in screen.all_views() {
+            if let Ok(view_type) = &view.view {
+                context.views.insert(id, view_type.name());
+            }
+        }
+
+        context
+    }
+
+    /// Adds views (and their children) into the context, views with an unknown type are skipped
+    pub fn add_views(&mut self, views: &[View]) {
+        for view in views {
+            if let Ok(view_type) = &view.view {
+                self.views.insert(view.id.to_owned(), view_type.name());
+            }
+
+            self.add_views(&view.children);
+        }
+    }
+
+    /// Type checks the given blocks, returns every type error found
+    pub fn check(&self, blocks: &Blocks) -> Vec<TypeError> {
+        let mut errors = Vec::new();
+
+        blocks.visit(|path, block| {
+            for (index, arg) in block.content.get_args().into_iter().enumerate() {
+                if let Some(kind) = self.check_argument(arg) {
+                    errors.push(TypeError {
+                        path: path.to_owned(),
+                        op_code: block.op_code.to_owned(),
+                        argument: index,
+                        kind,
+                    });
+                }
+            }
+        });
+
+        errors
+    }
+
+    fn check_argument(&self, arg: &Argument) -> Option<TypeErrorKind> {
+        if let Some(block) = arg.block() {
+            return if accepts_block(arg, block) {
+                None
+            } else {
+                Some(TypeErrorKind::MismatchedBlock {
+                    expected: ArgumentKind::of(arg),
+                    found: block.block_type.to_owned(),
+                })
+            };
+        }
+
+        let (menu, value) = match arg {
+            Argument::Menu {
+                name,
+                value: ArgValue::Value(value),
+            } if !value.is_empty() => (name.as_str(), value),
+            _ => return None,
+        };
+
+        if let Some(expected) = view_menu(menu) {
+            return match self.views.get(value) {
+                None => Some(TypeErrorKind::UnknownView {
+                    id: value.to_owned(),
+                }),
+                Some(found) if !expected.is_empty() && !expected.contains(found) => {
+                    Some(TypeErrorKind::MismatchedView {
+                        id: value.to_owned(),
+                        menu: menu.to_string(),
+                        found,
+                    })
+                }
+                _ => None,
+            };
+        }
+
+        if let Some(expected) = component_menu(menu) {
+            return match self.components.get(value) {
+                None => Some(TypeErrorKind::UnknownComponent {
+                    id: value.to_owned(),
+                }),
+                Some(found) if *found != expected => Some(TypeErrorKind::MismatchedComponent {
+                    id: value.to_owned(),
+                    expected,
+                    found,
+                }),
+                _ => None,
+            };
+        }
+
+        if let Some(expected) = variable_menu(menu) {
+            return match self.variables.get(value) {
+                None => Some(TypeErrorKind::UnknownVariable {
+                    name: value.to_owned(),
+                }),
+                Some(found) if *found != expected => Some(TypeErrorKind::MismatchedVariable {
+                    name: value.to_owned(),
+                    expected,
+                    found: *found,
+                }),
+                _ => None,
+            };
+        }
+
+        if let Some(expected) = list_menu(menu) {
+            return match self.list_variables.get(value) {
+                None => Some(TypeErrorKind::UnknownList {
+                    name: value.to_owned(),
+                }),
+                Some(found) if expected.map(|e| e != *found).unwrap_or(false) => {
+                    Some(TypeErrorKind::MismatchedList {
+                        name: value.to_owned(),
+                        expected: expected.unwrap(),
+                        found: *found,
+                    })
+                }
+                _ => None,
+            };
+        }
+
+        None
+    }
+}
+
+/// Checks whether a block can be put on an argument
+fn accepts_block(arg: &Argument, block: &Block) -> bool {
+    let return_type = match &block.block_type {
+        BlockType::Argument(return_type) => return_type,
+        _ => return false,
+    };
+
+    match arg {
+        Argument::String { .. } => *return_type == ArgumentBlockReturnType::String,
+        Argument::Number { .. } => *return_type == ArgumentBlockReturnType::Number,
+        Argument::Boolean { .. } => *return_type == ArgumentBlockReturnType::Boolean,
+        Argument::Menu { name, .. } => {
+            if view_menu(name).is_some() {
+                matches!(return_type, ArgumentBlockReturnType::View { .. })
+            } else if component_menu(name).is_some() {
+                matches!(return_type, ArgumentBlockReturnType::Component { .. })
+            } else if list_menu(name).is_some() {
+                matches!(return_type, ArgumentBlockReturnType::List { .. })
+            } else if variable_menu(name).is_some() {
+                false
+            } else {
+                // we don't know what this menu is
+                true
+            }
+        }
+    }
+}
+
+/// Retrieves the view types a view menu accepts, an empty slice means that it accepts any views
+pub(crate) fn view_menu(menu: &str) -> Option<&'static [&'static str]> {
+    Some(match menu {
+        "view" => &[],
+        "textview" => &["TextView", "EditText"],
+        "edittext" => &["EditText"],
+        "button" => &["Button"],
+        "imageview" => &["ImageView"],
+        "listview" => &["ListView"],
+        "spinner" => &["Spinner"],
+        "webview" => &["WebView"],
+        "checkbox" => &["CheckBox"],
+        "switch" => &["Switch"],
+        "seekbar" => &["SeekBar"],
+        "calendarview" => &["CalendarView"],
+        "adview" => &["AdView"],
+        "mapview" => &["MapView"],
+        "progressbar" => &["ProgressBar"],
+        _ => return None,
+    })
+}
+
+/// Retrieves the component kind a component menu accepts
+pub(crate) fn component_menu(menu: &str) -> Option<&'static str> {
+    Some(match menu {
+        "intent" => "Intent",
+        "file" => "SharedPreferences",
+        "calendar" => "Calendar",
+        "vibrator" => "Vibrator",
+        "timer" => "Timer",
+        "firebase" => "FirebaseDatabase",
+        "dialog" => "Dialog",
+        "mediaplayer" => "MediaPlayer",
+        "soundpool" => "SoundPool",
+        "objectanimator" => "ObjectAnimator",
+        "gyroscope" => "Gyroscope",
+        "firebaseauth" => "FirebaseAuth",
+        "interstitialad" => "InterstitialAd",
+        "firebasestorage" => "FirebaseStorage",
+        "camera" => "Camera",
+        "filepicker" => "FilePicker",
+        "requestnetwork" => "RequestNetwork",
+        "texttospeech" => "TextToSpeech",
+        "speechtotext" => "SpeechToText",
+        "bluetoothconnect" => "BluetoothConnect",
+        "locationmanager" => "LocationManager",
+        _ => return None,
+    })
+}
+
+/// Retrieves the variable type a variable menu accepts
+pub(crate) fn variable_menu(menu: &str) -> Option<VariableType> {
+    Some(match menu {
+        "varInt" => VariableType::Integer,
+        "varStr" => VariableType::String,
+        "varBool" => VariableType::Boolean,
+        "varMap" => VariableType::HashMap,
+        _ => return None,
+    })
+}
+
+/// Retrieves the item type a list menu accepts, None means that it accepts any lists
+pub(crate) fn list_menu(menu: &str) -> Option<Option<VariableType>> {
+    Some(match menu {
+        "list" => None,
+        "listInt" => Some(VariableType::Integer),
+        "listStr" => Some(VariableType::String),
+        "listMap" => Some(VariableType::HashMap),
+        _ => return None,
+    })
+}
+
+/// Type checks every event and moreblock of a screen. Views of the custom views (given by
+/// `custom_views`) are also available to the `onBindCustomView` events of listviews
+pub fn check_screen(screen: &Screen, custom_views: &[CustomView]) -> Vec<ScreenTypeError> {
+    let context = TypeContext::from_screen(screen);
+    let mut errors = Vec::new();
+
+    let mut push_errors = |container: String, context: &TypeContext, blocks: &Blocks| {
+        errors.extend(
+            context
+                .check(blocks)
+                .into_iter()
+                .map(|error| ScreenTypeError {
+                    screen: screen.layout_name.to_owned(),
+                    container: container.clone(),
+                    error,
+                }),
+        );
+    };
+
+    // onBindCustomView events can also access the views of their custom view
+    let custom_contexts: HashMap<String, TypeContext> = screen
+        .events
+        .iter()
+        .filter_map(|event| {
+            let custom_view = match (&event.event_type, event.name.as_str()) {
+                (EventType::ViewEvent { id }, "onBindCustomView") => {
+                    screen.find_view(id).and_then(|view| match &view.view {
+                        Ok(ViewType::ListView { custom_view, .. }) => custom_views
+                            .iter()
+                            .find(|custom| &custom.res_name == custom_view),
+                        _ => None,
+                    })
+                }
+                _ => None,
+            }?;
+
+            let mut context = context.clone();
+            context.add_views(&custom_view.layout);
+            Some((event.get_block_container_id(), context))
+        })
+        .collect();
+
+    for (container, blocks) in screen.block_containers() {
+        let context = custom_contexts.get(&container).unwrap_or(&context);
+        push_errors(container, context, blocks);
+    }
+
+    errors
+}
+
+/// Type checks every screen of a project, see [`check_screen`]
+pub fn check_project(project: &SketchwareProject) -> Vec<ScreenTypeError> {
+    project
+        .screens
+        .iter()
+        .flat_map(|screen| check_screen(screen, &project.custom_views))
+        .collect()
+}
+
+/// A type error found inside of a block
+#[derive(Debug, Clone, PartialEq, Error)]
+#[error("argument {argument} of block `{op_code}` at {path}: {kind}")]
+pub struct TypeError {
+    /// The path of the block that has the wrongly typed argument
+    pub path: BlockPath,
+    pub op_code: String,
+
+    /// The index of the wrongly typed argument
+    pub argument: usize,
+    pub kind: TypeErrorKind,
+}
+
+/// A [`TypeError`] along with where it's found
+#[derive(Debug, Clone, PartialEq, Error)]
+#[error("in {screen}, {container}: {error}")]
+pub struct ScreenTypeError {
+    /// The layout name of the screen
+    pub screen: String,
+
+    /// The block container id of the event / moreblock
+    pub container: String,
+    pub error: TypeError,
+}
+
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum TypeErrorKind {
+    #[error("expected {expected:?}, found a block of type {found:?}")]
+    MismatchedBlock {
+        expected: ArgumentKind,
+        found: BlockType,
+    },
+
+    #[error("there is no view with the id `{id}`")]
+    UnknownView { id: String },
+
+    #[error("`{id}` is a {found}, which can't be used on a `{menu}` menu")]
+    MismatchedView {
+        id: String,
+        menu: String,
+        found: &'static str,
+    },
+
+    #[error("there is no component with the id `{id}`")]
+    UnknownComponent { id: String },
+
+    #[error("expected a {expected} component, but `{id}` is a {found}")]
+    MismatchedComponent {
+        id: String,
+        expected: &'static str,
+        found: &'static str,
+    },
+
+    #[error("there is no variable named `{name}`")]
+    UnknownVariable { name: String },
+
+    #[error("expected a {expected:?} variable, but `{name}` is a {found:?}")]
+    MismatchedVariable {
+        name: String,
+        expected: VariableType,
+        found: VariableType,
+    },
+
+    #[error("there is no list named `{name}`")]
+    UnknownList { name: String },
+
+    #[error("expected a list of {expected:?}, but `{name}` is a list of {found:?}")]
+    MismatchedList {
+        name: String,
+        expected: VariableType,
+        found: VariableType,
+    },
+}
+
+/// What kind of value an argument accepts
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ArgumentKind {
+    String,
+    Number,
+    Boolean,
+    Menu { name: String },
+}
+
+impl ArgumentKind {
+    pub fn of(arg: &Argument) -> Self {
+        match arg {
+            Argument::String { .. } => ArgumentKind::String,
+            Argument::Number { .. } => ArgumentKind::Number,
+            Argument::Boolean { .. } => ArgumentKind::Boolean,
+            Argument::Menu { name, .. } => ArgumentKind::Menu {
+                name: name.to_owned(),
+            },
+        }
+    }
+}
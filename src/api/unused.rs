@@ -0,0 +1,294 @@
+//! Detection of dead code and unused declarations. [`find_unused`] lists the variables, lists,
+//! components and moreblocks of a project that aren't used, and events whose views or components
+//! no longer exist, [`prune`] removes them.
+//!
+//! Usages are only counted from live code: events whose targets exist and the moreblocks they
+//! (transitively) call. A declaration that is only used by dead code is unused as well, so
+//! everything reported can be removed at once. Any menu value or `getVar` name is counted as a
+//! usage of every declaration with that name, so nothing in use is ever reported.
+//!
+//! Block containers of the raw logic that no event or moreblock owns are dropped when a project
+//! is converted into the api models, use [`find_orphan_block_containers`] to find them on a
+//! [`ScreenLogic`].
+
+use crate::api::block::{ArgValue, Argument, Blocks};
+use crate::api::interpreter::block_text;
+use crate::api::screen::{Event, EventType, Screen};
+use crate::api::snippet::first_text;
+use crate::api::SketchwareProject;
+use crate::parser::logic::ScreenLogic;
+use std::collections::HashSet;
+use std::fmt::{Display, Formatter};
+
+/// Something that isn't used, screens are identified by their layout names except in
+/// [`Unused::OrphanBlockContainer`] where it's the name of the raw logic
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Unused {
+    Variable {
+        screen: String,
+        name: String,
+    },
+    ListVariable {
+        screen: String,
+        name: String,
+    },
+    Component {
+        screen: String,
+        id: String,
+    },
+
+    /// A moreblock that is never called from live code
+    MoreBlock {
+        screen: String,
+        name: String,
+    },
+
+    /// An event of a view or a component that doesn't exist, identified by its block container
+    /// id
+    OrphanEvent {
+        screen: String,
+        container: String,
+    },
+
+    /// A block container of a raw logic that doesn't belong to any event nor moreblock
+    OrphanBlockContainer {
+        screen: String,
+        container: String,
+    },
+}
+
+impl Display for Unused {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Unused::Variable { screen, name } => {
+                write!(f, "{}: variable `{}` is never used", screen, name)
+            }
+            Unused::ListVariable { screen, name } => {
+                write!(f, "{}: list `{}` is never used", screen, name)
+            }
+            Unused::Component { screen, id } => {
+                write!(f, "{}: component `{}` is never used", screen, id)
+            }
+            Unused::MoreBlock { screen, name } => {
+                write!(f, "{}: moreblock `{}` is never called", screen, name)
+            }
+            Unused::OrphanEvent { screen, container } => write!(
+                f,
+                "{}: event `{}` belongs to something that doesn't exist",
+                screen, container
+            ),
+            Unused::OrphanBlockContainer { screen, container } => write!(
+                f,
+                "{}: block container `{}` doesn't belong to any event or moreblock",
+                screen, container
+            ),
+        }
+    }
+}
+
+/// Finds every unused declaration and orphan event of a project
+pub fn find_unused(project: &SketchwareProject) -> Vec<Unused> {
+    project
+        .screens
+        .iter()
+        .flat_map(find_unused_in_screen)
+        .collect()
+}
+
+/// Finds every unused declaration and orphan event of a screen
+pub fn find_unused_in_screen(screen: &Screen) -> Vec<Unused> {
+    let name = &screen.layout_name;
+    let (live_events, orphan_events): (Vec<_>, Vec<_>) = screen
+        .events
+        .iter()
+        .partition(|event| is_live(screen, event));
+
+    // moreblocks reachable from live events
+    let mut referenced = HashSet::new();
+    let mut called = HashSet::new();
+    let mut pending = Vec::new();
+
+    for event in &live_events {
+        pending.extend(collect_usages(&event.code, &mut referenced));
+
+        if let EventType::ComponentEvent { id, .. } = &event.event_type {
+            referenced.insert(id.to_owned());
+        }
+    }
+
+    while let Some(more_block) = pending.pop() {
+        if !called.insert(more_block.to_owned()) {
+            continue;
+        }
+
+        if let Some(more_block) = screen.more_blocks.get(&more_block) {
+            pending.extend(collect_usages(&more_block.code, &mut referenced));
+        }
+    }
+
+    let mut result = Vec::new();
+
+    result.extend(
+        screen
+            .variables
+            .keys()
+            .filter(|variable| !referenced.contains(*variable))
+            .map(|variable| Unused::Variable {
+                screen: name.to_owned(),
+                name: variable.to_owned(),
+            }),
+    );
+    result.extend(
+        screen
+            .list_variables
+            .keys()
+            .filter(|list| !referenced.contains(*list))
+            .map(|list| Unused::ListVariable {
+                screen: name.to_owned(),
+                name: list.to_owned(),
+            }),
+    );
+    result.extend(
+        screen
+            .components
+            .keys()
+            .filter(|id| !referenced.contains(*id))
+            .map(|id| Unused::Component {
+                screen: name.to_owned(),
+                id: id.to_owned(),
+            }),
+    );
+    result.extend(
+        screen
+            .more_blocks
+            .keys()
+            .filter(|more_block| !called.contains(*more_block))
+            .map(|more_block| Unused::MoreBlock {
+                screen: name.to_owned(),
+                name: more_block.to_owned(),
+            }),
+    );
+    result.extend(orphan_events.into_iter().map(|event| Unused::OrphanEvent {
+        screen: name.to_owned(),
+        container: event.get_block_container_id(),
+    }));
+
+    result
+}
+
+/// Checks whether the view or component of an event exists
+fn is_live(screen: &Screen, event: &Event) -> bool {
+    match &event.event_type {
+        EventType::ViewEvent { id } => screen.find_view(id).is_some(),
+        EventType::ComponentEvent { id, .. } => screen.components.contains_key(id),
+        EventType::ActivityEvent => true,
+    }
+}
+
+/// Collects the names blocks refer to (from menus and `getVar`s) into `referenced`, returns the
+/// names of moreblocks they call
+fn collect_usages(blocks: &Blocks, referenced: &mut HashSet<String>) -> Vec<String> {
+    let mut more_blocks = Vec::new();
+
+    blocks.visit(|_, block| {
+        match block.op_code.as_str() {
+            "getVar" => {
+                referenced.insert(block_text(block));
+            }
+            "definedFunc" => more_blocks.extend(first_text(block).map(ToString::to_string)),
+            _ => {}
+        }
+
+        for arg in block.content.get_args() {
+            if let Argument::Menu {
+                value: ArgValue::Value(value),
+                ..
+            } = arg
+            {
+                referenced.insert(value.to_owned());
+            }
+        }
+    });
+
+    more_blocks
+}
+
+/// Removes everything [`find_unused`] reports from a project, returns what's removed
+pub fn prune(project: &mut SketchwareProject) -> Vec<Unused> {
+    project.screens.iter_mut().flat_map(prune_screen).collect()
+}
+
+/// Removes everything [`find_unused_in_screen`] reports from a screen, returns what's removed
+pub fn prune_screen(screen: &mut Screen) -> Vec<Unused> {
+    let unused = find_unused_in_screen(screen);
+
+    for item in &unused {
+        match item {
+            Unused::Variable { name, .. } => {
+                screen.variables.remove(name);
+            }
+            Unused::ListVariable { name, .. } => {
+                screen.list_variables.remove(name);
+            }
+            Unused::Component { id, .. } => {
+                screen.components.remove(id);
+            }
+            Unused::MoreBlock { name, .. } => {
+                screen.more_blocks.remove(name);
+            }
+            Unused::OrphanEvent { container, .. } => screen
+                .events
+                .retain(|event| &event.get_block_container_id() != container),
+            Unused::OrphanBlockContainer { .. } => {}
+        }
+    }
+
+    unused
+}
+
+/// Finds block containers of a raw logic that don't belong to any event nor moreblock
+pub fn find_orphan_block_containers(logic: &ScreenLogic) -> Vec<Unused> {
+    let mut owned = HashSet::new();
+
+    // onCreate doesn't have an event, see Screen::from_parsed
+    owned.insert("onCreate_initializeLogic".to_string());
+
+    for event in logic.events.iter().flat_map(|events| &events.0) {
+        owned.insert(match Event::try_from(event.to_owned()) {
+            Ok(event) => event.get_block_container_id(),
+            Err(_) => format!("{}_{}", event.target_id, event.event_name),
+        });
+    }
+
+    for id in logic
+        .more_blocks
+        .iter()
+        .flat_map(|more_blocks| more_blocks.0.keys())
+    {
+        owned.insert(format!("{}_moreBlock", id));
+    }
+
+    logic
+        .block_containers
+        .keys()
+        .filter(|container| !owned.contains(*container))
+        .map(|container| Unused::OrphanBlockContainer {
+            screen: logic.name.to_owned(),
+            container: container.to_owned(),
+        })
+        .collect()
+}
+
+/// Removes the block containers [`find_orphan_block_containers`] reports, returns what's
+/// removed
+pub fn prune_orphan_block_containers(logic: &mut ScreenLogic) -> Vec<Unused> {
+    let orphans = find_orphan_block_containers(logic);
+
+    for orphan in &orphans {
+        if let Unused::OrphanBlockContainer { container, .. } = orphan {
+            logic.block_containers.remove(container);
+        }
+    }
+
+    orphans
+}
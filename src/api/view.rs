@@ -1,7 +1,9 @@
+use crate::api::library::LibraryKind;
 use crate::color::Color;
 use crate::parser::view::models::layout::Orientation;
 use crate::parser::view::models::{image, layout, text, AndroidView, SpinnerMode};
 use crate::parser::view::Layout;
+use crate::LinkedHashMap;
 use thiserror::Error;
 
 /// A model that represents a single view
@@ -63,6 +65,23 @@ pub struct View {
 }
 
 impl View {
+    /// Creates a view without any children with the default values of sketchware; layouts fill
+    /// the width of their parent, while other views wrap their content
+    pub fn new(id: &str, view_type: ViewType) -> View {
+        let mut raw = AndroidView::new_empty(id, view_type.get_type_id(), "root", 0);
+        view_type.clone().apply_values_to_view(&mut raw);
+
+        let mut view = View::from(raw);
+        view.width = if view_type.accepts_children() {
+            layout::Size::MatchParent
+        } else {
+            layout::Size::WrapContent
+        };
+        view.height = layout::Size::WrapContent;
+        view.view = Ok(view_type);
+        view
+    }
+
     pub fn find_id(&self, id: &str) -> Option<&View> {
         if self.id == id {
             Some(self)
@@ -80,8 +99,126 @@ impl View {
             self.children.iter_mut().find_map(|i| i.find_id_mut(id))
         }
     }
+
+    /// Converts this view (without its children) into its raw form, the fields of this struct are
+    /// applied onto it the same way they are when the layout gets reconstructed
+    pub fn to_raw(&self) -> AndroidView {
+        let view = View {
+            id: self.id.to_owned(),
+            background_color: self.background_color,
+            height: self.height,
+            width: self.width,
+            padding: self.padding.clone(),
+            margin: self.margin.clone(),
+            weight: self.weight,
+            weight_sum: self.weight_sum,
+            layout_gravity: self.layout_gravity,
+            view: self.view.clone(),
+            children: vec![],
+            raw: self.raw.clone(),
+        };
+
+        flatten_views(vec![view], None, None).remove(0)
+    }
+
+    /// Retrieves the attributes of this view as a flat map of their raw names (like `text.text`
+    /// or `layout.width`) to their raw values. Fields that describe where the view is located in
+    /// the layout (like `id`, `parent` or `index`) are left out.
+    pub fn attributes(&self) -> LinkedHashMap<String, serde_json::Value> {
+        let mut attributes = LinkedHashMap::new();
+
+        if let Ok(serde_json::Value::Object(fields)) = serde_json::to_value(self.to_raw()) {
+            for (key, value) in fields {
+                if POSITIONAL_ATTRIBUTES.contains(&key.as_str()) {
+                    continue;
+                }
+
+                match value {
+                    serde_json::Value::Object(inner) => {
+                        for (inner_key, inner_value) in inner {
+                            attributes.insert(format!("{}.{}", key, inner_key), inner_value);
+                        }
+                    }
+                    value => {
+                        attributes.insert(key, value);
+                    }
+                }
+            }
+        }
+
+        attributes
+    }
+
+    /// Sets an attribute of this view using its raw name (see [`View::attributes`]), the view
+    /// gets re-parsed from its raw form afterwards while its children are kept as they are.
+    pub fn set_attribute(
+        &mut self,
+        attribute: &str,
+        value: serde_json::Value,
+    ) -> Result<(), SetAttributeError> {
+        if POSITIONAL_ATTRIBUTES.contains(&attribute) {
+            return Err(SetAttributeError::PositionalAttribute {
+                attribute: attribute.to_string(),
+            });
+        }
+
+        let mut raw =
+            serde_json::to_value(self.to_raw()).map_err(|err| SetAttributeError::InvalidValue {
+                attribute: attribute.to_string(),
+                source: err,
+            })?;
+
+        let field = match attribute.split_once('.') {
+            Some((outer, inner)) => raw.get_mut(outer).and_then(|outer| outer.get_mut(inner)),
+            None => raw.get_mut(attribute),
+        }
+        .ok_or_else(|| SetAttributeError::UnknownAttribute {
+            attribute: attribute.to_string(),
+        })?;
+
+        *field = value;
+
+        let raw: AndroidView =
+            serde_json::from_value(raw).map_err(|err| SetAttributeError::InvalidValue {
+                attribute: attribute.to_string(),
+                source: err,
+            })?;
+
+        let children = std::mem::take(&mut self.children);
+        *self = View::from(raw);
+        self.children = children;
+
+        Ok(())
+    }
 }
 
+#[derive(Error, Debug)]
+pub enum SetAttributeError {
+    #[error("there is no attribute named `{attribute}`")]
+    UnknownAttribute { attribute: String },
+
+    #[error("attribute `{attribute}` defines where the view is located and can't be set")]
+    PositionalAttribute { attribute: String },
+
+    #[error("invalid value given for attribute `{attribute}`")]
+    InvalidValue {
+        attribute: String,
+        source: serde_json::Error,
+    },
+}
+
+/// Raw fields of a view that describe its position in the layout
+const POSITIONAL_ATTRIBUTES: [&str; 8] = [
+    "id",
+    "index",
+    "parent",
+    "parentType",
+    "preId",
+    "preIndex",
+    "preParent",
+    "preParentType",
+];
+
 impl From<AndroidView> for View {
     fn from(value: AndroidView) -> Self {
         View {
@@ -332,7 +469,10 @@ impl ViewType {
     pub fn get_type_id(&self) -> u8 {
         match self {
             ViewType::LinearLayout { .. } => 0,
-            ViewType::ScrollView { .. } => 2,
+            ViewType::ScrollView { orientation, .. } => match orientation {
+                Orientation::Horizontal => 2,
+                _ => 12,
+            },
             ViewType::Button { .. } => 3,
             ViewType::TextView { .. } => 4,
             ViewType::EditText { .. } => 5,
@@ -342,12 +482,57 @@ impl ViewType {
             ViewType::ListView { .. } => 9,
             ViewType::Spinner { .. } => 10,
             ViewType::CheckBox { .. } => 11,
-            ViewType::Switch { .. } => 12,
-            ViewType::SeekBar { .. } => 13,
-            ViewType::CalendarView { .. } => 14,
-            ViewType::Fab { .. } => 15,
-            ViewType::AdView { .. } => 16,
-            ViewType::MapView => 17,
+            ViewType::Switch { .. } => 13,
+            ViewType::SeekBar { .. } => 14,
+            ViewType::CalendarView { .. } => 15,
+            ViewType::Fab { .. } => 16,
+            ViewType::AdView { .. } => 17,
+            ViewType::MapView => 18,
+        }
+    }
+
+    /// Retrieves the name of this view type, as it's shown in sketchware
+    pub fn name(&self) -> &'static str {
+        match self {
+            ViewType::LinearLayout { .. } => "LinearLayout",
+            ViewType::ScrollView {
+                orientation: layout::Orientation::Horizontal,
+                ..
+            } => "HorizontalScrollView",
+            ViewType::ScrollView { .. } => "ScrollView",
+            ViewType::Button { .. } => "Button",
+            ViewType::TextView { .. } => "TextView",
+            ViewType::EditText { .. } => "EditText",
+            ViewType::ImageView { .. } => "ImageView",
+            ViewType::WebView => "WebView",
+            ViewType::ProgressBar { .. } => "ProgressBar",
+            ViewType::ListView { .. } => "ListView",
+            ViewType::Spinner { .. } => "Spinner",
+            ViewType::CheckBox { .. } => "CheckBox",
+            ViewType::Switch { .. } => "Switch",
+            ViewType::SeekBar { .. } => "SeekBar",
+            ViewType::CalendarView { .. } => "CalendarView",
+            ViewType::Fab { .. } => "Fab",
+            ViewType::AdView { .. } => "AdView",
+            ViewType::MapView => "MapView",
+        }
+    }
+
+    /// Checks whether views of this type can have children, only LinearLayouts and ScrollViews
+    /// (including horizontal ones) can
+    pub fn accepts_children(&self) -> bool {
+        matches!(
+            self,
+            ViewType::LinearLayout { .. } | ViewType::ScrollView { .. }
+        )
+    }
+
+    /// Retrieves the library that has to be enabled for this view type to work
+    pub fn required_library(&self) -> Option<LibraryKind> {
+        match self {
+            ViewType::AdView { .. } => Some(LibraryKind::AdMob),
+            ViewType::MapView => Some(LibraryKind::GoogleMap),
+            _ => None,
         }
     }
 
@@ -640,3 +825,28 @@ pub fn flatten_views(
 
     result
 }
+
+/// Retrieves every view of a tree of views in pre-order
+pub(crate) fn walk(views: &[View]) -> Vec<&View> {
+    fn add<'a>(views: &'a [View], result: &mut Vec<&'a View>) {
+        for view in views {
+            result.push(view);
+            add(&view.children, result);
+        }
+    }
+
+    let mut result = Vec::new();
+    add(views, &mut result);
+    result
+}
+
+/// Removes a view (along with its children) from a tree of views
+pub(crate) fn take_view(views: &mut Vec<View>, id: &str) -> Option<View> {
+    if let Some(position) = views.iter().position(|view| view.id == id) {
+        return Some(views.remove(position));
+    }
+
+    views
+        .iter_mut()
+        .find_map(|view| take_view(&mut view.children, id))
+}
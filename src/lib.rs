@@ -4,6 +4,12 @@ pub mod parser;
 #[cfg(feature = "api")]
 pub mod api;
 
+#[cfg(feature = "api")]
+pub mod lint;
+
+#[cfg(feature = "api")]
+pub mod render;
+
 pub(crate) mod util;
 
 /// We use [`ritelinked::LinkedHashMap`] to preserve insertion order while having a key-value pair
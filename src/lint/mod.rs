@@ -0,0 +1,227 @@
+//! Lints sketchware projects. A [`Linter`] runs a set of [`LintRule`]s over an
+//! [`api::SketchwareProject`](SketchwareProject) and collects their findings as [`Diagnostic`]s,
+//! the severity of each rule can be changed (or the rule turned off) with a [`LintConfig`].
+//!
+//! The built-in rules are listed in [`rules`], custom rules can be added with
+//! [`Linter::add_rule`].
+
+pub mod rules;
+
+use crate::api::block::BlockPath;
+use crate::api::SketchwareProject;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fmt::{Display, Formatter};
+
+/// How serious a diagnostic is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Severity::Info => "info",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        })
+    }
+}
+
+/// Where a diagnostic is found, screens are identified by their layout names
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Location {
+    Project,
+    Screen {
+        screen: String,
+    },
+    View {
+        screen: String,
+        id: String,
+    },
+    Component {
+        screen: String,
+        id: String,
+    },
+
+    /// An event or a moreblock, identified by its block container id
+    Container {
+        screen: String,
+        container: String,
+    },
+    Block {
+        screen: String,
+        container: String,
+        path: BlockPath,
+    },
+}
+
+impl Display for Location {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Location::Project => f.write_str("project"),
+            Location::Screen { screen } => f.write_str(screen),
+            Location::View { screen, id } => write!(f, "{}, view `{}`", screen, id),
+            Location::Component { screen, id } => write!(f, "{}, component `{}`", screen, id),
+            Location::Container { screen, container } => write!(f, "{}, {}", screen, container),
+            Location::Block {
+                screen,
+                container,
+                path,
+            } => write!(f, "{}, {} at {}", screen, container, path),
+        }
+    }
+}
+
+/// Something a [`LintRule`] found
+#[derive(Debug, Clone, PartialEq)]
+pub struct Finding {
+    pub location: Location,
+    pub message: String,
+}
+
+impl Finding {
+    pub fn new<S: ToString>(location: Location, message: S) -> Self {
+        Finding {
+            location,
+            message: message.to_string(),
+        }
+    }
+}
+
+/// A [`Finding`] along with the rule that found it and its severity
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Diagnostic {
+    pub rule: &'static str,
+    pub severity: Severity,
+    pub location: Location,
+    pub message: String,
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}[{}] {}: {}",
+            self.severity, self.rule, self.location, self.message
+        )
+    }
+}
+
+/// A check that's run over a whole project
+pub trait LintRule {
+    /// The name of this rule in kebab-case, used to refer to it in a [`LintConfig`]
+    fn name(&self) -> &'static str;
+
+    /// A short description of what this rule checks
+    fn description(&self) -> &'static str;
+
+    fn default_severity(&self) -> Severity;
+
+    fn check(&self, project: &SketchwareProject) -> Vec<Finding>;
+}
+
+/// Configures the rules of a [`Linter`], rules are referred to by their names
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LintConfig {
+    /// Rules that aren't run
+    pub disabled: HashSet<String>,
+
+    /// Severities that override the default severities of the rules
+    pub severities: HashMap<String, Severity>,
+}
+
+impl LintConfig {
+    /// Turns off a rule
+    pub fn disable(mut self, rule: &str) -> Self {
+        self.disabled.insert(rule.to_string());
+        self
+    }
+
+    /// Changes the severity of a rule
+    pub fn severity(mut self, rule: &str, severity: Severity) -> Self {
+        self.severities.insert(rule.to_string(), severity);
+        self
+    }
+
+    /// Retrieves the severity of a rule with this config, or `None` if it's turned off
+    pub fn severity_of(&self, rule: &dyn LintRule) -> Option<Severity> {
+        if self.disabled.contains(rule.name()) {
+            return None;
+        }
+
+        Some(
+            self.severities
+                .get(rule.name())
+                .copied()
+                .unwrap_or_else(|| rule.default_severity()),
+        )
+    }
+}
+
+/// Runs [`LintRule`]s over projects
+pub struct Linter {
+    rules: Vec<Box<dyn LintRule>>,
+    config: LintConfig,
+}
+
+impl Linter {
+    /// Creates a linter with every built-in rule, see [`rules::builtin`]
+    pub fn new(config: LintConfig) -> Self {
+        Linter {
+            rules: rules::builtin(),
+            config,
+        }
+    }
+
+    /// Creates a linter without any rule
+    pub fn empty(config: LintConfig) -> Self {
+        Linter {
+            rules: vec![],
+            config,
+        }
+    }
+
+    pub fn add_rule<R: LintRule + 'static>(&mut self, rule: R) {
+        self.rules.push(Box::new(rule));
+    }
+
+    pub fn rules(&self) -> impl Iterator<Item = &dyn LintRule> {
+        self.rules.iter().map(AsRef::as_ref)
+    }
+
+    /// Runs every rule that isn't turned off over a project, diagnostics are ordered by the
+    /// rules that found them
+    pub fn run(&self, project: &SketchwareProject) -> Vec<Diagnostic> {
+        self.rules()
+            .filter_map(|rule| Some((rule, self.config.severity_of(rule)?)))
+            .flat_map(|(rule, severity)| {
+                rule.check(project)
+                    .into_iter()
+                    .map(move |finding| Diagnostic {
+                        rule: rule.name(),
+                        severity,
+                        location: finding.location,
+                        message: finding.message,
+                    })
+            })
+            .collect()
+    }
+}
+
+impl Default for Linter {
+    fn default() -> Self {
+        Linter::new(LintConfig::default())
+    }
+}
+
+/// Lints a project with every built-in rule on their default severities
+pub fn lint(project: &SketchwareProject) -> Vec<Diagnostic> {
+    Linter::default().run(project)
+}
@@ -0,0 +1,368 @@
+//! The built-in lint rules
+
+use super::{Finding, LintRule, Location, Severity};
+use crate::api::block::{ArgValue, Argument};
+use crate::api::screen::Screen;
+use crate::api::view::ViewType;
+use crate::api::{ResourceId, SketchwareProject};
+use std::collections::HashSet;
+
+/// Every built-in rule
+pub fn builtin() -> Vec<Box<dyn LintRule>> {
+    vec![
+        Box::new(DuplicateViewId),
+        Box::new(EmptyEvent),
+        Box::new(UnbalancedBraces),
+        Box::new(MissingCustomView),
+        Box::new(MissingImage),
+        Box::new(MissingLibrary),
+    ]
+}
+
+/// Two or more views of a screen share the same id
+pub struct DuplicateViewId;
+
+impl LintRule for DuplicateViewId {
+    fn name(&self) -> &'static str {
+        "duplicate-view-id"
+    }
+
+    fn description(&self) -> &'static str {
+        "views of a screen must have unique ids"
+    }
+
+    fn default_severity(&self) -> Severity {
+        Severity::Error
+    }
+
+    fn check(&self, project: &SketchwareProject) -> Vec<Finding> {
+        let mut findings = Vec::new();
+
+        for screen in &project.screens {
+            let mut seen = HashSet::new();
+            let mut reported = HashSet::new();
+
+            for (id, _) in screen.all_views() {
+                if !seen.insert(id.to_owned()) && reported.insert(id.to_owned()) {
+                    findings.push(Finding::new(
+                        view_location(screen, &id),
+                        format!("the id `{}` is used by more than one view", id),
+                    ));
+                }
+            }
+        }
+
+        findings
+    }
+}
+
+/// An event without any block
+pub struct EmptyEvent;
+
+impl LintRule for EmptyEvent {
+    fn name(&self) -> &'static str {
+        "empty-event"
+    }
+
+    fn description(&self) -> &'static str {
+        "events should have at least one block"
+    }
+
+    fn default_severity(&self) -> Severity {
+        Severity::Warning
+    }
+
+    fn check(&self, project: &SketchwareProject) -> Vec<Finding> {
+        project
+            .screens
+            .iter()
+            .flat_map(|screen| {
+                screen
+                    .events
+                    .iter()
+                    .filter(|event| event.code.0.is_empty())
+                    .map(|event| {
+                        Finding::new(
+                            Location::Container {
+                                screen: screen.layout_name.to_owned(),
+                                container: event.get_block_container_id(),
+                            },
+                            format!("the event `{}` has no blocks", event.name),
+                        )
+                    })
+            })
+            .collect()
+    }
+}
+
+/// An `addSourceDirectly` block whose code has unbalanced curly braces. This is sometimes done
+/// on purpose to wrap the blocks after it, so it's only a warning.
+pub struct UnbalancedBraces;
+
+impl LintRule for UnbalancedBraces {
+    fn name(&self) -> &'static str {
+        "unbalanced-braces"
+    }
+
+    fn description(&self) -> &'static str {
+        "code put with `addSourceDirectly` should have balanced curly braces"
+    }
+
+    fn default_severity(&self) -> Severity {
+        Severity::Warning
+    }
+
+    fn check(&self, project: &SketchwareProject) -> Vec<Finding> {
+        let mut findings = Vec::new();
+
+        for screen in &project.screens {
+            for (container, blocks) in screen.block_containers() {
+                blocks.visit(|path, block| {
+                    if block.op_code != "addSourceDirectly" {
+                        return;
+                    }
+
+                    let code = block
+                        .content
+                        .get_args()
+                        .into_iter()
+                        .find_map(|arg| match arg {
+                            Argument::String {
+                                value: ArgValue::Value(code),
+                                ..
+                            } => Some(code),
+                            _ => None,
+                        });
+
+                    if let Some(message) = code.and_then(|code| check_braces(code)) {
+                        findings.push(Finding::new(
+                            Location::Block {
+                                screen: screen.layout_name.to_owned(),
+                                container: container.to_owned(),
+                                path: path.to_owned(),
+                            },
+                            message,
+                        ));
+                    }
+                });
+            }
+        }
+
+        findings
+    }
+}
+
+/// Checks whether the curly braces of java code are balanced, braces inside of strings,
+/// characters and comments are ignored. Returns what's wrong if they aren't.
+fn check_braces(code: &str) -> Option<String> {
+    let mut depth = 0usize;
+    let mut chars = code.chars().peekable();
+
+    while let Some(char) = chars.next() {
+        match char {
+            '{' => depth += 1,
+            '}' if depth == 0 => return Some("`}` without a matching `{`".to_string()),
+            '}' => depth -= 1,
+            '"' | '\'' => {
+                while let Some(next) = chars.next() {
+                    match next {
+                        '\\' => {
+                            chars.next();
+                        }
+                        '\n' => break,
+                        _ if next == char => break,
+                        _ => {}
+                    }
+                }
+            }
+            '/' if chars.peek() == Some(&'/') => {
+                for next in chars.by_ref() {
+                    if next == '\n' {
+                        break;
+                    }
+                }
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut previous = None;
+
+                for next in chars.by_ref() {
+                    if previous == Some('*') && next == '/' {
+                        break;
+                    }
+                    previous = Some(next);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    match depth {
+        0 => None,
+        1 => Some("1 `{` is never closed".to_string()),
+        depth => Some(format!("{} `{{`s are never closed", depth)),
+    }
+}
+
+/// A ListView whose custom view doesn't exist
+pub struct MissingCustomView;
+
+impl LintRule for MissingCustomView {
+    fn name(&self) -> &'static str {
+        "missing-custom-view"
+    }
+
+    fn description(&self) -> &'static str {
+        "the custom view of a ListView must exist"
+    }
+
+    fn default_severity(&self) -> Severity {
+        Severity::Error
+    }
+
+    fn check(&self, project: &SketchwareProject) -> Vec<Finding> {
+        let mut findings = Vec::new();
+
+        for screen in &project.screens {
+            for (id, view) in screen.all_views() {
+                if let Ok(ViewType::ListView { custom_view, .. }) = &view.view {
+                    if !custom_view.is_empty()
+                        && !project
+                            .custom_views
+                            .iter()
+                            .any(|custom| &custom.res_name == custom_view)
+                    {
+                        findings.push(Finding::new(
+                            view_location(screen, &id),
+                            format!("the custom view `{}` doesn't exist", custom_view),
+                        ));
+                    }
+                }
+            }
+        }
+
+        findings
+    }
+}
+
+/// An ImageView (or a FAB) whose image doesn't exist in the resources
+pub struct MissingImage;
+
+impl LintRule for MissingImage {
+    fn name(&self) -> &'static str {
+        "missing-image"
+    }
+
+    fn description(&self) -> &'static str {
+        "images used by ImageViews and FABs must exist in the resources"
+    }
+
+    fn default_severity(&self) -> Severity {
+        Severity::Error
+    }
+
+    fn check(&self, project: &SketchwareProject) -> Vec<Finding> {
+        let mut findings = Vec::new();
+
+        for screen in &project.screens {
+            for (id, view) in screen.all_views() {
+                let image = match &view.view {
+                    Ok(ViewType::ImageView { image_res_name, .. })
+                    | Ok(ViewType::Fab { image_res_name }) => image_res_name,
+                    _ => continue,
+                };
+
+                // default_image is bundled with sketchware
+                if image.is_empty()
+                    || image == "default_image"
+                    || project
+                        .resources
+                        .get_images()
+                        .contains_key(&ResourceId(image.to_owned()))
+                {
+                    continue;
+                }
+
+                findings.push(Finding::new(
+                    view_location(screen, &id),
+                    format!("the image `{}` doesn't exist", image),
+                ));
+            }
+        }
+
+        findings
+    }
+}
+
+/// A component or a view that needs a library which isn't enabled
+pub struct MissingLibrary;
+
+impl LintRule for MissingLibrary {
+    fn name(&self) -> &'static str {
+        "missing-library"
+    }
+
+    fn description(&self) -> &'static str {
+        "components and views that need a library can't be used without enabling it"
+    }
+
+    fn default_severity(&self) -> Severity {
+        Severity::Error
+    }
+
+    fn check(&self, project: &SketchwareProject) -> Vec<Finding> {
+        let libraries = &project.libraries;
+        let mut findings = Vec::new();
+
+        for screen in &project.screens {
+            for (id, component) in &screen.components {
+                let missing = match component.required_library() {
+                    Some(library) if !libraries.is_enabled(library) => library,
+                    _ => continue,
+                };
+
+                findings.push(Finding::new(
+                    Location::Component {
+                        screen: screen.layout_name.to_owned(),
+                        id: id.to_owned(),
+                    },
+                    format!(
+                        "the {} component `{}` needs {} to be enabled",
+                        component.name(),
+                        id,
+                        missing.name()
+                    ),
+                ));
+            }
+
+            for (id, view) in screen.all_views() {
+                let (view_type, missing) = match &view.view {
+                    Ok(view_type) => match view_type.required_library() {
+                        Some(library) if !libraries.is_enabled(library) => (view_type, library),
+                        _ => continue,
+                    },
+                    Err(_) => continue,
+                };
+
+                findings.push(Finding::new(
+                    view_location(screen, &id),
+                    format!(
+                        "the {} `{}` needs {} to be enabled",
+                        view_type.name(),
+                        id,
+                        missing.name()
+                    ),
+                ));
+            }
+        }
+
+        findings
+    }
+}
+
+fn view_location(screen: &Screen, id: &str) -> Location {
+    Location::View {
+        screen: screen.layout_name.to_owned(),
+        id: id.to_owned(),
+    }
+}
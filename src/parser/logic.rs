@@ -361,6 +361,7 @@ impl ScreenLogic {
 pub mod variable {
     use crate::parser::Parsable;
     use ritelinked::LinkedHashMap;
+    use serde::{Deserialize, Serialize};
     use std::convert::TryFrom;
     use std::num::ParseIntError;
     use thiserror::Error;
@@ -477,7 +478,7 @@ pub mod variable {
         InvalidVariableType(#[source] InvalidVariableTypeError),
     }
 
-    #[derive(Debug, Copy, Clone, Eq, PartialEq)]
+    #[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
     #[repr(u8)]
     pub enum VariableType {
         Boolean,
@@ -4,6 +4,7 @@ use crate::parser::logic::{LogicParseError, LogicReconstructionError};
 use crate::parser::resource::{ResourceParseError, ResourceReconstructionError};
 use crate::parser::view::{ViewParseError, ViewReconstructionError};
 use crate::CryptoError;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::path::PathBuf;
 use thiserror::Error;
@@ -335,7 +336,7 @@ pub enum SketchwareProjectReconstructionError {
 ///
 /// This enum is made so that swrs is portable and can be used across platforms with very little to
 /// no tweaking
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ResourceFileWrapper {
     /// A real path to a real file in the filesystem. swrs will use its path to determine what type
     /// of resource this is, filename as the resource name. and swrs will do a check if this file
@@ -391,7 +392,7 @@ impl ResourceFileWrapper {
     }
 }
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ResourceType {
     Image,
     Sound,
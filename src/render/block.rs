@@ -0,0 +1,616 @@
+use super::svg::{num, text_width, Style, SvgWriter};
+use crate::api::block::{
+    ArgValue, Argument, ArgumentBlockReturnType, Block, BlockCategory, BlockContent, BlockControl,
+    BlockType, Blocks, SpecItem,
+};
+use crate::api::screen::{Event, EventType, MoreBlock};
+use crate::color::Color;
+use std::fmt::Debug;
+
+/// Options used to render blocks, see [`render_blocks`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlockRenderOptions {
+    /// The space around the blocks
+    pub padding: f32,
+
+    /// The background of the image, gives a transparent background if None
+    pub background: Option<Color>,
+}
+
+impl Default for BlockRenderOptions {
+    fn default() -> Self {
+        BlockRenderOptions {
+            padding: 8.,
+            background: None,
+        }
+    }
+}
+
+const FONT_SIZE: f32 = 12.;
+const GAP: f32 = 4.;
+const PADDING_X: f32 = 8.;
+
+// minimum height of a row of a statement block
+const ROW_HEIGHT: f32 = 28.;
+const ELSE_HEIGHT: f32 = 22.;
+const SLOT_HEIGHT: f32 = 18.;
+const REPORTER_HEIGHT: f32 = 20.;
+
+// sizes of the parts of C and E shaped blocks
+const ARM_WIDTH: f32 = 14.;
+const FOOT_HEIGHT: f32 = 14.;
+const EMPTY_STACK_HEIGHT: f32 = 18.;
+
+// the curved top of event and moreblock definitions
+const HAT_HEIGHT: f32 = 14.;
+const HAT_WIDTH: f32 = 80.;
+
+// the puzzle-like notch on top of statement blocks and the bump under them
+const NOTCH_X: f32 = 10.;
+const NOTCH_WIDTH: f32 = 10.;
+const NOTCH_DEPTH: f32 = 3.;
+
+// long string arguments (like the ones in add source directly) are cut
+const MAX_LABEL_CHARS: usize = 40;
+
+/// Renders a stack of blocks into an svg image
+pub fn render_blocks(blocks: &Blocks, options: &BlockRenderOptions) -> String {
+    render(None, blocks, options)
+}
+
+/// Renders the code of an event into an svg image, topped with a hat block that contains the name
+/// of the event
+pub fn render_event(event: &Event, options: &BlockRenderOptions) -> String {
+    let mut label = BlockContent::builder();
+
+    label = match &event.event_type {
+        EventType::ViewEvent { id } | EventType::ComponentEvent { id, .. } => {
+            label.text("when").text(id).text(&event.name)
+        }
+        EventType::ActivityEvent => label.text("when").text(&event.name),
+    };
+
+    render(
+        Some(Hat {
+            content: label.build(),
+            color: BlockCategory::Control.into(),
+        }),
+        &event.code,
+        options,
+    )
+}
+
+/// Renders the code of a moreblock into an svg image, topped with a hat block that contains its
+/// spec along with its parameters
+pub fn render_more_block(more_block: &MoreBlock, options: &BlockRenderOptions) -> String {
+    let mut items = vec![SpecItem::Text("define".to_string())];
+    items.extend(more_block.spec.items.iter().cloned());
+
+    render(
+        Some(Hat {
+            content: BlockContent { items },
+            color: BlockCategory::MoreBlock.into(),
+        }),
+        &more_block.code,
+        options,
+    )
+}
+
+/// The definition block that is drawn on top of events and moreblocks
+struct Hat {
+    content: BlockContent,
+    color: Color,
+}
+
+fn render(hat: Option<Hat>, blocks: &Blocks, options: &BlockRenderOptions) -> String {
+    let padding = options.padding;
+
+    let hat_size = hat.as_ref().map(|hat| {
+        let (w, h) = content_size(&hat.content, true);
+        (
+            (w + PADDING_X * 2.).max(HAT_WIDTH + PADDING_X * 2.),
+            HAT_HEIGHT + (h + 10.).max(ROW_HEIGHT),
+        )
+    });
+    let (hat_w, hat_h) = hat_size.unwrap_or((0., 0.));
+    let (stack_w, stack_h) = stack_size(blocks);
+
+    // the bottom bump of the last block pokes out a bit
+    let width = hat_w.max(stack_w) + padding * 2.;
+    let height = hat_h + stack_h + NOTCH_DEPTH + padding * 2.;
+
+    let mut svg = SvgWriter::new(width, height);
+
+    if let Some(background) = options.background {
+        svg.rect(0., 0., width, height, &Style::fill(background));
+    }
+
+    if let Some(hat) = hat {
+        svg.start_group(&[("class", "hat")]);
+        svg.path(
+            &statement_path(
+                padding,
+                padding,
+                hat_w,
+                &[(hat_h - HAT_HEIGHT, true)],
+                true,
+                true,
+            ),
+            &block_style(hat.color),
+        );
+        draw_content(
+            &mut svg,
+            &hat.content,
+            padding + PADDING_X,
+            padding + HAT_HEIGHT + (hat_h - HAT_HEIGHT) / 2.,
+            hat.color,
+            true,
+        );
+        svg.end_group();
+    }
+
+    draw_stack(&mut svg, blocks, padding, padding + hat_h);
+
+    svg.finish()
+}
+
+/// Measures a stack of blocks, returns its width and height
+fn stack_size(blocks: &Blocks) -> (f32, f32) {
+    blocks.0.iter().fold((0., 0.), |(w, h), block| {
+        let (block_w, block_h) = block_size(block);
+        (w.max(block_w), h + block_h)
+    })
+}
+
+/// Measures a stack of blocks that is inside of a C or E shaped block
+fn nested_stack_size(blocks: Option<&Blocks>) -> (f32, f32) {
+    let (w, h) = blocks.map(stack_size).unwrap_or((0., 0.));
+    (w, h.max(EMPTY_STACK_HEIGHT))
+}
+
+/// Retrieves how many substacks / nests a block has
+fn nest_count(block: &Block) -> u8 {
+    match block.block_type {
+        BlockType::Control(BlockControl::OneNest) => 1,
+        BlockType::Control(BlockControl::TwoNest) => 2,
+        _ if block.sub_stack2.is_some() => 2,
+        _ if block.sub_stack1.is_some() => 1,
+        _ => 0,
+    }
+}
+
+/// Measures the header (the part that holds the content) of a statement block
+fn header_size(block: &Block) -> (f32, f32) {
+    let (w, h) = content_size(&block.content, false);
+
+    // leave some space for the notch, and the bump inside the mouth of C / E shaped blocks
+    let mut min_w = NOTCH_X + NOTCH_WIDTH + NOTCH_DEPTH * 2. + PADDING_X;
+    if nest_count(block) > 0 {
+        min_w += ARM_WIDTH;
+    }
+
+    ((w + PADDING_X * 2.).max(min_w), (h + 10.).max(ROW_HEIGHT))
+}
+
+/// Measures a block along with its substacks, returns its width and height
+fn block_size(block: &Block) -> (f32, f32) {
+    if let BlockType::Argument(return_type) = &block.block_type {
+        let (w, h) = content_size(&block.content, false);
+        let h = (h + 6.).max(REPORTER_HEIGHT);
+
+        return match return_type {
+            // the pointy sides of a boolean block takes more space
+            ArgumentBlockReturnType::Boolean => (w + h + 8., h),
+            _ => (w + h, h),
+        };
+    }
+
+    let (header_w, header_h) = header_size(block);
+
+    match nest_count(block) {
+        0 => (header_w, header_h),
+        1 => {
+            let (stack_w, stack_h) = nested_stack_size(block.sub_stack1.as_ref());
+            (
+                header_w.max(ARM_WIDTH + stack_w),
+                header_h + stack_h + FOOT_HEIGHT,
+            )
+        }
+        _ => {
+            let (stack1_w, stack1_h) = nested_stack_size(block.sub_stack1.as_ref());
+            let (stack2_w, stack2_h) = nested_stack_size(block.sub_stack2.as_ref());
+            (
+                header_w.max(ARM_WIDTH + stack1_w.max(stack2_w)),
+                header_h + stack1_h + ELSE_HEIGHT + stack2_h + FOOT_HEIGHT,
+            )
+        }
+    }
+}
+
+/// Measures the content of a block, `show_names` shows the names of the parameters that don't
+/// have any value (used for moreblock definitions)
+fn content_size(content: &BlockContent, show_names: bool) -> (f32, f32) {
+    let mut width = 0f32;
+    let mut height = FONT_SIZE;
+
+    for (index, item) in content.items.iter().enumerate() {
+        if index != 0 {
+            width += GAP;
+        }
+
+        let (w, h) = item_size(item, show_names);
+        width += w;
+        height = height.max(h);
+    }
+
+    (width, height)
+}
+
+fn item_size(item: &SpecItem, show_names: bool) -> (f32, f32) {
+    match item {
+        SpecItem::Text(text) => (text_width(text, FONT_SIZE), FONT_SIZE),
+        SpecItem::Parameter(arg) => match arg.block() {
+            Some(block) => block_size(block),
+            None => (slot_width(arg, &slot_label(arg, show_names)), SLOT_HEIGHT),
+        },
+    }
+}
+
+/// Retrieves the text that is going to be shown inside an argument slot
+fn slot_label(arg: &Argument, show_names: bool) -> String {
+    fn label<T: Debug + Clone + PartialEq>(
+        value: &ArgValue<T>,
+        name: Option<&String>,
+        show_names: bool,
+        to_string: impl Fn(&T) -> String,
+    ) -> String {
+        match value {
+            ArgValue::Value(value) => to_string(value),
+            ArgValue::BlockPlaceholder { block_id } => format!("@{}", block_id),
+            ArgValue::Empty if show_names => name.cloned().unwrap_or_default(),
+            _ => String::new(),
+        }
+    }
+
+    let text = match arg {
+        Argument::String { name, value } => label(value, name.as_ref(), show_names, String::clone),
+        Argument::Number { name, value } => label(value, name.as_ref(), show_names, |value| {
+            if value.fract() == 0. && value.abs() < 1e15 {
+                format!("{}", *value as i64)
+            } else {
+                value.to_string()
+            }
+        }),
+        Argument::Boolean { name, value } => {
+            label(value, name.as_ref(), show_names, bool::to_string)
+        }
+        Argument::Menu { name, value } => label(value, Some(name), show_names, String::clone),
+    };
+
+    // newlines aren't rendered in svg texts
+    let text = text.replace('\n', " ");
+
+    if text.chars().count() > MAX_LABEL_CHARS {
+        let mut cut = text.chars().take(MAX_LABEL_CHARS - 1).collect::<String>();
+        cut.push('…');
+        cut
+    } else {
+        text
+    }
+}
+
+fn slot_width(arg: &Argument, label: &str) -> f32 {
+    let width = (text_width(label, FONT_SIZE) + 10.).max(20.);
+
+    match arg {
+        Argument::Boolean { .. } => width + SLOT_HEIGHT / 2.,
+        _ => width,
+    }
+}
+
+/// Draws a stack of blocks at the given position, returns the height of the drawn stack
+fn draw_stack(svg: &mut SvgWriter, blocks: &Blocks, x: f32, y: f32) -> f32 {
+    let mut height = 0.;
+
+    for block in &blocks.0 {
+        height += draw_block(svg, block, x, y + height);
+    }
+
+    height
+}
+
+/// Draws a block (along with its arguments and substacks) at the given position, returns the
+/// height of the drawn block
+fn draw_block(svg: &mut SvgWriter, block: &Block, x: f32, y: f32) -> f32 {
+    let (r, g, b) = block.color.rgb();
+    let color = Color::from_rgb(r, g, b);
+
+    svg.start_group(&[("class", "block"), ("data-opcode", &block.op_code)]);
+
+    let height = if let BlockType::Argument(return_type) = &block.block_type {
+        let (w, h) = block_size(block);
+
+        if *return_type == ArgumentBlockReturnType::Boolean {
+            svg.path(&hexagon_path(x, y, w, h), &block_style(color));
+            draw_content(
+                svg,
+                &block.content,
+                x + h / 2. + 4.,
+                y + h / 2.,
+                color,
+                false,
+            );
+        } else {
+            svg.rounded_rect(x, y, w, h, h / 2., &block_style(color));
+            draw_content(svg, &block.content, x + h / 2., y + h / 2., color, false);
+        }
+
+        h
+    } else {
+        let (header_w, header_h) = header_size(block);
+        let nests = nest_count(block);
+
+        let mut sections = vec![(header_h, true)];
+        let stack1_h = nested_stack_size(block.sub_stack1.as_ref()).1;
+        let stack2_h = nested_stack_size(block.sub_stack2.as_ref()).1;
+
+        if nests >= 1 {
+            sections.push((stack1_h, false));
+        }
+        if nests >= 2 {
+            sections.push((ELSE_HEIGHT, true));
+            sections.push((stack2_h, false));
+        }
+        if nests >= 1 {
+            sections.push((FOOT_HEIGHT, true));
+        }
+
+        let ending = block.block_type == BlockType::Control(BlockControl::EndingBlock);
+
+        svg.path(
+            &statement_path(x, y, header_w, &sections, false, !ending),
+            &block_style(color),
+        );
+        draw_content(
+            svg,
+            &block.content,
+            x + PADDING_X,
+            y + header_h / 2.,
+            color,
+            false,
+        );
+
+        let mut height = header_h;
+
+        if nests >= 1 {
+            if let Some(stack) = &block.sub_stack1 {
+                draw_stack(svg, stack, x + ARM_WIDTH, y + height);
+            }
+            height += stack1_h;
+        }
+
+        if nests >= 2 {
+            svg.text(
+                x + PADDING_X,
+                y + height + ELSE_HEIGHT / 2. + FONT_SIZE * 0.35,
+                FONT_SIZE,
+                "else",
+                &Style::fill(text_color()),
+            );
+            height += ELSE_HEIGHT;
+
+            if let Some(stack) = &block.sub_stack2 {
+                draw_stack(svg, stack, x + ARM_WIDTH, y + height);
+            }
+            height += stack2_h;
+        }
+
+        if nests >= 1 {
+            height += FOOT_HEIGHT;
+        }
+
+        height
+    };
+
+    svg.end_group();
+
+    height
+}
+
+/// Draws the content of a block, `center_y` is the vertical center of the row
+fn draw_content(
+    svg: &mut SvgWriter,
+    content: &BlockContent,
+    x: f32,
+    center_y: f32,
+    color: Color,
+    show_names: bool,
+) {
+    let mut x = x;
+
+    for item in &content.items {
+        let (w, h) = item_size(item, show_names);
+
+        match item {
+            SpecItem::Text(text) => svg.text(
+                x,
+                center_y + FONT_SIZE * 0.35,
+                FONT_SIZE,
+                text,
+                &Style::fill(text_color()),
+            ),
+
+            SpecItem::Parameter(arg) => match arg.block() {
+                Some(block) => {
+                    draw_block(svg, block, x, center_y - h / 2.);
+                }
+                None => draw_slot(
+                    svg,
+                    arg,
+                    &slot_label(arg, show_names),
+                    x,
+                    center_y - h / 2.,
+                    w,
+                    color,
+                ),
+            },
+        }
+
+        x += w + GAP;
+    }
+}
+
+/// Draws an argument slot that doesn't have any block in it
+fn draw_slot(
+    svg: &mut SvgWriter,
+    arg: &Argument,
+    label: &str,
+    x: f32,
+    y: f32,
+    width: f32,
+    color: Color,
+) {
+    let darker = darken(color);
+    let white = Color::from_rgb(0xff, 0xff, 0xff);
+
+    let label_color = match arg {
+        Argument::String { .. } => {
+            svg.rect(
+                x,
+                y,
+                width,
+                SLOT_HEIGHT,
+                &Style::stroke(darker, 1.).with_fill(white),
+            );
+            Color::from_rgb(0, 0, 0)
+        }
+        Argument::Number { .. } => {
+            svg.rounded_rect(
+                x,
+                y,
+                width,
+                SLOT_HEIGHT,
+                SLOT_HEIGHT / 2.,
+                &Style::stroke(darker, 1.).with_fill(white),
+            );
+            Color::from_rgb(0, 0, 0)
+        }
+        Argument::Boolean { .. } => {
+            svg.path(
+                &hexagon_path(x, y, width, SLOT_HEIGHT),
+                &Style::fill(darker),
+            );
+            text_color()
+        }
+        Argument::Menu { .. } => {
+            svg.rounded_rect(x, y, width, SLOT_HEIGHT, 2., &Style::fill(darker));
+            text_color()
+        }
+    };
+
+    svg.text(
+        x + width / 2.,
+        y + SLOT_HEIGHT / 2. + FONT_SIZE * 0.35,
+        FONT_SIZE,
+        label,
+        &Style::fill(label_color).anchor("middle"),
+    );
+}
+
+/// Builds the outline of a statement block. `sections` are the heights of the rows (`true`) and
+/// the mouths of C / E shaped blocks (`false`) from top to bottom
+fn statement_path(
+    x: f32,
+    y: f32,
+    width: f32,
+    sections: &[(f32, bool)],
+    hat: bool,
+    bottom_bump: bool,
+) -> String {
+    let notch_end = NOTCH_X + NOTCH_WIDTH + NOTCH_DEPTH * 2.;
+    let mut current_y = y;
+
+    let mut path = if hat {
+        current_y += HAT_HEIGHT;
+        format!(
+            "M {} {} C {} {} {} {} {} {} H {}",
+            num(x),
+            num(current_y),
+            num(x + HAT_WIDTH / 4.),
+            num(y - HAT_HEIGHT / 4.),
+            num(x + HAT_WIDTH * 3. / 4.),
+            num(y - HAT_HEIGHT / 4.),
+            num(x + HAT_WIDTH),
+            num(current_y),
+            num(x + width)
+        )
+    } else {
+        format!(
+            "M {} {} H {} l {d} {d} h {} l {d} -{d} H {}",
+            num(x),
+            num(y),
+            num(x + NOTCH_X),
+            num(NOTCH_WIDTH),
+            num(x + width),
+            d = num(NOTCH_DEPTH),
+        )
+    };
+
+    for (height, is_row) in sections {
+        if *is_row {
+            current_y += height;
+            path.push_str(&format!(" V {}", num(current_y)));
+        } else {
+            // the bump where the blocks inside the mouth are attached to
+            path.push_str(&format!(
+                " H {} l -{d} {d} h -{} l -{d} -{d} H {}",
+                num(x + ARM_WIDTH + notch_end),
+                num(NOTCH_WIDTH),
+                num(x + ARM_WIDTH),
+                d = num(NOTCH_DEPTH),
+            ));
+            current_y += height;
+            path.push_str(&format!(" V {} H {}", num(current_y), num(x + width)));
+        }
+    }
+
+    if bottom_bump {
+        path.push_str(&format!(
+            " H {} l -{d} {d} h -{} l -{d} -{d}",
+            num(x + notch_end),
+            num(NOTCH_WIDTH),
+            d = num(NOTCH_DEPTH),
+        ));
+    }
+
+    path.push_str(&format!(" H {} Z", num(x)));
+    path
+}
+
+fn hexagon_path(x: f32, y: f32, width: f32, height: f32) -> String {
+    let half = height / 2.;
+
+    format!(
+        "M {} {} l {h} -{h} H {} l {h} {h} l -{h} {h} H {} Z",
+        num(x),
+        num(y + half),
+        num(x + width - half),
+        num(x + half),
+        h = num(half),
+    )
+}
+
+fn block_style(color: Color) -> Style {
+    Style::fill(color).with_stroke(darken(color), 1.)
+}
+
+fn text_color() -> Color {
+    Color::from_rgb(0xff, 0xff, 0xff)
+}
+
+fn darken(color: Color) -> Color {
+    let (r, g, b) = color.rgb();
+    let darken = |c: u8| (c as f32 * 0.8) as u8;
+
+    Color::from_rgb(darken(r), darken(g), darken(b))
+}
@@ -0,0 +1,972 @@
+use super::svg::{text_width, Style, SvgWriter};
+use crate::api::screen::Screen;
+use crate::api::view::{View, ViewType};
+use crate::api::Colors;
+use crate::color::Color;
+use crate::parser::project::ProjectColorPalette;
+use crate::parser::view::models::layout::gravity::Gravity;
+use crate::parser::view::models::layout::{Orientation, Size};
+use crate::parser::view::models::text::TextType;
+
+/// Options used to render a layout, see [`render_screen`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct LayoutRenderOptions {
+    /// The width of the rendered device screen (in dp)
+    pub width: f32,
+
+    /// The height of the rendered device screen (in dp)
+    pub height: f32,
+
+    /// Color of the toolbar
+    pub color_primary: Color,
+
+    /// Color of the status bar
+    pub color_primary_dark: Color,
+
+    /// Color of the FAB and of checked widgets
+    pub color_accent: Color,
+}
+
+impl LayoutRenderOptions {
+    /// Creates render options that uses the colors of a project
+    pub fn from_colors(colors: &Colors) -> Self {
+        LayoutRenderOptions {
+            color_primary: colors.color_primary,
+            color_primary_dark: colors.color_primary_dark,
+            color_accent: colors.color_accent,
+            ..Default::default()
+        }
+    }
+}
+
+impl Default for LayoutRenderOptions {
+    fn default() -> Self {
+        // uses the default sketchware palette
+        let palette = ProjectColorPalette::default();
+
+        LayoutRenderOptions {
+            width: 360.,
+            height: 640.,
+            color_primary: palette.color_primary,
+            color_primary_dark: palette.color_primary_dark,
+            color_accent: palette.color_accent,
+        }
+    }
+}
+
+const STATUS_BAR_HEIGHT: f32 = 24.;
+const TOOLBAR_HEIGHT: f32 = 56.;
+const FAB_SIZE: f32 = 56.;
+
+/// Renders the layout of a screen into an svg wireframe, along with its status bar, its toolbar
+/// (if enabled), its drawer icon (if the drawer is enabled) and its FAB (if enabled).
+///
+/// The ids of the clip paths are prefixed with the layout name of the screen, so the svgs of
+/// several screens can be inlined in the same page
+pub fn render_screen(screen: &Screen, options: &LayoutRenderOptions) -> String {
+    let mut svg = SvgWriter::new(options.width, options.height)
+        .with_id_prefix(&format!("{}-", screen.layout_name));
+    let white = Color::from_rgb(0xff, 0xff, 0xff);
+
+    svg.rect(0., 0., options.width, options.height, &Style::fill(white));
+
+    let mut top = 0.;
+
+    if !screen.fullscreen_enabled {
+        svg.rect(
+            0.,
+            0.,
+            options.width,
+            STATUS_BAR_HEIGHT,
+            &Style::fill(options.color_primary_dark),
+        );
+        top += STATUS_BAR_HEIGHT;
+    }
+
+    if screen.toolbar_enabled {
+        svg.start_group(&[("class", "toolbar")]);
+        svg.rect(
+            0.,
+            top,
+            options.width,
+            TOOLBAR_HEIGHT,
+            &Style::fill(options.color_primary),
+        );
+
+        let mut title_x = 16.;
+
+        if screen.drawer_enabled {
+            // the "hamburger" icon
+            for i in 0..3 {
+                let y = top + 21. + i as f32 * 7.;
+                svg.rect(16., y, 18., 2., &Style::fill(white));
+            }
+            title_x = 72.;
+        }
+
+        let mut style = Style::fill(white);
+        style.bold = true;
+        svg.text(
+            title_x,
+            top + TOOLBAR_HEIGHT / 2. + 7.,
+            20.,
+            &screen.layout_name,
+            &style,
+        );
+        svg.end_group();
+
+        top += TOOLBAR_HEIGHT;
+    }
+
+    let content = Rect {
+        x: 0.,
+        y: top,
+        w: options.width,
+        h: options.height - top,
+    };
+
+    svg.start_clip("content", content.x, content.y, content.w, content.h);
+    draw_children(
+        &mut svg,
+        &screen.layout,
+        content,
+        Axis::Vertical,
+        0,
+        options,
+    );
+    svg.end_group();
+
+    if screen.fab_enabled {
+        let cx = options.width - 16. - FAB_SIZE / 2.;
+        let cy = options.height - 16. - FAB_SIZE / 2.;
+
+        svg.start_group(&[("class", "fab")]);
+        svg.circle(cx, cy, FAB_SIZE / 2., &Style::fill(options.color_accent));
+
+        if let Some(Ok(ViewType::Fab { image_res_name })) = screen.fab.as_ref().map(|f| &f.view) {
+            svg.text(
+                cx,
+                cy + 4.,
+                10.,
+                image_res_name,
+                &Style::fill(white).anchor("middle"),
+            );
+        } else {
+            svg.text(cx, cy + 8., 24., "+", &Style::fill(white).anchor("middle"));
+        }
+        svg.end_group();
+    }
+
+    svg.finish()
+}
+
+/// Renders a list of views (as the children of a vertical root layout) into an svg wireframe
+/// without any of the screen decorations. This can be used to render custom views
+pub fn render_views(views: &[View], options: &LayoutRenderOptions) -> String {
+    let mut svg = SvgWriter::new(options.width, options.height);
+
+    svg.rect(
+        0.,
+        0.,
+        options.width,
+        options.height,
+        &Style::fill(Color::from_rgb(0xff, 0xff, 0xff)),
+    );
+
+    draw_children(
+        &mut svg,
+        views,
+        Rect {
+            x: 0.,
+            y: 0.,
+            w: options.width,
+            h: options.height,
+        },
+        Axis::Vertical,
+        0,
+        options,
+    );
+
+    svg.finish()
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Rect {
+    x: f32,
+    y: f32,
+    w: f32,
+    h: f32,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum Axis {
+    Vertical,
+    Horizontal,
+}
+
+/// Horizontal or vertical placement that is retrieved from a [`Gravity`]
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum Placement {
+    Start,
+    Center,
+    End,
+}
+
+fn horizontal_placement(gravity: Gravity) -> Option<Placement> {
+    match gravity.0 & 0x07 {
+        0 => None,
+        1 => Some(Placement::Center),
+        5 => Some(Placement::End),
+        _ => Some(Placement::Start),
+    }
+}
+
+fn vertical_placement(gravity: Gravity) -> Option<Placement> {
+    match gravity.0 & 0x70 {
+        0 => None,
+        0x10 => Some(Placement::Center),
+        0x50 => Some(Placement::End),
+        _ => Some(Placement::Start),
+    }
+}
+
+fn place(placement: Option<Placement>, available: f32, size: f32) -> f32 {
+    match placement {
+        Some(Placement::Center) => ((available - size) / 2.).max(0.),
+        Some(Placement::End) => (available - size).max(0.),
+        _ => 0.,
+    }
+}
+
+fn container_info(view: &View) -> Option<(Axis, Gravity, bool)> {
+    match &view.view {
+        Ok(ViewType::LinearLayout {
+            orientation,
+            gravity,
+        }) => Some((
+            if *orientation == Orientation::Vertical {
+                Axis::Vertical
+            } else {
+                Axis::Horizontal
+            },
+            *gravity,
+            false,
+        )),
+        Ok(ViewType::ScrollView {
+            orientation,
+            gravity,
+        }) => Some((
+            if *orientation == Orientation::Horizontal {
+                Axis::Horizontal
+            } else {
+                Axis::Vertical
+            },
+            *gravity,
+            true,
+        )),
+        _ => None,
+    }
+}
+
+/// Measures a view given the available space (margins excluded), returns its width and height
+fn measure(view: &View, available_w: f32, available_h: f32) -> (f32, f32) {
+    let padding_w = (view.padding.left + view.padding.right) as f32;
+    let padding_h = (view.padding.top + view.padding.bottom) as f32;
+
+    let fixed_or_match = |size: Size, available: f32| match size {
+        Size::Fixed(dp) => Some(dp.max(0) as f32),
+        Size::MatchParent => Some(available.max(0.)),
+        Size::WrapContent => None,
+    };
+
+    let width = fixed_or_match(view.width, available_w);
+    let height = fixed_or_match(view.height, available_h);
+
+    if let (Some(width), Some(height)) = (width, height) {
+        return (width, height);
+    }
+
+    let inner_w = width.unwrap_or(available_w) - padding_w;
+    let inner_h = height.unwrap_or(available_h) - padding_h;
+
+    let (content_w, content_h) = if let Some((axis, _, scrolls)) = container_info(view) {
+        let (mut main, mut cross) = (0f32, 0f32);
+
+        for child in &view.children {
+            let (margin_w, margin_h) = margins(child);
+            let (child_w, child_h) = if scrolls {
+                match axis {
+                    Axis::Vertical => measure(child, inner_w - margin_w, f32::MAX / 4.),
+                    Axis::Horizontal => measure(child, f32::MAX / 4., inner_h - margin_h),
+                }
+            } else {
+                measure(child, inner_w - margin_w, inner_h - margin_h)
+            };
+
+            let (child_main, child_cross) = match axis {
+                Axis::Vertical => (child_h + margin_h, child_w + margin_w),
+                Axis::Horizontal => (child_w + margin_w, child_h + margin_h),
+            };
+
+            main += child_main;
+            cross = cross.max(child_cross);
+        }
+
+        match axis {
+            Axis::Vertical => (cross, main),
+            Axis::Horizontal => (main, cross),
+        }
+    } else {
+        leaf_content_size(view, inner_w)
+    };
+
+    (
+        width
+            .unwrap_or(content_w + padding_w)
+            .min(available_w.max(0.)),
+        height.unwrap_or(content_h + padding_h),
+    )
+}
+
+fn margins(view: &View) -> (f32, f32) {
+    (
+        (view.margin.left + view.margin.right) as f32,
+        (view.margin.top + view.margin.bottom) as f32,
+    )
+}
+
+/// Retrieves the text, text size and the amount of lines a text-based view has
+fn text_of(view_type: &ViewType) -> Option<(&str, f32, u32, bool)> {
+    Some(match view_type {
+        ViewType::Button {
+            text, text_size, ..
+        } => (text.as_str(), *text_size as f32, 0, false),
+        ViewType::TextView {
+            text,
+            text_size,
+            lines,
+            single_line,
+            ..
+        } => (text.as_str(), *text_size as f32, *lines, *single_line),
+        ViewType::EditText {
+            text,
+            hint,
+            text_size,
+            lines,
+            single_line,
+            ..
+        } => (
+            if text.is_empty() { hint } else { text }.as_str(),
+            *text_size as f32,
+            *lines,
+            *single_line,
+        ),
+        ViewType::CheckBox {
+            text, text_size, ..
+        }
+        | ViewType::Switch {
+            text, text_size, ..
+        } => (text.as_str(), *text_size as f32, 0, false),
+        _ => return None,
+    })
+}
+
+/// Breaks a text into lines that fits inside the given width
+fn wrap_text(text: &str, max_width: f32, size: f32, single_line: bool) -> Vec<String> {
+    // the small epsilon keeps a text from wrapping when it is given exactly its own width
+    let max_chars = (((max_width + 0.01) / (size * 0.55)).floor() as usize).max(1);
+    let mut result = Vec::new();
+
+    for line in text.split('\n') {
+        let chars = line.chars().collect::<Vec<_>>();
+
+        if chars.is_empty() {
+            result.push(String::new());
+        }
+
+        for chunk in chars.chunks(max_chars) {
+            result.push(chunk.iter().collect());
+        }
+    }
+
+    if single_line {
+        result.truncate(1);
+    }
+
+    result
+}
+
+fn leaf_content_size(view: &View, available_w: f32) -> (f32, f32) {
+    let view_type = match &view.view {
+        Ok(view_type) => view_type,
+        Err(_) => return (48., 48.),
+    };
+
+    // the space a glyph of a checkbox or a switch takes
+    let glyph_w = match view_type {
+        ViewType::CheckBox { .. } => 32.,
+        ViewType::Switch { .. } => 48.,
+        _ => 0.,
+    };
+
+    if let Some((text, size, lines, single_line)) = text_of(view_type) {
+        let wrapped = wrap_text(text, available_w - glyph_w, size, single_line);
+        let line_count = (wrapped.len() as u32).max(lines).max(1);
+        let text_w = wrapped
+            .iter()
+            .map(|l| text_width(l, size))
+            .fold(0f32, f32::max);
+
+        let (w, h) = (text_w + glyph_w, line_count as f32 * size * 1.25);
+
+        return match view_type {
+            // buttons and edittexts have minimum sizes
+            ViewType::Button { .. } => (w.max(64.), h.max(32.)),
+            ViewType::EditText { .. } => (w.max(80.), h.max(size * 1.25 + 8.)),
+            ViewType::Switch { .. } | ViewType::CheckBox { .. } => (w, h.max(24.)),
+            _ => (w, h),
+        };
+    }
+
+    match view_type {
+        ViewType::ImageView { .. } => (64., 64.),
+        ViewType::WebView => (available_w, 200.),
+        ViewType::ProgressBar { progress_style, .. } => {
+            if progress_style.contains("Horizontal") {
+                (160., 8.)
+            } else {
+                (48., 48.)
+            }
+        }
+        ViewType::ListView { .. } => (available_w, 160.),
+        ViewType::Spinner { .. } => (120., 40.),
+        ViewType::SeekBar { .. } => (160., 24.),
+        ViewType::CalendarView { .. } => (280., 300.),
+        ViewType::Fab { .. } => (FAB_SIZE, FAB_SIZE),
+        ViewType::AdView { .. } => (320., 50.),
+        ViewType::MapView => (available_w, 240.),
+        _ => (0., 0.),
+    }
+}
+
+/// Lays out and draws a list of children (of a linear layout) in the given area
+fn draw_children(
+    svg: &mut SvgWriter,
+    children: &[View],
+    area: Rect,
+    axis: Axis,
+    gravity: u8,
+    options: &LayoutRenderOptions,
+) {
+    let gravity = Gravity(gravity);
+
+    // the main size of every child, weighted children are resolved later
+    let mut sizes = Vec::with_capacity(children.len());
+    let mut used = 0f32;
+    let mut total_weight = 0u32;
+
+    for child in children {
+        let (margin_w, margin_h) = margins(child);
+        let (available_w, available_h) = match axis {
+            Axis::Vertical => (area.w - margin_w, area.h - used - margin_h),
+            Axis::Horizontal => (area.w - used - margin_w, area.h - margin_h),
+        };
+
+        let (w, h) = measure(child, available_w, available_h);
+
+        if child.weight > 0 {
+            total_weight += child.weight;
+            sizes.push((w, h));
+            continue;
+        }
+
+        used += match axis {
+            Axis::Vertical => h + margin_h,
+            Axis::Horizontal => w + margin_w,
+        };
+        sizes.push((w, h));
+    }
+
+    let main_available = match axis {
+        Axis::Vertical => area.h,
+        Axis::Horizontal => area.w,
+    };
+    let leftover = (main_available - used).max(0.);
+
+    // give the leftover space to the weighted children
+    if total_weight > 0 {
+        for (child, size) in children.iter().zip(sizes.iter_mut()) {
+            if child.weight == 0 {
+                continue;
+            }
+
+            let share = leftover * child.weight as f32 / total_weight as f32;
+            let (margin_w, margin_h) = margins(child);
+
+            match axis {
+                Axis::Vertical => size.1 = (share - margin_h).max(0.),
+                Axis::Horizontal => size.0 = (share - margin_w).max(0.),
+            }
+            used += share;
+        }
+    }
+
+    let mut cursor = match axis {
+        Axis::Vertical => place(vertical_placement(gravity), area.h, used),
+        Axis::Horizontal => place(horizontal_placement(gravity), area.w, used),
+    };
+
+    for (child, (w, h)) in children.iter().zip(sizes) {
+        let m = &child.margin;
+
+        let frame = match axis {
+            Axis::Vertical => {
+                let placement = horizontal_placement(child.layout_gravity)
+                    .or_else(|| horizontal_placement(gravity));
+                let cross_space = area.w - (m.left + m.right) as f32;
+
+                let frame = Rect {
+                    x: area.x + m.left as f32 + place(placement, cross_space, w),
+                    y: area.y + cursor + m.top as f32,
+                    w,
+                    h,
+                };
+                cursor += h + (m.top + m.bottom) as f32;
+                frame
+            }
+
+            Axis::Horizontal => {
+                let placement = vertical_placement(child.layout_gravity)
+                    .or_else(|| vertical_placement(gravity));
+                let cross_space = area.h - (m.top + m.bottom) as f32;
+
+                let frame = Rect {
+                    x: area.x + cursor + m.left as f32,
+                    y: area.y + m.top as f32 + place(placement, cross_space, h),
+                    w,
+                    h,
+                };
+                cursor += w + (m.left + m.right) as f32;
+                frame
+            }
+        };
+
+        draw_view(svg, child, frame, options);
+    }
+}
+
+fn draw_view(svg: &mut SvgWriter, view: &View, frame: Rect, options: &LayoutRenderOptions) {
+    let type_name = match &view.view {
+        Ok(view_type) => view_type.name(),
+        Err(_) => "Unknown",
+    };
+
+    svg.start_group(&[("data-id", &view.id), ("class", type_name)]);
+
+    if view.background_color.alpha() != 0 {
+        svg.rect(
+            frame.x,
+            frame.y,
+            frame.w,
+            frame.h,
+            &Style::fill(view.background_color),
+        );
+    }
+
+    // the wireframe outline of this view
+    svg.rect(
+        frame.x,
+        frame.y,
+        frame.w,
+        frame.h,
+        &Style::stroke(Color::from_rgb(0xbd, 0xbd, 0xbd), 1.).dashed(),
+    );
+
+    let inner = Rect {
+        x: frame.x + view.padding.left as f32,
+        y: frame.y + view.padding.top as f32,
+        w: (frame.w - (view.padding.left + view.padding.right) as f32).max(0.),
+        h: (frame.h - (view.padding.top + view.padding.bottom) as f32).max(0.),
+    };
+
+    match &view.view {
+        Ok(view_type) => {
+            if let Some((axis, gravity, scrolls)) = container_info(view) {
+                if scrolls {
+                    svg.start_clip(
+                        &format!("clip-{}", view.id),
+                        frame.x,
+                        frame.y,
+                        frame.w,
+                        frame.h,
+                    );
+                    draw_children(svg, &view.children, inner, axis, gravity.0, options);
+                    svg.end_group();
+                } else {
+                    draw_children(svg, &view.children, inner, axis, gravity.0, options);
+                }
+            } else {
+                draw_widget(svg, view_type, inner, options);
+            }
+        }
+
+        Err(_) => {
+            svg.text(
+                inner.x + inner.w / 2.,
+                inner.y + inner.h / 2. + 4.,
+                10.,
+                &format!("? {}", view.raw.r#type),
+                &Style::fill(Color::from_rgb(0x75, 0x75, 0x75)).anchor("middle"),
+            );
+        }
+    }
+
+    svg.end_group();
+}
+
+fn draw_text(svg: &mut SvgWriter, text: &str, area: Rect, size: f32, color: Color, single: bool) {
+    for (index, line) in wrap_text(text, area.w, size, single).iter().enumerate() {
+        svg.text(
+            area.x,
+            area.y + size + index as f32 * size * 1.25,
+            size,
+            line,
+            &Style::fill(color),
+        );
+    }
+}
+
+fn draw_widget(
+    svg: &mut SvgWriter,
+    view_type: &ViewType,
+    area: Rect,
+    options: &LayoutRenderOptions,
+) {
+    let grey = Color::from_rgb(0x9e, 0x9e, 0x9e);
+    let light_grey = Color::from_rgb(0xe0, 0xe0, 0xe0);
+    let dark_grey = Color::from_rgb(0x61, 0x61, 0x61);
+    let center_y = area.y + area.h / 2.;
+
+    match view_type {
+        ViewType::LinearLayout { .. } | ViewType::ScrollView { .. } => {}
+
+        ViewType::Button {
+            text,
+            text_color,
+            text_size,
+            text_style,
+        } => {
+            svg.rounded_rect(area.x, area.y, area.w, area.h, 2., &Style::fill(light_grey));
+
+            let mut style = Style::fill(*text_color).anchor("middle");
+            style.bold = matches!(text_style, TextType::Bold | TextType::BoldItalic);
+            style.italic = matches!(text_style, TextType::Italic | TextType::BoldItalic);
+
+            svg.text(
+                area.x + area.w / 2.,
+                center_y + *text_size as f32 * 0.35,
+                *text_size as f32,
+                text,
+                &style,
+            );
+        }
+
+        ViewType::TextView {
+            text,
+            text_color,
+            text_size,
+            single_line,
+            ..
+        } => draw_text(
+            svg,
+            text,
+            area,
+            *text_size as f32,
+            *text_color,
+            *single_line,
+        ),
+
+        ViewType::EditText {
+            text,
+            text_color,
+            text_size,
+            single_line,
+            hint,
+            hint_color,
+            ..
+        } => {
+            if text.is_empty() {
+                draw_text(
+                    svg,
+                    hint,
+                    area,
+                    *text_size as f32,
+                    *hint_color,
+                    *single_line,
+                );
+            } else {
+                draw_text(
+                    svg,
+                    text,
+                    area,
+                    *text_size as f32,
+                    *text_color,
+                    *single_line,
+                );
+            }
+
+            // the underline of an edittext
+            let y = area.y + area.h - 1.;
+            svg.line(area.x, y, area.x + area.w, y, &Style::stroke(grey, 1.));
+        }
+
+        ViewType::ImageView { image_res_name, .. } => {
+            svg.rect(area.x, area.y, area.w, area.h, &Style::fill(light_grey));
+            svg.line(
+                area.x,
+                area.y,
+                area.x + area.w,
+                area.y + area.h,
+                &Style::stroke(grey, 1.),
+            );
+            svg.line(
+                area.x + area.w,
+                area.y,
+                area.x,
+                area.y + area.h,
+                &Style::stroke(grey, 1.),
+            );
+            svg.text(
+                area.x + area.w / 2.,
+                center_y + 4.,
+                10.,
+                image_res_name,
+                &Style::fill(dark_grey).anchor("middle"),
+            );
+        }
+
+        ViewType::ProgressBar {
+            max_progress,
+            progress,
+            indeterminate,
+            progress_style,
+        } => {
+            if progress_style.contains("Horizontal") {
+                svg.rect(area.x, center_y - 2., area.w, 4., &Style::fill(light_grey));
+
+                let ratio = if *indeterminate {
+                    0.5
+                } else {
+                    ratio(*progress, *max_progress)
+                };
+                svg.rect(
+                    area.x,
+                    center_y - 2.,
+                    area.w * ratio,
+                    4.,
+                    &Style::fill(options.color_accent),
+                );
+            } else {
+                let radius = (area.w.min(area.h) / 2. - 4.).max(2.);
+                let cx = area.x + area.w / 2.;
+
+                svg.circle(cx, center_y, radius, &Style::stroke(light_grey, 4.));
+                // a quarter arc, to make it look like it's spinning
+                svg.path(
+                    &format!(
+                        "M {} {} A {r} {r} 0 0 1 {} {}",
+                        cx,
+                        center_y - radius,
+                        cx + radius,
+                        center_y,
+                        r = radius
+                    ),
+                    &Style::stroke(options.color_accent, 4.),
+                );
+            }
+        }
+
+        ViewType::ListView { custom_view, .. } => {
+            let row_height = 48.;
+            let mut y = area.y;
+
+            while y + row_height <= area.y + area.h {
+                svg.text(
+                    area.x + 8.,
+                    y + row_height / 2. + 4.,
+                    12.,
+                    if custom_view.is_empty() {
+                        "list item"
+                    } else {
+                        custom_view
+                    },
+                    &Style::fill(grey),
+                );
+                y += row_height;
+                svg.line(
+                    area.x,
+                    y,
+                    area.x + area.w,
+                    y,
+                    &Style::stroke(light_grey, 1.),
+                );
+            }
+        }
+
+        ViewType::Spinner { .. } => {
+            let x = area.x + area.w - 16.;
+            svg.path(
+                &format!("M {} {} l 10 0 l -5 6 z", x, center_y - 3.),
+                &Style::fill(dark_grey),
+            );
+        }
+
+        ViewType::CheckBox {
+            checked,
+            text,
+            text_color,
+            text_size,
+            ..
+        } => {
+            let box_y = center_y - 9.;
+
+            if *checked {
+                svg.rounded_rect(
+                    area.x,
+                    box_y,
+                    18.,
+                    18.,
+                    2.,
+                    &Style::fill(options.color_accent),
+                );
+                svg.path(
+                    &format!("M {} {} l 4 4 l 8 -8", area.x + 4., box_y + 9.),
+                    &Style::stroke(Color::from_rgb(0xff, 0xff, 0xff), 2.),
+                );
+            } else {
+                svg.rounded_rect(area.x, box_y, 18., 18., 2., &Style::stroke(dark_grey, 2.));
+            }
+
+            svg.text(
+                area.x + 32.,
+                center_y + *text_size as f32 * 0.35,
+                *text_size as f32,
+                text,
+                &Style::fill(*text_color),
+            );
+        }
+
+        ViewType::Switch {
+            checked,
+            text,
+            text_color,
+            text_size,
+            ..
+        } => {
+            svg.text(
+                area.x,
+                center_y + *text_size as f32 * 0.35,
+                *text_size as f32,
+                text,
+                &Style::fill(*text_color),
+            );
+
+            let track_x = area.x + area.w - 36.;
+            let (track_color, thumb_x) = if *checked {
+                (options.color_accent, track_x + 26.)
+            } else {
+                (grey, track_x + 8.)
+            };
+
+            svg.rounded_rect(
+                track_x,
+                center_y - 7.,
+                34.,
+                14.,
+                7.,
+                &Style::fill(track_color),
+            );
+            svg.circle(
+                thumb_x,
+                center_y,
+                10.,
+                &Style::fill(if *checked {
+                    options.color_accent
+                } else {
+                    Color::from_rgb(0xf5, 0xf5, 0xf5)
+                })
+                .with_stroke(light_grey, 1.),
+            );
+        }
+
+        ViewType::SeekBar {
+            max_progress,
+            progress,
+        } => {
+            let thumb_x = area.x + 8. + (area.w - 16.) * ratio(*progress, *max_progress);
+
+            svg.line(
+                area.x + 8.,
+                center_y,
+                area.x + area.w - 8.,
+                center_y,
+                &Style::stroke(light_grey, 2.),
+            );
+            svg.line(
+                area.x + 8.,
+                center_y,
+                thumb_x,
+                center_y,
+                &Style::stroke(options.color_accent, 2.),
+            );
+            svg.circle(thumb_x, center_y, 8., &Style::fill(options.color_accent));
+        }
+
+        ViewType::Fab { image_res_name } => {
+            let radius = area.w.min(area.h) / 2.;
+            svg.circle(
+                area.x + area.w / 2.,
+                center_y,
+                radius,
+                &Style::fill(options.color_accent),
+            );
+            svg.text(
+                area.x + area.w / 2.,
+                center_y + 4.,
+                10.,
+                image_res_name,
+                &Style::fill(Color::from_rgb(0xff, 0xff, 0xff)).anchor("middle"),
+            );
+        }
+
+        ViewType::WebView
+        | ViewType::CalendarView { .. }
+        | ViewType::AdView { .. }
+        | ViewType::MapView => {
+            svg.rect(area.x, area.y, area.w, area.h, &Style::fill(light_grey));
+
+            let label = match view_type {
+                ViewType::AdView { adview_size } if !adview_size.is_empty() => {
+                    format!("AdView ({})", adview_size)
+                }
+                _ => view_type.name().to_string(),
+            };
+
+            svg.text(
+                area.x + area.w / 2.,
+                center_y + 4.,
+                12.,
+                &label,
+                &Style::fill(dark_grey).anchor("middle"),
+            );
+        }
+    }
+}
+
+fn ratio(progress: u32, max: u32) -> f32 {
+    if max == 0 {
+        0.
+    } else {
+        (progress as f32 / max as f32).min(1.)
+    }
+}
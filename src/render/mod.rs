@@ -0,0 +1,7 @@
+//! Renders sketchware projects into images, currently only supports svg
+//!
+//! - [`layout`] renders screen layouts into wireframes
+//! - [`block`] renders blocks, events and moreblocks in sketchware's visual style
+pub mod block;
+pub mod layout;
+pub(crate) mod svg;
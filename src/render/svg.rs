@@ -0,0 +1,261 @@
+use crate::color::Color;
+use crate::util::escape_xml;
+use std::fmt::Write;
+
+/// A tiny svg document builder used by the renderers, it only supports the handful of elements
+/// that we actually need
+pub(crate) struct SvgWriter {
+    width: f32,
+    height: f32,
+    content: String,
+    id_prefix: String,
+}
+
+impl SvgWriter {
+    pub fn new(width: f32, height: f32) -> Self {
+        SvgWriter {
+            width,
+            height,
+            content: String::new(),
+            id_prefix: String::new(),
+        }
+    }
+
+    /// Prefixes every element id written by this writer, so that several documents can be
+    /// inlined in the same page without their ids colliding
+    pub fn with_id_prefix(mut self, prefix: &str) -> Self {
+        self.id_prefix = prefix.to_string();
+        self
+    }
+
+    pub fn rect(&mut self, x: f32, y: f32, width: f32, height: f32, style: &Style) {
+        let _ = writeln!(
+            self.content,
+            r#"<rect x="{}" y="{}" width="{}" height="{}"{}/>"#,
+            num(x),
+            num(y),
+            num(width.max(0.)),
+            num(height.max(0.)),
+            style
+        );
+    }
+
+    pub fn rounded_rect(
+        &mut self,
+        x: f32,
+        y: f32,
+        width: f32,
+        height: f32,
+        radius: f32,
+        style: &Style,
+    ) {
+        let _ = writeln!(
+            self.content,
+            r#"<rect x="{}" y="{}" width="{}" height="{}" rx="{}"{}/>"#,
+            num(x),
+            num(y),
+            num(width.max(0.)),
+            num(height.max(0.)),
+            num(radius),
+            style
+        );
+    }
+
+    pub fn circle(&mut self, cx: f32, cy: f32, radius: f32, style: &Style) {
+        let _ = writeln!(
+            self.content,
+            r#"<circle cx="{}" cy="{}" r="{}"{}/>"#,
+            num(cx),
+            num(cy),
+            num(radius),
+            style
+        );
+    }
+
+    pub fn line(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, style: &Style) {
+        let _ = writeln!(
+            self.content,
+            r#"<line x1="{}" y1="{}" x2="{}" y2="{}"{}/>"#,
+            num(x1),
+            num(y1),
+            num(x2),
+            num(y2),
+            style
+        );
+    }
+
+    /// Draws a path, `data` is the raw `d` attribute
+    pub fn path(&mut self, data: &str, style: &Style) {
+        let _ = writeln!(self.content, r#"<path d="{}"{}/>"#, data, style);
+    }
+
+    pub fn text(&mut self, x: f32, y: f32, size: f32, content: &str, style: &Style) {
+        let _ = writeln!(
+            self.content,
+            r#"<text x="{}" y="{}" font-size="{}"{}>{}</text>"#,
+            num(x),
+            num(y),
+            num(size),
+            style,
+            escape_xml(content)
+        );
+    }
+
+    /// Opens a group, every group opened must be closed with [`SvgWriter::end_group`]
+    pub fn start_group(&mut self, attributes: &[(&str, &str)]) {
+        self.content.push_str("<g");
+        for (name, value) in attributes {
+            let _ = write!(self.content, r#" {}="{}""#, name, escape_xml(value));
+        }
+        self.content.push_str(">\n");
+    }
+
+    pub fn end_group(&mut self) {
+        self.content.push_str("</g>\n");
+    }
+
+    /// Opens a group that clips everything inside of it into the given rect
+    pub fn start_clip(&mut self, id: &str, x: f32, y: f32, width: f32, height: f32) {
+        let id = format!("{}{}", self.id_prefix, id);
+        let _ = writeln!(
+            self.content,
+            r#"<clipPath id="{}"><rect x="{}" y="{}" width="{}" height="{}"/></clipPath>"#,
+            escape_xml(&id),
+            num(x),
+            num(y),
+            num(width.max(0.)),
+            num(height.max(0.))
+        );
+        let _ = writeln!(self.content, r#"<g clip-path="url(#{})">"#, escape_xml(&id));
+    }
+
+    pub fn finish(self) -> String {
+        format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" \
+             viewBox=\"0 0 {w} {h}\" font-family=\"sans-serif\">\n{}</svg>\n",
+            self.content,
+            w = num(self.width),
+            h = num(self.height),
+        )
+    }
+}
+
+/// Presentation attributes of an svg element
+#[derive(Default, Clone)]
+pub(crate) struct Style {
+    pub fill: Option<Color>,
+    pub stroke: Option<Color>,
+    pub stroke_width: Option<f32>,
+    pub dashed: bool,
+    pub anchor: Option<&'static str>,
+    pub bold: bool,
+    pub italic: bool,
+}
+
+impl Style {
+    pub fn fill(color: Color) -> Self {
+        Style {
+            fill: Some(color),
+            ..Default::default()
+        }
+    }
+
+    pub fn stroke(color: Color, width: f32) -> Self {
+        Style {
+            stroke: Some(color),
+            stroke_width: Some(width),
+            ..Default::default()
+        }
+    }
+
+    pub fn with_fill(mut self, color: Color) -> Self {
+        self.fill = Some(color);
+        self
+    }
+
+    pub fn with_stroke(mut self, color: Color, width: f32) -> Self {
+        self.stroke = Some(color);
+        self.stroke_width = Some(width);
+        self
+    }
+
+    pub fn dashed(mut self) -> Self {
+        self.dashed = true;
+        self
+    }
+
+    pub fn anchor(mut self, anchor: &'static str) -> Self {
+        self.anchor = Some(anchor);
+        self
+    }
+}
+
+impl std::fmt::Display for Style {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.fill {
+            Some(color) => paint(f, "fill", color)?,
+            None => f.write_str(r#" fill="none""#)?,
+        }
+
+        if let Some(color) = self.stroke {
+            paint(f, "stroke", color)?;
+        }
+        if let Some(width) = self.stroke_width {
+            write!(f, r#" stroke-width="{}""#, num(width))?;
+        }
+        if self.dashed {
+            f.write_str(r#" stroke-dasharray="4 2""#)?;
+        }
+        if let Some(anchor) = self.anchor {
+            write!(f, r#" text-anchor="{}""#, anchor)?;
+        }
+        if self.bold {
+            f.write_str(r#" font-weight="bold""#)?;
+        }
+        if self.italic {
+            f.write_str(r#" font-style="italic""#)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Writes a fill / stroke attribute of the given color, along with its opacity if the color is
+/// not fully opaque
+fn paint(f: &mut std::fmt::Formatter<'_>, attribute: &str, color: Color) -> std::fmt::Result {
+    write!(f, r#" {}="{}""#, attribute, css_color(color))?;
+
+    if color.alpha() != 0xff {
+        write!(
+            f,
+            r#" {}-opacity="{}""#,
+            attribute,
+            num(color.alpha() as f32 / 255.)
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Turns a color into a css hex color (without the alpha)
+pub(crate) fn css_color(color: Color) -> String {
+    let (r, g, b) = color.rgb();
+    format!("#{:02x}{:02x}{:02x}", r, g, b)
+}
+
+/// Formats a number without unnecessary trailing zeroes
+pub(crate) fn num(value: f32) -> String {
+    let rounded = (value * 100.).round() / 100.;
+
+    if rounded.fract() == 0. {
+        format!("{}", rounded as i64)
+    } else {
+        format!("{}", rounded)
+    }
+}
+
+/// Roughly estimates the width of a text with the given font size, since we don't have access to
+/// any font metrics
+pub(crate) fn text_width(s: &str, size: f32) -> f32 {
+    s.chars().count() as f32 * size * 0.55
+}
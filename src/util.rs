@@ -33,3 +33,72 @@ where
         self.iter.next()
     }
 }
+
+/// Escapes the characters that have special meanings in xml
+#[cfg(feature = "api")]
+pub fn escape_xml(s: &str) -> String {
+    let mut result = String::with_capacity(s.len());
+
+    for ch in s.chars() {
+        match ch {
+            '<' => result.push_str("&lt;"),
+            '>' => result.push_str("&gt;"),
+            '&' => result.push_str("&amp;"),
+            '"' => result.push_str("&quot;"),
+            '\'' => result.push_str("&apos;"),
+            _ => result.push(ch),
+        }
+    }
+
+    result
+}
+
+/// Escapes a string to be put inside of a quoted id or label in the DOT language
+#[cfg(feature = "api")]
+pub fn escape_dot(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Escapes a string to be put inside of a quoted label in Mermaid
+#[cfg(feature = "api")]
+pub fn escape_mermaid(s: &str) -> String {
+    s.replace('"', "#quot;")
+}
+
+/// Finds the longest common subsequence of two slices, returns the pairs of indexes of the items
+/// (`(index in a, index in b)`) that are part of it, in order
+#[cfg(feature = "api")]
+pub fn lcs<A, B, F>(a: &[A], b: &[B], mut eq: F) -> Vec<(usize, usize)>
+where
+    F: FnMut(&A, &B) -> bool,
+{
+    // lengths[i][j] is the length of the lcs of a[i..] and b[j..]
+    let mut lengths = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lengths[i][j] = if eq(&a[i], &b[j]) {
+                lengths[i + 1][j + 1] + 1
+            } else {
+                lengths[i + 1][j].max(lengths[i][j + 1])
+            };
+        }
+    }
+
+    let mut result = Vec::new();
+    let (mut i, mut j) = (0, 0);
+
+    while i < a.len() && j < b.len() {
+        if eq(&a[i], &b[j]) {
+            result.push((i, j));
+            i += 1;
+            j += 1;
+        } else if lengths[i + 1][j] >= lengths[i][j + 1] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+
+    result
+}
@@ -99,3 +99,27 @@ fn test1() {
     // assert_eq!(rpblocks, pblocks); // Blocks in this new API doesn't preserve the IDs, so
     //                                   it definitely wont match one-per-one
 }
+
+#[test]
+fn substack_round_trip() {
+    let logic = r#"{"color":-1988310,"id":"14","nextBlock":15,"opCode":"if","parameters":["@19"],"spec":"if %b then","subStack1":40,"subStack2":-1,"type":"c","typeName":""}
+{"color":-10701022,"id":"19","nextBlock":-1,"opCode":"true","parameters":[],"spec":"true","subStack1":-1,"subStack2":-1,"type":"b","typeName":""}
+{"color":-11899692,"id":"40","nextBlock":41,"opCode":"setText","parameters":["textview1","@42"],"spec":"%m.textview setText %s","subStack1":-1,"subStack2":-1,"type":" ","typeName":""}
+{"color":-10701022,"id":"42","nextBlock":-1,"opCode":"toString","parameters":["1"],"spec":"toString %d without decimal","subStack1":-1,"subStack2":-1,"type":"s","typeName":""}
+{"color":-11899692,"id":"41","nextBlock":-1,"opCode":"setText","parameters":["textview1","b"],"spec":"%m.textview setText %s","subStack1":-1,"subStack2":-1,"type":" ","typeName":""}
+{"color":-11899692,"id":"15","nextBlock":-1,"opCode":"setText","parameters":["textview1","c"],"spec":"%m.textview setText %s","subStack1":-1,"subStack2":-1,"type":" ","typeName":""}"#;
+
+    let blocks = Blocks::try_from(BlockContainer::parse(logic).unwrap()).unwrap();
+    let container: BlockContainer = blocks.clone().into();
+
+    // substacks should point to the first block of the substack, not to the last one
+    let first = container
+        .0
+        .iter()
+        .find(|block| block.op_code == "setText")
+        .unwrap();
+    assert_eq!(container.0[0].sub_stack1.to_string(), first.id);
+    // and argument blocks are referenced by their own ids
+    assert_eq!(container.0[2].parameters, ["textview1", "@13"]);
+    assert_eq!(Blocks::try_from(container).unwrap(), blocks);
+}
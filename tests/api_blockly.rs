@@ -0,0 +1,103 @@
+mod common;
+
+use common::blocks;
+use swrs::api::blockly::{
+    blocks_from_xml, blocks_to_xml, from_xml, to_xml, BlockDefinitions, BlocklyImportError,
+};
+use swrs::parser::logic::BlockContainer;
+use swrs::parser::Parsable;
+
+const LOGIC: &str = r#"{"color":-1988310,"id":"10","nextBlock":17,"opCode":"ifElse","parameters":["@11"],"spec":"if %b then","subStack1":14,"subStack2":16,"type":"e","typeName":""}
+{"color":-10701022,"id":"11","nextBlock":-1,"opCode":">","parameters":["@12","0"],"spec":"%d > %d","subStack1":-1,"subStack2":-1,"type":"b","typeName":""}
+{"color":-3384542,"id":"12","nextBlock":-1,"opCode":"lengthList","parameters":["webviews"],"spec":"length of %m.list","subStack1":-1,"subStack2":-1,"type":"d","typeName":""}
+{"color":-11899692,"id":"14","nextBlock":15,"opCode":"setText","parameters":["textview1","a <b> & \"c\""],"spec":"%m.textview setText %s","subStack1":-1,"subStack2":-1,"type":" ","typeName":""}
+{"color":-11899692,"id":"15","nextBlock":-1,"opCode":"setVisible","parameters":["listview1","VISIBLE"],"spec":"%m.view setVisible %m.visible","subStack1":-1,"subStack2":-1,"type":" ","typeName":""}
+{"color":-1147626,"id":"16","nextBlock":-1,"opCode":"setVarBoolean","parameters":["isLoaded","true"],"spec":"set %m.varBool to %b","subStack1":-1,"subStack2":-1,"type":" ","typeName":""}
+{"color":-1147626,"id":"17","nextBlock":-1,"opCode":"getVar","parameters":[],"spec":"currentWV","subStack1":-1,"subStack2":-1,"type":"d","typeName":""}"#;
+
+#[test]
+fn container_round_trip() {
+    let container = BlockContainer::parse(LOGIC).unwrap();
+    let definitions = BlockDefinitions::from_container(&container);
+
+    let xml = to_xml(&container).unwrap();
+    assert!(xml.contains(r#"<block type="ifElse" id="10" x="0" y="0">"#));
+    assert!(xml.contains(r#"<statement name="SUBSTACK2">"#));
+    assert!(xml.contains(r#"<field name="ARG0">textview1</field>"#));
+    assert!(xml.contains("a &lt;b&gt; &amp; &quot;c&quot;"));
+    assert!(xml.contains(r#"<block type="getVar:currentWV" id="17">"#));
+
+    let imported = from_xml(&xml, &definitions).unwrap();
+    assert_eq!(imported, container);
+}
+
+#[test]
+fn blocks_round_trip() {
+    let blocks = blocks(LOGIC);
+    let definitions = BlockDefinitions::from_blocks(&blocks);
+
+    let xml = blocks_to_xml(&blocks).unwrap();
+    assert_eq!(blocks_from_xml(&xml, &definitions).unwrap(), blocks);
+}
+
+#[test]
+fn definitions_json() {
+    let container = BlockContainer::parse(LOGIC).unwrap();
+    let definitions = BlockDefinitions::from_container(&container);
+
+    let json = definitions.to_json().unwrap();
+    let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+    let if_else = &parsed[0];
+    assert_eq!(if_else["type"], "ifElse");
+    assert_eq!(if_else["message0"], "if %1 then");
+    assert_eq!(if_else["args0"][0]["check"], "Boolean");
+    assert_eq!(if_else["args2"][0]["name"], "SUBSTACK2");
+    assert_eq!(if_else["colour"], "#e1a92a");
+
+    let greater = parsed
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|def| def["type"] == ">")
+        .unwrap();
+    assert_eq!(greater["output"], "Boolean");
+    assert!(greater.get("previousStatement").is_none());
+
+    assert_eq!(BlockDefinitions::from_json(&json).unwrap(), definitions);
+}
+
+#[test]
+fn import_edited_xml() {
+    let container = BlockContainer::parse(LOGIC).unwrap();
+    let definitions = BlockDefinitions::from_container(&container);
+
+    // a block that was added inside of blockly, with a blockly-generated id
+    let xml = r#"<xml xmlns="https://developers.google.com/blockly/xml">
+  <block type="setText" id="5" x="12" y="40">
+    <field name="ARG0">textview2</field>
+    <value name="ARG1">
+      <shadow type="text" id="a$b"><field name="TEXT">old</field></shadow>
+      <block type="getVar:currentWV" id="?fQz]1"></block>
+    </value>
+    <next>
+      <block type="setText" id="k(8">
+        <field name="ARG0">textview3</field>
+        <value name="ARG1"><shadow type="text"><field name="TEXT"> </field></shadow></value>
+      </block>
+    </next>
+  </block>
+</xml>"#;
+
+    let imported = from_xml(xml, &definitions).unwrap();
+    let ids = imported.0.iter().map(|b| b.id.as_str()).collect::<Vec<_>>();
+    assert_eq!(ids, ["5", "6", "7"]);
+    assert_eq!(imported.0[0].parameters, ["textview2", "@6"]);
+    assert_eq!(imported.0[0].next_block, 7);
+    assert_eq!(imported.0[2].parameters, ["textview3", " "]);
+
+    assert!(matches!(
+        from_xml(r#"<xml><block type="nope" id="1"/></xml>"#, &definitions),
+        Err(BlocklyImportError::UnknownBlockType { .. })
+    ));
+}
@@ -0,0 +1,136 @@
+mod common;
+
+use common::{blocks, screen};
+use swrs::api::block::BlockContent;
+use swrs::api::callgraph::{CallGraph, Node};
+use swrs::api::screen::{Event, EventType, MoreBlock, Screen};
+
+/// Blocks that call the given moreblocks one after another
+fn calls(names: &[&str]) -> String {
+    names
+        .iter()
+        .enumerate()
+        .map(|(index, name)| {
+            let next = if index + 1 == names.len() {
+                -1
+            } else {
+                index as i32 + 11
+            };
+
+            format!(
+                r#"{{"color":-7711273,"id":"{}","nextBlock":{},"opCode":"definedFunc","parameters":[],"spec":"{}","subStack1":-1,"subStack2":-1,"type":" ","typeName":""}}"#,
+                index + 10,
+                next,
+                name
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn main_screen() -> Screen {
+    let mut main = screen("main");
+
+    // onCreate -> load -> parse, parse -> load; tick calls itself; old -> load; missing doesn't
+    // exist
+    for (name, callees) in [
+        ("load", vec!["parse"]),
+        ("parse", vec!["load", "log"]),
+        ("log", vec![]),
+        ("tick", vec!["tick"]),
+        ("old", vec!["load"]),
+    ] {
+        main.more_blocks.insert(
+            name.to_string(),
+            MoreBlock {
+                name: name.to_string(),
+                spec: BlockContent::parse_wo_params(name).unwrap(),
+                code: blocks(&calls(&callees)),
+            },
+        );
+    }
+
+    main.events.push(Event {
+        name: "onCreate".to_string(),
+        event_type: EventType::ActivityEvent,
+        code: blocks(&calls(&["load", "missing", "load"])),
+    });
+
+    main
+}
+
+#[test]
+fn resolves_calls() {
+    let graph = CallGraph::build(&main_screen());
+
+    assert_eq!(graph.callees(&Node::on_create()), vec!["load"]);
+    assert_eq!(
+        graph.callers("load"),
+        vec![
+            &Node::on_create(),
+            &Node::MoreBlock("parse".to_string()),
+            &Node::MoreBlock("old".to_string()),
+        ]
+    );
+    assert_eq!(
+        graph.reachable_from(&Node::on_create()),
+        vec!["load", "parse", "log"]
+    );
+
+    assert_eq!(graph.unresolved.len(), 1);
+    assert_eq!(graph.unresolved[0].to, "missing");
+    assert_eq!(graph.unresolved[0].path.to_string(), "1");
+}
+
+#[test]
+fn unreachable_and_recursion() {
+    let graph = CallGraph::build(&main_screen());
+
+    assert_eq!(graph.unreachable(), vec!["tick", "old"]);
+    assert_eq!(graph.cycles(), vec![vec!["load", "parse"], vec!["tick"]]);
+}
+
+#[test]
+fn exports() {
+    let graph = CallGraph::build(&main_screen());
+
+    assert_eq!(
+        graph.to_dot(),
+        r#"digraph "main" {
+    e_onCreate_initializeLogic [label="onCreate_initializeLogic", shape=box];
+    m_load [label="load", shape=ellipse];
+    m_parse [label="parse", shape=ellipse];
+    m_log [label="log", shape=ellipse];
+    m_tick [label="tick", shape=ellipse, style=dashed];
+    m_old [label="old", shape=ellipse, style=dashed];
+    e_onCreate_initializeLogic -> m_load;
+    m_load -> m_parse;
+    m_parse -> m_load;
+    m_parse -> m_log;
+    m_tick -> m_tick;
+    m_old -> m_load;
+}
+"#
+    );
+
+    assert_eq!(
+        graph.to_mermaid(),
+        r#"flowchart LR
+    e_onCreate_initializeLogic["onCreate_initializeLogic"]
+    m_load(["load"])
+    m_parse(["parse"])
+    m_log(["log"])
+    m_tick(["tick"])
+    m_old(["old"])
+    e_onCreate_initializeLogic --> m_load
+    m_load --> m_parse
+    m_parse --> m_load
+    m_parse --> m_log
+    m_tick --> m_tick
+    m_old --> m_load
+    classDef unreachable stroke-dasharray: 5 5
+    class m_tick unreachable
+    class m_old unreachable
+"#
+    );
+}
@@ -0,0 +1,90 @@
+mod common;
+
+use common::project;
+use swrs::api::component::ComponentKind;
+use swrs::api::event::ComponentEvent;
+use swrs::api::library::{GoogleMap, LibraryKind};
+use swrs::api::view::{View, ViewType};
+use swrs::parser::logic::component::Component;
+
+#[test]
+fn component_metadata() {
+    let network = ComponentKind::RequestNetwork;
+    assert_eq!(
+        network.events(),
+        &[ComponentEvent::OnResponse, ComponentEvent::OnErrorResponse]
+    );
+    assert!(network
+        .blocks()
+        .contains(&"requestnetworkStartRequestNetwork"));
+    assert_eq!(
+        network.permissions(),
+        &[
+            "android.permission.INTERNET",
+            "android.permission.ACCESS_NETWORK_STATE"
+        ]
+    );
+    assert_eq!(network.required_library(), None);
+
+    assert_eq!(
+        ComponentKind::Camera.permissions(),
+        &["android.permission.CAMERA"]
+    );
+    assert_eq!(
+        ComponentKind::FirebaseAuth.required_library(),
+        Some(LibraryKind::Firebase)
+    );
+    assert_eq!(
+        ComponentKind::InterstitialAd.required_library(),
+        Some(LibraryKind::AdMob)
+    );
+}
+
+#[test]
+fn unknown_component_round_trip() {
+    let raw = Component::new(
+        "custom".to_string(),
+        "a".to_string(),
+        "b".to_string(),
+        "c".to_string(),
+        42,
+    );
+
+    let kind = ComponentKind::from_parser_component(&raw);
+    assert_eq!(
+        kind,
+        ComponentKind::Unknown {
+            r#type: 42,
+            params: ["a".to_string(), "b".to_string(), "c".to_string()]
+        }
+    );
+    assert_eq!(kind.get_type_id(), 42);
+    assert!(kind.events().is_empty());
+    assert_eq!(kind.into_parser_component("custom".to_string()), raw);
+
+    let known = Component::new_1param("prefs".to_string(), "data".to_string(), 2);
+    assert_eq!(
+        ComponentKind::from_parser_component(&known).into_parser_component("prefs".to_string()),
+        known
+    );
+}
+
+#[test]
+fn required_libraries() {
+    let mut project = project(vec![]);
+    assert!(!project.libraries.is_enabled(LibraryKind::GoogleMap));
+
+    project.libraries.google_map = Some(GoogleMap {
+        api_key: String::new(),
+    });
+    assert!(project.libraries.is_enabled(LibraryKind::GoogleMap));
+    assert!(!project.libraries.is_enabled(LibraryKind::Firebase));
+
+    let map = View::new("mapview1", ViewType::MapView);
+    assert_eq!(
+        map.view.unwrap().required_library(),
+        Some(LibraryKind::GoogleMap)
+    );
+    assert_eq!(ViewType::WebView.required_library(), None);
+    assert_eq!(LibraryKind::GoogleMap.name(), "Google Maps");
+}
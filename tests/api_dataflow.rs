@@ -0,0 +1,157 @@
+mod common;
+
+use common::{blocks, event, screen};
+use swrs::api::block::BlockContent;
+use swrs::api::dataflow::{Access, DataFlow, VariableKind};
+use swrs::api::screen::{EventType, MoreBlock, Screen};
+use swrs::parser::logic::list_variable::ListVariable;
+use swrs::parser::logic::variable::{Variable, VariableType};
+
+const ON_CREATE: &str = r#"{"color":-10701022,"id":"10","nextBlock":11,"opCode":"doToast","parameters":["@12"],"spec":"Toast %s","subStack1":-1,"subStack2":-1,"type":" ","typeName":""}
+{"color":-1147626,"id":"12","nextBlock":-1,"opCode":"getVar","parameters":[],"spec":"name","subStack1":-1,"subStack2":-1,"type":"s","typeName":""}
+{"color":-1147626,"id":"11","nextBlock":13,"opCode":"setVarInt","parameters":["count","0"],"spec":"set %m.varInt to %d","subStack1":-1,"subStack2":-1,"type":" ","typeName":""}
+{"color":-7711273,"id":"13","nextBlock":-1,"opCode":"definedFunc","parameters":[],"spec":"init","subStack1":-1,"subStack2":-1,"type":" ","typeName":""}"#;
+
+const INIT: &str = r#"{"color":-1147626,"id":"10","nextBlock":-1,"opCode":"setVarString","parameters":["name","Hi"],"spec":"set %m.varStr to %s","subStack1":-1,"subStack2":-1,"type":" ","typeName":""}"#;
+
+const BUTTON1: &str = r#"{"color":-10701022,"id":"10","nextBlock":11,"opCode":"doToast","parameters":["@12"],"spec":"Toast %s","subStack1":-1,"subStack2":-1,"type":" ","typeName":""}
+{"color":-1147626,"id":"12","nextBlock":-1,"opCode":"getVar","parameters":[],"spec":"name","subStack1":-1,"subStack2":-1,"type":"s","typeName":""}
+{"color":-1147626,"id":"11","nextBlock":13,"opCode":"increaseInt","parameters":["count"],"spec":"%m.varInt increase 1","subStack1":-1,"subStack2":-1,"type":" ","typeName":""}
+{"color":-1147626,"id":"13","nextBlock":-1,"opCode":"setVarBoolean","parameters":["flag","@14"],"spec":"set %m.varBool to %b","subStack1":-1,"subStack2":-1,"type":" ","typeName":""}
+{"color":-10701022,"id":"14","nextBlock":-1,"opCode":"true","parameters":[],"spec":"true","subStack1":-1,"subStack2":-1,"type":"b","typeName":""}"#;
+
+const BUTTON2: &str = r#"{"color":-10701022,"id":"10","nextBlock":11,"opCode":"doToast","parameters":["@12"],"spec":"Toast %s","subStack1":-1,"subStack2":-1,"type":" ","typeName":""}
+{"color":-1147626,"id":"12","nextBlock":-1,"opCode":"getVar","parameters":[],"spec":"total","subStack1":-1,"subStack2":-1,"type":"s","typeName":""}
+{"color":-3384542,"id":"11","nextBlock":-1,"opCode":"addListStr","parameters":["a","names"],"spec":"add %s to %m.listStr","subStack1":-1,"subStack2":-1,"type":" ","typeName":""}"#;
+
+fn variable(name: &str, r#type: VariableType) -> (String, Variable) {
+    (
+        name.to_string(),
+        Variable {
+            name: name.to_string(),
+            r#type,
+        },
+    )
+}
+
+fn main_screen() -> Screen {
+    let mut main = screen("main");
+
+    main.variables.extend([
+        variable("count", VariableType::Integer),
+        variable("name", VariableType::String),
+        variable("total", VariableType::Integer),
+        variable("flag", VariableType::Boolean),
+    ]);
+    main.list_variables.insert(
+        "names".to_string(),
+        ListVariable {
+            name: "names".to_string(),
+            r#type: VariableType::String,
+        },
+    );
+
+    main.more_blocks.insert(
+        "init".to_string(),
+        MoreBlock {
+            name: "init".to_string(),
+            spec: BlockContent::parse_wo_params("init").unwrap(),
+            code: blocks(INIT),
+        },
+    );
+
+    main.events.extend([
+        event("onCreate", EventType::ActivityEvent, ON_CREATE),
+        event(
+            "onClick",
+            EventType::ViewEvent {
+                id: "button1".to_string(),
+            },
+            BUTTON1,
+        ),
+        event(
+            "onClick",
+            EventType::ViewEvent {
+                id: "button2".to_string(),
+            },
+            BUTTON2,
+        ),
+    ]);
+
+    main
+}
+
+#[test]
+fn classifies_usages() {
+    let flow = DataFlow::analyze(&main_screen());
+
+    let count: Vec<_> = flow
+        .usages_of("count")
+        .map(|usage| {
+            (
+                usage.container.as_str(),
+                usage.path.to_string(),
+                usage.access,
+            )
+        })
+        .collect();
+
+    assert_eq!(
+        count,
+        vec![
+            ("onCreate_initializeLogic", "1".to_string(), Access::Write),
+            ("button1_onClick", "1".to_string(), Access::Read),
+            ("button1_onClick", "1".to_string(), Access::Write),
+        ]
+    );
+
+    let names: Vec<_> = flow
+        .usages_of("names")
+        .map(|usage| (usage.kind, usage.access))
+        .collect();
+    assert_eq!(names, vec![(VariableKind::List, Access::Write)]);
+}
+
+#[test]
+fn write_only_and_read_only() {
+    let flow = DataFlow::analyze(&main_screen());
+
+    assert_eq!(
+        flow.write_only,
+        vec![
+            (VariableKind::Variable, "flag".to_string()),
+            (VariableKind::List, "names".to_string()),
+        ]
+    );
+    assert_eq!(
+        flow.read_only,
+        vec![(VariableKind::Variable, "total".to_string())]
+    );
+}
+
+#[test]
+fn unassigned_reads() {
+    let flow = DataFlow::analyze(&main_screen());
+
+    // name is read in onCreate before init assigns it, but it's assigned by the time button1 is
+    // clicked. total is never assigned
+    let reads: Vec<_> = flow
+        .unassigned_reads
+        .iter()
+        .map(|usage| {
+            (
+                usage.name.as_str(),
+                usage.container.as_str(),
+                usage.path.to_string(),
+            )
+        })
+        .collect();
+
+    assert_eq!(
+        reads,
+        vec![
+            ("name", "onCreate_initializeLogic", "0/arg0".to_string()),
+            ("total", "button2_onClick", "0/arg0".to_string()),
+        ]
+    );
+}
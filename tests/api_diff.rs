@@ -0,0 +1,218 @@
+mod common;
+
+use common::{blocks, button, linear, project, screen, text_view};
+use swrs::api::block::{ArgValue, Argument, BlockPath, BlockPathSegment};
+use swrs::api::diff::{diff, diff_blocks, diff_screen, BlockEdit, Change, ScreenChange};
+use swrs::api::screen::{Event, EventType, Screen};
+use swrs::api::view::ViewType;
+use swrs::color::Color;
+use swrs::parser::logic::variable::{Variable, VariableType};
+
+const ON_CLICK: &str = r#"{"color":-7711273,"id":"10","nextBlock":11,"opCode":"setText","parameters":["textview1","Clicked"],"spec":"%m.textview setText %s","subStack1":-1,"subStack2":-1,"type":" ","typeName":""}
+{"color":-1147626,"id":"11","nextBlock":-1,"opCode":"increaseInt","parameters":["count"],"spec":"%m.varInt increase 1","subStack1":-1,"subStack2":-1,"type":" ","typeName":""}"#;
+
+fn main_screen() -> Screen {
+    let mut main = screen("main");
+    main.layout = vec![
+        linear(
+            "linear1",
+            true,
+            vec![text_view("textview1", "Hello"), button("button1", "Click")],
+        ),
+        linear("linear2", true, vec![]),
+    ];
+
+    main.variables.insert(
+        "count".to_string(),
+        Variable {
+            name: "count".to_string(),
+            r#type: VariableType::Integer,
+        },
+    );
+
+    main.events.push(Event {
+        name: "onClick".to_string(),
+        event_type: EventType::ViewEvent {
+            id: "button1".to_string(),
+        },
+        code: blocks(ON_CLICK),
+    });
+
+    main
+}
+
+#[test]
+fn identical_projects() {
+    let a = project(vec![main_screen()]);
+    assert!(diff(&a, &a.clone()).is_empty());
+}
+
+#[test]
+fn project_changes() {
+    let a = project(vec![main_screen(), screen("about")]);
+    let mut b = project(vec![main_screen(), screen("settings")]);
+
+    b.metadata.version_code = 2;
+    b.colors.color_accent = Color::from(0xffff0000);
+
+    let changes = diff(&a, &b).changes;
+
+    assert_eq!(
+        changes,
+        vec![
+            Change::MetadataChanged {
+                field: "version_code",
+                old: "1".to_string(),
+                new: "2".to_string(),
+            },
+            Change::ColorChanged {
+                field: "color_accent",
+                old: Color::from(0xff008dcd),
+                new: Color::from(0xffff0000),
+            },
+            Change::ScreenRemoved {
+                screen: "about".to_string()
+            },
+            Change::ScreenAdded {
+                screen: "settings".to_string()
+            },
+        ]
+    );
+}
+
+#[test]
+fn view_changes() {
+    let a = main_screen();
+    let mut b = main_screen();
+
+    // move button1 into linear2, change the text of textview1 and add a new textview
+    let button1 = b.layout[0].children.remove(1);
+    b.layout[1].children.push(button1);
+
+    if let Ok(ViewType::TextView { text, .. }) = &mut b.layout[0].children[0].view {
+        *text = "Hi".to_string();
+    }
+
+    b.layout[0].children.push(text_view("textview2", "New"));
+
+    b.variables.clear();
+
+    let changes = diff_screen(&a, &b);
+
+    assert!(matches!(
+        &changes[0],
+        ScreenChange::ViewAdded { id, parent: Some(parent), index: 1, .. }
+            if id == "textview2" && parent == "linear1"
+    ));
+
+    assert_eq!(
+        changes[1..],
+        [
+            ScreenChange::ViewMoved {
+                id: "button1".to_string(),
+                old_parent: Some("linear1".to_string()),
+                old_index: 1,
+                new_parent: Some("linear2".to_string()),
+                new_index: 0,
+            },
+            ScreenChange::ViewAttributeChanged {
+                id: "textview1".to_string(),
+                attribute: "text.text".to_string(),
+                old: "Hello".into(),
+                new: "Hi".into(),
+            },
+            ScreenChange::VariableRemoved {
+                name: "count".to_string(),
+                r#type: VariableType::Integer,
+            },
+        ]
+    );
+}
+
+#[test]
+fn drawer_changes() {
+    let mut a = main_screen();
+    a.set_drawer_enabled(true);
+    a.drawer.as_mut().unwrap().layout = vec![linear(
+        "linear1",
+        true,
+        vec![text_view("textview1", "Home")],
+    )];
+
+    // the drawer has views with the same ids as the ones in the layout
+    let mut b = a.clone();
+    let drawer = b.drawer.as_mut().unwrap();
+    if let Ok(ViewType::TextView { text, .. }) = &mut drawer.layout[0].children[0].view {
+        *text = "Beranda".to_string();
+    }
+    drawer.layout.push(button("button1", "Settings"));
+
+    let changes = diff_screen(&a, &b);
+
+    assert!(matches!(
+        &changes[0],
+        ScreenChange::ViewAdded { id, parent: None, index: 1, .. } if id == "_drawer_button1"
+    ));
+    assert_eq!(
+        changes[1..],
+        [ScreenChange::ViewAttributeChanged {
+            id: "_drawer_textview1".to_string(),
+            attribute: "text.text".to_string(),
+            old: "Home".into(),
+            new: "Beranda".into(),
+        }]
+    );
+}
+
+#[test]
+fn block_edits() {
+    let a = blocks(ON_CLICK);
+    let b = blocks(
+        r#"{"color":-7711273,"id":"10","nextBlock":12,"opCode":"setText","parameters":["textview1","Clicked!"],"spec":"%m.textview setText %s","subStack1":-1,"subStack2":-1,"type":" ","typeName":""}
+{"color":-10701022,"id":"12","nextBlock":-1,"opCode":"doToast","parameters":["Hello"],"spec":"Toast %s","subStack1":-1,"subStack2":-1,"type":" ","typeName":""}"#,
+    );
+
+    let edits = diff_blocks(&a, &b);
+
+    assert_eq!(edits.len(), 3);
+    assert_eq!(
+        edits[0],
+        BlockEdit::ArgumentChanged {
+            path: BlockPath(vec![BlockPathSegment::Index(0)]),
+            op_code: "setText".to_string(),
+            argument: 1,
+            old: Box::new(Argument::String {
+                name: None,
+                value: ArgValue::Value("Clicked".to_string())
+            }),
+            new: Box::new(Argument::String {
+                name: None,
+                value: ArgValue::Value("Clicked!".to_string())
+            }),
+        }
+    );
+    assert!(matches!(&edits[1], BlockEdit::Removed { path, block }
+        if path.to_string() == "1" && block.op_code == "increaseInt"));
+    assert!(matches!(&edits[2], BlockEdit::Inserted { path, block }
+        if path.to_string() == "1" && block.op_code == "doToast"));
+}
+
+#[test]
+fn text_rendering() {
+    let a = project(vec![main_screen()]);
+    let mut b = a.clone();
+    b.screens[0].events[0].code = blocks(
+        r#"{"color":-7711273,"id":"10","nextBlock":-1,"opCode":"setText","parameters":["textview1","Clicked"],"spec":"%m.textview setText %s","subStack1":-1,"subStack2":-1,"type":" ","typeName":""}"#,
+    );
+    b.screens[0].layout[1]
+        .children
+        .push(button("button2", "Ok"));
+
+    assert_eq!(
+        diff(&a, &b).to_string(),
+        "~ screen main\n    \
+         + view button2 at linear2[0]\n    \
+         ~ event button1_onClick\n        \
+         - block increaseInt at 1\n"
+    );
+}
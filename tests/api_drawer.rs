@@ -0,0 +1,172 @@
+mod common;
+
+use common::{blocks, button, linear, project, screen, text_view};
+use swrs::api::block::Blocks;
+use swrs::api::event::{validate_screen, InvalidEventKind};
+use swrs::api::i18n::{localize, StringTable};
+use swrs::api::metrics::ScreenMetrics;
+use swrs::api::permissions::{PermissionReport, Reason};
+use swrs::api::screen::{Drawer, Event, EventType};
+use swrs::api::typecheck::check_screen;
+use swrs::api::view::{View, ViewType};
+use swrs::api::{CustomView, SketchwareProject};
+use swrs::parser::file::FileType;
+use swrs::parser::SketchwareProject as ParsedSketchwareProject;
+use swrs::LinkedHashMap;
+
+fn drawer_event(id: &str) -> Event {
+    Event {
+        name: "onClick".to_string(),
+        event_type: EventType::ViewEvent { id: id.to_string() },
+        code: Blocks::new(),
+    }
+}
+
+fn round_trip(project: SketchwareProject) -> (ParsedSketchwareProject, SketchwareProject) {
+    let parsed = ParsedSketchwareProject::from(project);
+    let result = SketchwareProject::try_from(parsed.clone()).unwrap();
+    (parsed, result)
+}
+
+#[test]
+fn drawer_round_trip() {
+    let mut main = screen("main");
+    main.layout = vec![linear("linear1", true, vec![text_view("textview1", "Hi")])];
+    main.set_drawer_enabled(true);
+    main.drawer.as_mut().unwrap().layout =
+        vec![linear("linear1", true, vec![button("button1", "Home")])];
+    main.events.push(drawer_event("_drawer_button1"));
+
+    let (parsed, project) = round_trip(project(vec![main.clone()]));
+
+    // stored the way sketchware does, as a drawer file with its own layout
+    assert_eq!(parsed.file.custom_views.len(), 1);
+    assert_eq!(parsed.file.custom_views[0].filename, "_drawer_main");
+    assert_eq!(parsed.file.custom_views[0].file_type, FileType::Drawer);
+    assert!(parsed.file.activities[0].options.drawer_enabled);
+    assert!(parsed.view.layouts.contains_key("_drawer_main"));
+
+    assert!(project.custom_views.is_empty());
+    let views = |drawer: &Drawer| {
+        drawer
+            .views()
+            .into_iter()
+            .map(|view| (view.id.to_owned(), view.view.clone().ok()))
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        views(project.screens[0].drawer.as_ref().unwrap()),
+        views(main.drawer.as_ref().unwrap())
+    );
+    assert_eq!(project.screens[0].events, main.events);
+}
+
+#[test]
+fn unowned_drawer_stays_custom_view() {
+    let mut project = project(vec![screen("main")]);
+    project.custom_views.push(CustomView {
+        res_name: "_drawer_settings".to_string(),
+        layout: vec![button("button1", "Back")],
+    });
+
+    let mut parsed = ParsedSketchwareProject::from(project);
+    parsed.file.custom_views[0].file_type = FileType::Drawer;
+    let project = SketchwareProject::try_from(parsed).unwrap();
+
+    assert_eq!(project.screens[0].drawer, None);
+    assert_eq!(project.custom_views[0].res_name, "_drawer_settings");
+}
+
+#[test]
+fn drawer_views_and_events() {
+    let mut main = screen("main");
+    main.layout = vec![button("button1", "Open")];
+    main.events.extend([
+        drawer_event("button1"),
+        drawer_event("_drawer_button1"),
+        drawer_event("_drawer_button2"),
+    ]);
+
+    main.set_drawer_enabled(true);
+    assert_eq!(main.drawer, Some(Drawer::default()));
+    main.drawer.as_mut().unwrap().layout = vec![text_view("button1", "Home")];
+
+    assert_eq!(main.drawer_layout_name(), "_drawer_main");
+    assert!(main.find_view("_drawer_button1").unwrap().view.is_ok());
+    assert_ne!(main.find_view("_drawer_button1"), main.find_view("button1"));
+    assert_eq!(main.drawer_events().len(), 2);
+
+    // drawer events are checked against the drawer layout
+    let errors: Vec<_> = validate_screen(&main)
+        .into_iter()
+        .map(|error| (error.container, error.kind))
+        .collect();
+    assert_eq!(
+        errors,
+        vec![(
+            "_drawer_button2_onClick".to_string(),
+            InvalidEventKind::MissingTarget {
+                id: "_drawer_button2".to_string()
+            }
+        )]
+    );
+
+    main.set_drawer_enabled(false);
+    assert!(!main.drawer_enabled);
+    assert_eq!(main.drawer, None);
+    assert_eq!(main.events, vec![drawer_event("button1")]);
+}
+
+#[test]
+fn drawer_views_are_analyzed() {
+    let mut main = screen("main");
+    main.layout = vec![text_view("textview1", "Hi")];
+    main.set_drawer_enabled(true);
+    main.drawer.as_mut().unwrap().layout = vec![linear(
+        "linear1",
+        true,
+        vec![
+            text_view("textview1", "Home"),
+            View::new("webview1", ViewType::WebView),
+        ],
+    )];
+    main.events.push(Event {
+        name: "onClick".to_string(),
+        event_type: EventType::ViewEvent {
+            id: "_drawer_textview1".to_string(),
+        },
+        code: blocks(
+            r#"{"color":-7711273,"id":"10","nextBlock":-1,"opCode":"setText","parameters":["_drawer_textview1","Clicked"],"spec":"%m.textview setText %s","subStack1":-1,"subStack2":-1,"type":" ","typeName":""}"#,
+        ),
+    });
+
+    let project = project(vec![main]);
+    let screen = &project.screens[0];
+
+    assert_eq!(ScreenMetrics::measure(screen).layout.views, 4);
+    assert_eq!(check_screen(screen, &project.custom_views), vec![]);
+
+    let report = PermissionReport::infer(&project);
+    assert_eq!(
+        report.get("android.permission.INTERNET").unwrap().reasons,
+        vec![Reason::View {
+            screen: "main".to_string(),
+            id: "_drawer_webview1".to_string(),
+            view_type: "WebView",
+        }]
+    );
+
+    let table = StringTable::extract(&project);
+    assert_eq!(table.get("main__drawer_textview1_text"), Some("Home"));
+
+    let mut translations = LinkedHashMap::new();
+    translations.insert(
+        "main__drawer_textview1_text".to_string(),
+        "Beranda".to_string(),
+    );
+    let localized = localize(&project, &translations);
+    assert!(matches!(
+        &localized.screens[0].find_view("_drawer_textview1").unwrap().view,
+        Ok(ViewType::TextView { text, .. }) if text == "Beranda"
+    ));
+}
@@ -0,0 +1,146 @@
+mod common;
+
+use common::{button, linear, screen};
+use swrs::api::block::Blocks;
+use swrs::api::component::ComponentKind;
+use swrs::api::event::{
+    validate_screen, ComponentEvent, InvalidEventKind, UnsupportedEvent, ViewEvent,
+};
+use swrs::api::screen::{Drawer, Event, EventType};
+use swrs::api::view::{View, ViewType};
+
+#[test]
+fn catalog() {
+    assert_eq!(
+        ViewEvent::of(&ViewType::WebView),
+        &[ViewEvent::OnPageStarted, ViewEvent::OnPageFinished]
+    );
+    assert_eq!(ViewEvent::OnPageStarted.parameters(), "%s.url");
+    assert_eq!(
+        ComponentEvent::of(&ComponentKind::RequestNetwork),
+        &[ComponentEvent::OnResponse, ComponentEvent::OnErrorResponse]
+    );
+    assert!(ComponentEvent::of(&ComponentKind::Intent).is_empty());
+
+    for event in ViewEvent::ALL {
+        assert_eq!(ViewEvent::from_name(event.name()), Some(*event));
+    }
+    for event in ComponentEvent::ALL {
+        assert_eq!(ComponentEvent::from_name(event.name()), Some(*event));
+    }
+}
+
+#[test]
+fn typed_constructors() {
+    let button = button("button1", "Click");
+
+    let event = Event::for_view(&button, ViewEvent::OnClick).unwrap();
+    assert_eq!(event.get_block_container_id(), "button1_onClick");
+    assert_eq!(
+        event.get_block_container_id(),
+        ViewEvent::OnClick.container_id("button1")
+    );
+
+    let error = Event::for_view(&button, ViewEvent::OnProgressChanged).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Button doesn't have the event `onProgressChanged`"
+    );
+
+    let event = Event::for_component(
+        "net",
+        &ComponentKind::RequestNetwork,
+        ComponentEvent::OnResponse,
+    )
+    .unwrap();
+    assert_eq!(
+        event.event_type,
+        EventType::ComponentEvent {
+            id: "net".to_string(),
+            component_type: 17
+        }
+    );
+    assert!(
+        Event::for_component("timer", &ComponentKind::Timer, ComponentEvent::OnResponse).is_err()
+    );
+}
+
+#[test]
+fn validates_events() {
+    let mut main = screen("main");
+    main.layout = vec![linear(
+        "linear1",
+        true,
+        vec![
+            button("button1", "Click"),
+            View::new("webview1", ViewType::WebView),
+        ],
+    )];
+    main.drawer = Some(Drawer {
+        layout: vec![button("button1", "Home")],
+    });
+    main.components
+        .insert("net".to_string(), ComponentKind::RequestNetwork);
+
+    let event = |name: &str, event_type: EventType| Event {
+        name: name.to_string(),
+        event_type,
+        code: Blocks::new(),
+    };
+    let view_event =
+        |id: &str, name: &str| event(name, EventType::ViewEvent { id: id.to_string() });
+
+    main.events.extend([
+        event("onCreate", EventType::ActivityEvent),
+        view_event("button1", "onClick"),
+        view_event("webview1", "onPageFinished"),
+        view_event("button1", "onProgressChanged"),
+        view_event("button2", "onClick"),
+        view_event("_drawer_button1", "onClick"),
+        event(
+            "onResponse",
+            EventType::ComponentEvent {
+                id: "net".to_string(),
+                component_type: 17,
+            },
+        ),
+        event(
+            "onResponse",
+            EventType::ComponentEvent {
+                id: "net".to_string(),
+                component_type: 5,
+            },
+        ),
+    ]);
+
+    let errors: Vec<_> = validate_screen(&main)
+        .into_iter()
+        .map(|error| (error.container, error.kind))
+        .collect();
+
+    assert_eq!(
+        errors,
+        vec![
+            (
+                "button1_onProgressChanged".to_string(),
+                InvalidEventKind::Unsupported(UnsupportedEvent {
+                    event: "onProgressChanged".to_string(),
+                    target: "Button".to_string()
+                })
+            ),
+            (
+                "button2_onClick".to_string(),
+                InvalidEventKind::MissingTarget {
+                    id: "button2".to_string()
+                }
+            ),
+            (
+                "net_onResponse".to_string(),
+                InvalidEventKind::ComponentTypeMismatch {
+                    expected: 17,
+                    found: 5
+                }
+            ),
+        ]
+    );
+}
@@ -0,0 +1,164 @@
+mod common;
+
+use common::{blocks, button, linear, project, screen, text_view};
+use swrs::api::block::{ArgValue, Argument, BlockPath};
+use swrs::api::i18n::{
+    localize, parse_strings_xml, to_strings_xml, write_strings_xml, StringTable,
+};
+use swrs::api::screen::{Event, EventType};
+use swrs::api::view::{View, ViewType};
+use swrs::api::{CustomView, SketchwareProject};
+use swrs::color::Color;
+use swrs::parser::view::models::text::{ImeOption, InputType, TextType};
+use swrs::LinkedHashMap;
+
+const ON_CLICK: &str = r#"{"color":-10701022,"id":"10","nextBlock":11,"opCode":"doToast","parameters":["Saved!"],"spec":"Toast %s","subStack1":-1,"subStack2":-1,"type":" ","typeName":""}
+{"color":-8453889,"id":"11","nextBlock":-1,"opCode":"addSourceDirectly","parameters":["finish();"],"spec":"add source directly %s.inputOnly","subStack1":-1,"subStack2":-1,"type":" ","typeName":""}"#;
+
+fn edit_text(id: &str, hint: &str) -> swrs::api::view::View {
+    View::new(
+        id,
+        ViewType::EditText {
+            text: String::new(),
+            text_color: Color::from_rgb(0, 0, 0),
+            text_size: 14,
+            single_line: false,
+            text_font: "default_font".to_string(),
+            text_style: TextType::Normal,
+            lines: 0,
+            hint: hint.to_string(),
+            hint_color: Color::from_rgb(0x60, 0x60, 0x60),
+            ime_option: ImeOption::Normal,
+            input_type: InputType::Text,
+        },
+    )
+}
+
+fn test_project() -> SketchwareProject {
+    let mut main = screen("main");
+
+    main.layout = vec![linear(
+        "linear1",
+        true,
+        vec![
+            edit_text("edittext1", "Your name"),
+            button("button1", "Save"),
+        ],
+    )];
+    main.events.push(Event {
+        name: "onClick".to_string(),
+        event_type: EventType::ViewEvent {
+            id: "button1".to_string(),
+        },
+        code: blocks(ON_CLICK),
+    });
+
+    let mut project = project(vec![main]);
+    project.custom_views.push(CustomView {
+        res_name: "item".to_string(),
+        layout: vec![text_view("textview1", "Item")],
+    });
+
+    project
+}
+
+#[test]
+fn extracts_strings() {
+    let table = StringTable::extract(&test_project());
+
+    let strings: Vec<_> = table
+        .strings
+        .iter()
+        .map(|string| (string.key.as_str(), string.value.as_str()))
+        .collect();
+
+    // the empty text of the edittext and the code of addSourceDirectly are skipped
+    assert_eq!(
+        strings,
+        vec![
+            ("main_edittext1_hint", "Your name"),
+            ("main_button1_text", "Save"),
+            ("item_textview1_text", "Item"),
+            ("main_button1_onclick_0_arg0", "Saved!"),
+        ]
+    );
+    assert_eq!(table.get("main_button1_text"), Some("Save"));
+}
+
+#[test]
+fn strings_xml_round_trip() {
+    let mut strings = LinkedHashMap::new();
+    strings.insert(
+        "greeting".to_string(),
+        "Don't \"quote\" <me> & me".to_string(),
+    );
+    strings.insert("lines".to_string(), "first\nsecond".to_string());
+    strings.insert("mention".to_string(), "@home".to_string());
+
+    let xml = to_strings_xml(&strings);
+    assert_eq!(
+        xml,
+        r#"<?xml version="1.0" encoding="utf-8"?>
+<resources>
+    <string name="greeting">Don\&apos;t \&quot;quote\&quot; &lt;me&gt; &amp; me</string>
+    <string name="lines">first\nsecond</string>
+    <string name="mention">\@home</string>
+</resources>
+"#
+    );
+    assert_eq!(parse_strings_xml(&xml).unwrap(), strings);
+
+    let res = std::env::temp_dir().join(format!("swrs_i18n_{}", std::process::id()));
+    let path = write_strings_xml(&res, Some("id"), &strings).unwrap();
+    assert_eq!(path, res.join("values-id").join("strings.xml"));
+    assert_eq!(
+        parse_strings_xml(&std::fs::read_to_string(&path).unwrap()).unwrap(),
+        strings
+    );
+    std::fs::remove_dir_all(&res).unwrap();
+}
+
+#[test]
+fn localizes_a_copy() {
+    let project = test_project();
+
+    let translations = parse_strings_xml(
+        r#"<resources>
+    <string name="main_edittext1_hint">Nama kamu</string>
+    <string name="main_button1_text">Simpan</string>
+    <string name="main_button1_onclick_0_arg0">Tersimpan!</string>
+</resources>"#,
+    )
+    .unwrap();
+
+    let localized = localize(&project, &translations);
+    let main = &localized.screens[0];
+
+    assert!(matches!(
+        &main.find_view("edittext1").unwrap().view,
+        Ok(ViewType::EditText { hint, .. }) if hint == "Nama kamu"
+    ));
+    assert!(matches!(
+        &main.find_view("button1").unwrap().view,
+        Ok(ViewType::Button { text, .. }) if text == "Simpan"
+    ));
+
+    let toast = main.events[0]
+        .code
+        .get(&"0".parse::<BlockPath>().unwrap())
+        .unwrap();
+    assert!(matches!(
+        toast.content.get_args()[0],
+        Argument::String { value: ArgValue::Value(value), .. } if value == "Tersimpan!"
+    ));
+
+    // untranslated strings and the original project are left untouched
+    assert_eq!(
+        StringTable::extract(&localized).get("item_textview1_text"),
+        Some("Item")
+    );
+    assert_eq!(
+        StringTable::extract(&project).get("main_button1_text"),
+        Some("Save")
+    );
+}
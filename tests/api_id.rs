@@ -0,0 +1,107 @@
+mod common;
+
+use common::{button, linear, screen, text_view};
+use swrs::api::component::ComponentKind;
+use swrs::api::screen::{Drawer, Screen};
+use swrs::api::tree::TreeError;
+use swrs::api::view::ViewType;
+use swrs::parser::logic::variable::{Variable, VariableType};
+use swrs::parser::view::models::layout::{Orientation, Size};
+
+fn test_screen() -> Screen {
+    let mut main = screen("main");
+    main.layout = vec![linear(
+        "linear1",
+        true,
+        vec![text_view("textview1", "Hi"), text_view("textview3", "")],
+    )];
+    main.drawer = Some(Drawer {
+        layout: vec![linear("linear2", true, vec![])],
+    });
+    main.components
+        .insert("timer1".to_string(), ComponentKind::Timer);
+    main.variables.insert(
+        "button1".to_string(),
+        Variable {
+            name: "button1".to_string(),
+            r#type: VariableType::Boolean,
+        },
+    );
+
+    main
+}
+
+fn text_view_type() -> ViewType {
+    text_view("x", "").view.unwrap()
+}
+
+fn button_type() -> ViewType {
+    button("x", "").view.unwrap()
+}
+
+#[test]
+fn sketchware_ids() {
+    let main = test_screen();
+    let mut allocator = main.id_allocator();
+
+    assert_eq!(allocator.view_id(&ViewType::WebView), "webview1");
+    assert_eq!(allocator.view_id(&ViewType::WebView), "webview2");
+
+    let text = text_view_type();
+    assert_eq!(allocator.view_id(&text), "textview2");
+    assert_eq!(allocator.view_id(&text), "textview4");
+
+    // drawer views, variables and components are taken into account
+    let linear = linear("x", true, vec![]).view.unwrap();
+    assert_eq!(allocator.view_id(&linear), "linear3");
+    assert_eq!(allocator.view_id(&button_type()), "button2");
+    assert_eq!(allocator.component_id(&ComponentKind::Timer), "timer2");
+    assert_eq!(
+        allocator.component_id(&ComponentKind::RequestNetwork),
+        "requestnetwork1"
+    );
+
+    let scroll = ViewType::ScrollView {
+        orientation: Orientation::Horizontal,
+        gravity: Default::default(),
+    };
+    assert_eq!(allocator.view_id(&scroll), "hscroll1");
+
+    assert!(allocator.is_used("webview1"));
+    assert!(!allocator.reserve("textview1"));
+    assert!(allocator.reserve("custom5"));
+    assert_eq!(allocator.next("custom"), "custom1");
+}
+
+#[test]
+fn creation_helpers() {
+    let mut main = test_screen();
+
+    assert_eq!(main.add_component(ComponentKind::Timer), "timer2");
+    assert_eq!(main.add_component(ComponentKind::Intent), "intent1");
+    assert!(main.components.contains_key("intent1"));
+
+    let id = main.add_view(Some("linear1"), 1, button_type()).unwrap();
+    assert_eq!(id, "button2");
+    let button = main.find_view("button2").unwrap();
+    assert_eq!(button.width, Size::WrapContent);
+    assert_eq!(main.layout[0].children[1].id, "button2");
+
+    let id = main
+        .add_view(None, 1, linear("x", false, vec![]).view.unwrap())
+        .unwrap();
+    assert_eq!(id, "linear3");
+    assert_eq!(main.find_view("linear3").unwrap().width, Size::MatchParent);
+
+    assert_eq!(
+        main.add_view(Some("textview1"), 0, button_type()),
+        Err(TreeError::NotAContainer {
+            id: "textview1".to_string(),
+            view_type: "TextView".to_string()
+        })
+    );
+
+    let view = main.new_view(text_view_type());
+    assert_eq!(view.id, "textview2");
+    assert_eq!(view.view, Ok(text_view_type()));
+}
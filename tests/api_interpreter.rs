@@ -0,0 +1,190 @@
+mod common;
+
+use common::{blocks, screen};
+use swrs::api::block::{Block, BlockContent};
+use swrs::api::interpreter::{Interpreter, InterpreterError, NoHost, Value};
+use swrs::api::screen::{MoreBlock, Screen};
+use swrs::parser::logic::list_variable::ListVariable;
+use swrs::parser::logic::variable::{Variable, VariableType};
+
+fn main_screen() -> Screen {
+    let mut main = screen("main");
+
+    for (name, r#type) in [
+        ("count", VariableType::Integer),
+        ("name", VariableType::String),
+        ("loaded", VariableType::Boolean),
+    ] {
+        main.variables.insert(
+            name.to_string(),
+            Variable {
+                name: name.to_string(),
+                r#type,
+            },
+        );
+    }
+
+    main.list_variables.insert(
+        "names".to_string(),
+        ListVariable {
+            name: "names".to_string(),
+            r#type: VariableType::String,
+        },
+    );
+
+    main.more_blocks.insert(
+        "greet".to_string(),
+        MoreBlock {
+            name: "greet".to_string(),
+            spec: BlockContent::parse_wo_params("greet %s.name").unwrap(),
+            code: blocks(
+                r#"{"color":-7711273,"id":"10","nextBlock":-1,"opCode":"setText","parameters":["textview1","@11"],"spec":"%m.textview setText %s","subStack1":-1,"subStack2":-1,"type":" ","typeName":""}
+{"color":-10701022,"id":"11","nextBlock":-1,"opCode":"stringJoin","parameters":["Hello, ","@12"],"spec":"join %s and %s","subStack1":-1,"subStack2":-1,"type":"s","typeName":""}
+{"color":-10701022,"id":"12","nextBlock":-1,"opCode":"getArg","parameters":[],"spec":"name","subStack1":-1,"subStack2":-1,"type":"s","typeName":""}"#,
+            ),
+        },
+    );
+
+    main
+}
+
+#[test]
+fn environment_is_seeded() {
+    let main = main_screen();
+    let interpreter = Interpreter::new(&main, NoHost);
+
+    assert_eq!(interpreter.env.variables["count"], Value::Number(0.0));
+    assert_eq!(
+        interpreter.env.variables["name"],
+        Value::String(String::new())
+    );
+    assert_eq!(interpreter.env.variables["loaded"], Value::Boolean(false));
+    assert_eq!(interpreter.env.lists["names"], vec![]);
+}
+
+#[test]
+fn repeat_and_break() {
+    let code = blocks(
+        r#"{"color":-1988310,"id":"10","nextBlock":16,"opCode":"repeat","parameters":["10"],"spec":"repeat %d","subStack1":11,"subStack2":-1,"type":"c","typeName":""}
+{"color":-1147626,"id":"11","nextBlock":12,"opCode":"increaseInt","parameters":["count"],"spec":"%m.varInt increase 1","subStack1":-1,"subStack2":-1,"type":" ","typeName":""}
+{"color":-1988310,"id":"12","nextBlock":-1,"opCode":"if","parameters":["@13"],"spec":"if %b then","subStack1":14,"subStack2":-1,"type":"c","typeName":""}
+{"color":-10701022,"id":"13","nextBlock":-1,"opCode":">","parameters":["@15","2"],"spec":"%d > %d","subStack1":-1,"subStack2":-1,"type":"b","typeName":""}
+{"color":-1147626,"id":"15","nextBlock":-1,"opCode":"getVar","parameters":[],"spec":"count","subStack1":-1,"subStack2":-1,"type":"d","typeName":""}
+{"color":-1988310,"id":"14","nextBlock":-1,"opCode":"break","parameters":[],"spec":"break","subStack1":-1,"subStack2":-1,"type":"f","typeName":""}
+{"color":-3384542,"id":"16","nextBlock":17,"opCode":"addListStr","parameters":["@18","names"],"spec":"add %s to %m.listStr","subStack1":-1,"subStack2":-1,"type":" ","typeName":""}
+{"color":-10701022,"id":"18","nextBlock":-1,"opCode":"toString","parameters":["@19"],"spec":"toString %d without decimal","subStack1":-1,"subStack2":-1,"type":"s","typeName":""}
+{"color":-1147626,"id":"19","nextBlock":-1,"opCode":"getVar","parameters":[],"spec":"count","subStack1":-1,"subStack2":-1,"type":"d","typeName":""}
+{"color":-1147626,"id":"17","nextBlock":-1,"opCode":"setVarBoolean","parameters":["loaded","@20"],"spec":"set %m.varBool to %b","subStack1":-1,"subStack2":-1,"type":" ","typeName":""}
+{"color":-10701022,"id":"20","nextBlock":-1,"opCode":"true","parameters":[],"spec":"true","subStack1":-1,"subStack2":-1,"type":"b","typeName":""}"#,
+    );
+
+    let main = main_screen();
+    let mut interpreter = Interpreter::new(&main, NoHost);
+
+    interpreter.run(&code).unwrap();
+
+    assert_eq!(interpreter.env.variables["count"], Value::Number(3.0));
+    assert_eq!(
+        interpreter.env.lists["names"],
+        vec![Value::String("3".to_string())]
+    );
+    assert_eq!(interpreter.env.variables["loaded"], Value::Boolean(true));
+}
+
+#[test]
+fn step_budget() {
+    let code = blocks(
+        r#"{"color":-1988310,"id":"10","nextBlock":-1,"opCode":"forever","parameters":[],"spec":"forever","subStack1":11,"subStack2":-1,"type":"c","typeName":""}
+{"color":-1147626,"id":"11","nextBlock":-1,"opCode":"increaseInt","parameters":["count"],"spec":"%m.varInt increase 1","subStack1":-1,"subStack2":-1,"type":" ","typeName":""}"#,
+    );
+
+    let main = main_screen();
+    let mut interpreter = Interpreter::new(&main, NoHost).with_step_budget(100);
+
+    assert_eq!(
+        interpreter.run(&code),
+        Err(InterpreterError::StepBudgetExceeded { budget: 100 })
+    );
+    assert_eq!(interpreter.steps(), 101);
+}
+
+#[test]
+fn more_blocks_and_host() {
+    let code = blocks(
+        r#"{"color":-7711273,"id":"10","nextBlock":11,"opCode":"definedFunc","parameters":["World"],"spec":"greet %s.name","subStack1":-1,"subStack2":-1,"type":" ","typeName":""}
+{"color":-7711273,"id":"11","nextBlock":-1,"opCode":"doToast","parameters":["done"],"spec":"Toast %s","subStack1":-1,"subStack2":-1,"type":" ","typeName":""}"#,
+    );
+
+    let main = main_screen();
+    let mut calls = Vec::new();
+    let mut interpreter = Interpreter::new(&main, |block: &Block, args: Vec<Value>| {
+        calls.push((block.op_code.to_owned(), args));
+        Ok(None)
+    });
+
+    interpreter.run(&code).unwrap();
+    drop(interpreter);
+
+    assert_eq!(
+        calls,
+        vec![
+            (
+                "setText".to_string(),
+                vec![
+                    Value::String("textview1".to_string()),
+                    Value::String("Hello, World".to_string())
+                ]
+            ),
+            (
+                "doToast".to_string(),
+                vec![Value::String("done".to_string())]
+            ),
+        ]
+    );
+
+    // android specific blocks aren't supported without a host
+    let mut interpreter = Interpreter::new(&main, NoHost);
+    assert_eq!(
+        interpreter.run(&code),
+        Err(InterpreterError::UnsupportedBlock {
+            op_code: "setText".to_string()
+        })
+    );
+}
+
+#[test]
+fn menu_parameters() {
+    let mut main = main_screen();
+    main.more_blocks.insert(
+        "toastView".to_string(),
+        MoreBlock {
+            name: "toastView".to_string(),
+            spec: BlockContent::parse_wo_params("toastView %m.view.target").unwrap(),
+            code: blocks(
+                r#"{"color":-7711273,"id":"10","nextBlock":-1,"opCode":"doToast","parameters":["@11"],"spec":"Toast %s","subStack1":-1,"subStack2":-1,"type":" ","typeName":""}
+{"color":-10701022,"id":"11","nextBlock":-1,"opCode":"getArg","parameters":[],"spec":"target","subStack1":-1,"subStack2":-1,"type":"s","typeName":""}"#,
+            ),
+        },
+    );
+
+    let code = blocks(
+        r#"{"color":-7711273,"id":"10","nextBlock":-1,"opCode":"definedFunc","parameters":["textview1"],"spec":"toastView %m.view.target","subStack1":-1,"subStack2":-1,"type":" ","typeName":""}"#,
+    );
+
+    let mut calls = Vec::new();
+    let mut interpreter = Interpreter::new(&main, |block: &Block, args: Vec<Value>| {
+        calls.push((block.op_code.to_owned(), args));
+        Ok(None)
+    });
+
+    interpreter.run(&code).unwrap();
+    drop(interpreter);
+
+    assert_eq!(
+        calls,
+        vec![(
+            "doToast".to_string(),
+            vec![Value::String("textview1".to_string())]
+        )]
+    );
+}
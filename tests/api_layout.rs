@@ -0,0 +1,193 @@
+mod common;
+
+use common::{button, linear, text_view};
+use swrs::api::id::IdAllocator;
+use swrs::api::layout::{normalize, normalize_raw, validate, validate_raw, LayoutProblem};
+use swrs::api::view::{View, ViewType};
+use swrs::parser::view::models::layout::gravity::Gravity;
+use swrs::parser::view::models::layout::Orientation;
+use swrs::parser::view::models::AndroidView;
+use swrs::parser::view::Layout;
+
+fn raw(id: &str, r#type: u8, parent: &str, parent_type: i8, index: u32) -> AndroidView {
+    let mut view = AndroidView::new_empty(id, r#type, parent, parent_type);
+    view.index = index;
+    view
+}
+
+/// Describes a tree of views by their ids, like `linear1(button1,textview1)`
+fn shape(views: &[View]) -> String {
+    views
+        .iter()
+        .map(|view| {
+            if view.children.is_empty() {
+                view.id.to_owned()
+            } else {
+                format!("{}({})", view.id, shape(&view.children))
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+#[test]
+fn raw_problems() {
+    let valid = Layout(vec![
+        raw("linear1", 0, "root", 0, 0),
+        raw("vscroll1", 12, "linear1", 0, 0),
+        raw("textview1", 4, "vscroll1", 12, 0),
+    ]);
+    assert!(validate_raw(&valid).is_empty());
+
+    let layout = Layout(vec![
+        raw("linear1", 0, "root", 0, 0),
+        raw("button1", 3, "root", 0, 1),
+        raw("textview1", 4, "button1", 3, 0),
+        raw("vscroll1", 12, "root", 0, 3),
+        raw("linear2", 0, "vscroll1", 12, 0),
+        raw("linear3", 0, "vscroll1", 0, 1),
+        raw("fab", 16, "root", 0, 4),
+        raw("linear4", 0, "linear5", 0, 0),
+        raw("linear5", 0, "root", 0, 5),
+        raw("textview2", 4, "ghost", 0, 0),
+        raw("linear1", 0, "root", 0, 6),
+    ]);
+
+    let id = |id: &str| id.to_string();
+    assert_eq!(
+        validate_raw(&layout),
+        vec![
+            LayoutProblem::DuplicateId {
+                id: id("linear1"),
+                count: 2
+            },
+            LayoutProblem::NonContainerParent {
+                id: id("textview1"),
+                parent: id("button1"),
+                parent_type: id("Button"),
+            },
+            LayoutProblem::ParentTypeMismatch {
+                id: id("linear3"),
+                parent: id("vscroll1"),
+                expected: 12,
+                found: 0,
+            },
+            LayoutProblem::FabInLayout { id: id("fab") },
+            LayoutProblem::ParentAfterChild {
+                id: id("linear4"),
+                parent: id("linear5"),
+            },
+            LayoutProblem::MissingParent {
+                id: id("textview2"),
+                parent: id("ghost"),
+            },
+            LayoutProblem::CrowdedScrollView {
+                id: id("vscroll1"),
+                count: 2,
+            },
+            LayoutProblem::NonContiguousIndex {
+                parent: id("root"),
+                indexes: vec![0, 1, 3, 4, 5, 6],
+            },
+        ]
+    );
+}
+
+#[test]
+fn normalize_views() {
+    let mut button = button("button1", "Click");
+    button.children = vec![text_view("textview1", "Inside")];
+
+    let mut scroll = View::new(
+        "vscroll1",
+        ViewType::ScrollView {
+            orientation: Orientation::Vertical,
+            gravity: Gravity::default(),
+        },
+    );
+    scroll.children = vec![
+        text_view("textview2", "First"),
+        text_view("textview1", "Second"),
+    ];
+
+    let fab = View::new(
+        "fab1",
+        ViewType::Fab {
+            image_res_name: "icon".to_string(),
+        },
+    );
+
+    let mut layout = vec![linear("linear1", true, vec![button]), scroll, fab];
+
+    let problems: Vec<String> = validate(&layout).iter().map(ToString::to_string).collect();
+    assert_eq!(
+        problems,
+        vec![
+            "the id `textview1` is used by 2 views",
+            "`textview1` is inside of `button1`, which is a Button that can't have children",
+            "the scroll view `vscroll1` has 2 children, it can only have one",
+            "the fab `fab1` is inside of the layout",
+        ]
+    );
+
+    let mut allocator = IdAllocator::from_ids(
+        ["linear1", "button1", "textview1", "textview2", "vscroll1"]
+            .iter()
+            .map(ToString::to_string),
+    );
+
+    assert!(normalize(&mut layout, &mut allocator).is_empty());
+    assert_eq!(
+        shape(&layout),
+        "linear1(button1,textview1),vscroll1(linear2(textview2,textview3))"
+    );
+
+    // the wrapper follows the orientation of the scroll view
+    let wrapper = &layout[1].children[0];
+    assert!(matches!(
+        wrapper.view,
+        Ok(ViewType::LinearLayout {
+            orientation: Orientation::Vertical,
+            ..
+        })
+    ));
+}
+
+#[test]
+fn normalize_raw_layout() {
+    let mut layout = Layout(vec![
+        raw("textview1", 4, "linear1", 0, 3),
+        raw("linear1", 0, "root", 0, 7),
+        raw("button1", 3, "linear1", 3, 1),
+        raw("textview2", 4, "button1", 3, 0),
+        raw("textview3", 4, "ghost", 0, 0),
+        raw("fab", 16, "root", 0, 0),
+    ]);
+    assert!(!validate_raw(&layout).is_empty());
+
+    assert!(normalize_raw(&mut layout).is_empty());
+
+    let views: Vec<_> = layout
+        .0
+        .iter()
+        .map(|view| {
+            (
+                view.id.as_str(),
+                view.parent.as_deref().unwrap(),
+                view.parent_type,
+                view.index,
+            )
+        })
+        .collect();
+
+    assert_eq!(
+        views,
+        vec![
+            ("linear1", "root", 0, 0),
+            ("textview3", "root", 0, 1),
+            ("textview1", "linear1", 0, 0),
+            ("button1", "linear1", 0, 1),
+            ("textview2", "linear1", 0, 2),
+        ]
+    );
+}
@@ -0,0 +1,161 @@
+mod common;
+
+use common::{button, on_click, project, screen};
+use swrs::api::block::{ArgValue, Argument, BlockPath};
+use swrs::api::lifecycle::{
+    java_name, validate_screen_name, InvalidScreenName, ScreenLifecycleError,
+};
+use swrs::api::screen::{Drawer, Event};
+use swrs::api::{CustomView, SketchwareProject};
+
+fn open_screen(activity: &str) -> Event {
+    on_click(
+        "button1",
+        &format!(
+            r#"{{"color":-11899692,"id":"10","nextBlock":11,"opCode":"intentSetScreen","parameters":["intent","{}"],"spec":"%m.intent setScreen %m.activity","subStack1":-1,"subStack2":-1,"type":" ","typeName":""}}
+{{"color":-11899692,"id":"11","nextBlock":-1,"opCode":"startActivity","parameters":["intent"],"spec":"StartActivity %m.intent","subStack1":-1,"subStack2":-1,"type":" ","typeName":""}}"#,
+            activity
+        ),
+    )
+}
+
+fn target(project: &SketchwareProject) -> String {
+    let block = project.screens[0].events[0]
+        .code
+        .get(&"0".parse::<BlockPath>().unwrap())
+        .unwrap();
+
+    match block.content.get_args()[1] {
+        Argument::Menu {
+            value: ArgValue::Value(value),
+            ..
+        } => value.to_owned(),
+        other => panic!("unexpected argument {:?}", other),
+    }
+}
+
+fn test_project() -> SketchwareProject {
+    let mut main = screen("main");
+    main.layout = vec![button("button1", "Settings")];
+    main.events.push(open_screen("SettingsActivity"));
+
+    let mut settings = screen("settings");
+    settings.set_drawer_enabled(true);
+    settings.drawer = Some(Drawer {
+        layout: vec![button("button1", "Home")],
+    });
+
+    project(vec![main, settings])
+}
+
+#[test]
+fn screen_names() {
+    assert_eq!(java_name("screen_display"), "ScreenDisplayActivity");
+    assert_eq!(validate_screen_name("about_2"), Ok(()));
+    assert_eq!(validate_screen_name(""), Err(InvalidScreenName::Empty));
+    assert_eq!(
+        validate_screen_name("2nd"),
+        Err(InvalidScreenName::InvalidStart('2'))
+    );
+    assert_eq!(
+        validate_screen_name("Settings"),
+        Err(InvalidScreenName::InvalidStart('S'))
+    );
+    assert_eq!(
+        validate_screen_name("my-screen"),
+        Err(InvalidScreenName::InvalidCharacter('-'))
+    );
+    assert_eq!(
+        validate_screen_name("class"),
+        Err(InvalidScreenName::Keyword)
+    );
+
+    let mut project = test_project();
+    project.custom_views.push(CustomView {
+        res_name: "item".to_string(),
+        layout: vec![],
+    });
+
+    let screen = project.add_screen("about").unwrap();
+    assert_eq!(screen.java_name, "AboutActivity");
+    assert!(screen.layout.is_empty());
+    assert_eq!(project.screens.len(), 3);
+
+    assert_eq!(
+        project.add_screen("item").unwrap_err(),
+        ScreenLifecycleError::AlreadyExists {
+            name: "item".to_string()
+        }
+    );
+    assert_eq!(
+        project.add_screen("settings_").unwrap_err(),
+        ScreenLifecycleError::JavaNameTaken {
+            java_name: "SettingsActivity".to_string(),
+            screen: "settings".to_string()
+        }
+    );
+    assert!(matches!(
+        project.add_screen("Main"),
+        Err(ScreenLifecycleError::InvalidName { .. })
+    ));
+}
+
+#[test]
+fn rename_and_remove() {
+    let mut project = test_project();
+    assert_eq!(
+        project.rename_screen("main", "home"),
+        Err(ScreenLifecycleError::MainScreen)
+    );
+
+    project.rename_screen("settings", "preferences").unwrap();
+    let screen = project.get_screen("preferences").unwrap();
+    assert_eq!(screen.java_name, "PreferencesActivity");
+    assert_eq!(screen.drawer_layout_name(), "_drawer_preferences");
+    assert_eq!(target(&project), "PreferencesActivity");
+
+    // a stray drawer layout of a removed screen mustn't be picked up by a new one
+    project.custom_views.push(CustomView {
+        res_name: "_drawer_preferences".to_string(),
+        layout: vec![],
+    });
+    let removed = project.remove_screen("preferences").unwrap();
+    assert!(removed.drawer.is_some());
+    assert!(project.custom_views.is_empty());
+    assert!(project.get_screen("preferences").is_none());
+    assert_eq!(target(&project), "");
+
+    assert_eq!(
+        project.remove_screen("preferences").unwrap_err(),
+        ScreenLifecycleError::NotFound {
+            name: "preferences".to_string()
+        }
+    );
+    assert_eq!(
+        project.remove_screen("main"),
+        Err(ScreenLifecycleError::MainScreen)
+    );
+}
+
+#[test]
+fn duplicate() {
+    let mut project = test_project();
+
+    let copy = project.duplicate_screen("main", "main_copy").unwrap();
+    assert_eq!(copy.java_name, "MainCopyActivity");
+    copy.layout.clear();
+
+    let names: Vec<_> = project
+        .screens
+        .iter()
+        .map(|screen| screen.layout_name.as_str())
+        .collect();
+    assert_eq!(names, vec!["main", "main_copy", "settings"]);
+
+    // the copy is independent of the original but goes to the same screens
+    assert_eq!(project.screens[0].layout.len(), 1);
+    assert_eq!(project.screens[1].events, project.screens[0].events);
+
+    assert!(project.duplicate_screen("main", "settings").is_err());
+    assert!(project.duplicate_screen("about", "about_copy").is_err());
+}
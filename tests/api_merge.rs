@@ -0,0 +1,222 @@
+mod common;
+
+use common::{blocks, button, linear, project, screen, text_view};
+use swrs::api::merge::{merge, Conflict, Side};
+use swrs::api::screen::{Event, EventType, Screen};
+use swrs::api::view::ViewType;
+use swrs::api::SketchwareProject;
+use swrs::parser::logic::variable::{Variable, VariableType};
+
+fn base() -> SketchwareProject {
+    let mut main = screen("main");
+    main.layout = vec![
+        linear(
+            "linear1",
+            true,
+            vec![text_view("textview1", "Hello"), button("button1", "Click")],
+        ),
+        linear("linear2", true, vec![]),
+    ];
+
+    main.events.push(Event {
+        name: "onClick".to_string(),
+        event_type: EventType::ViewEvent {
+            id: "button1".to_string(),
+        },
+        code: blocks(
+            r#"{"color":-7711273,"id":"10","nextBlock":-1,"opCode":"doToast","parameters":["Clicked"],"spec":"Toast %s","subStack1":-1,"subStack2":-1,"type":" ","typeName":""}"#,
+        ),
+    });
+
+    project(vec![main])
+}
+
+fn text_of(screen: &Screen, id: &str) -> String {
+    match &screen.find_view(id).unwrap().view {
+        Ok(ViewType::TextView { text, .. }) | Ok(ViewType::Button { text, .. }) => text.to_owned(),
+        _ => panic!("{} has no text", id),
+    }
+}
+
+fn set_text(screen: &mut Screen, id: &str, new_text: &str) {
+    match &mut screen.find_view_mut(id).unwrap().view {
+        Ok(ViewType::TextView { text, .. }) | Ok(ViewType::Button { text, .. }) => {
+            *text = new_text.to_string()
+        }
+        _ => panic!("{} has no text", id),
+    }
+}
+
+#[test]
+fn combines_both_sides() {
+    let base = base();
+
+    let mut ours = base.clone();
+    ours.metadata.version_code = 2;
+    set_text(&mut ours.screens[0], "textview1", "Hi");
+    ours.screens[0].variables.insert(
+        "count".to_string(),
+        Variable {
+            name: "count".to_string(),
+            r#type: VariableType::Integer,
+        },
+    );
+
+    let mut theirs = base.clone();
+    theirs.metadata.version_name = "1.1".to_string();
+    set_text(&mut theirs.screens[0], "button1", "Press");
+    let button1 = theirs.screens[0].layout[0].children.remove(1);
+    theirs.screens[0].layout[1].children.push(button1);
+    theirs.screens[0].layout[1]
+        .children
+        .push(text_view("textview2", "New"));
+    theirs.screens.push(screen("about"));
+
+    let result = merge(&base, &ours, &theirs);
+    assert!(result.is_clean(), "{:?}", result.conflicts);
+
+    let project = result.project;
+    assert_eq!(project.metadata.version_code, 2);
+    assert_eq!(project.metadata.version_name, "1.1");
+    assert_eq!(project.screens.len(), 2);
+
+    let main = &project.screens[0];
+    assert!(main.variables.contains_key("count"));
+    assert_eq!(text_of(main, "textview1"), "Hi");
+    assert_eq!(text_of(main, "button1"), "Press");
+
+    let ids = |children: &[swrs::api::view::View]| {
+        children
+            .iter()
+            .map(|view| view.id.to_owned())
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(ids(&main.layout[0].children), vec!["textview1"]);
+    assert_eq!(ids(&main.layout[1].children), vec!["button1", "textview2"]);
+}
+
+#[test]
+fn attribute_conflict() {
+    let base = base();
+
+    let mut ours = base.clone();
+    set_text(&mut ours.screens[0], "textview1", "Ours");
+
+    let mut theirs = base.clone();
+    set_text(&mut theirs.screens[0], "textview1", "Theirs");
+    // deletes a view that ours modified
+    theirs.screens[0].layout[0].children.remove(1);
+    set_text(&mut ours.screens[0], "button1", "Press");
+
+    let mut result = merge(&base, &ours, &theirs);
+    assert_eq!(
+        result.conflicts,
+        vec![
+            Conflict::ViewAttribute {
+                screen: "main".to_string(),
+                id: "textview1".to_string(),
+                attribute: "text.text".to_string(),
+                ours: "Ours".into(),
+                theirs: "Theirs".into(),
+            },
+            Conflict::ViewDeleted {
+                screen: "main".to_string(),
+                id: "button1".to_string(),
+                deleted_by: Side::Theirs,
+            },
+        ]
+    );
+
+    // conflicts are left as ours
+    assert_eq!(text_of(&result.project.screens[0], "textview1"), "Ours");
+    assert!(result.project.screens[0].find_view("button1").is_some());
+
+    result.resolve_all(Side::Theirs).unwrap();
+    assert_eq!(text_of(&result.project.screens[0], "textview1"), "Theirs");
+    assert!(result.project.screens[0].find_view("button1").is_none());
+}
+
+#[test]
+fn event_conflict() {
+    let base = base();
+
+    let mut ours = base.clone();
+    ours.screens[0].events[0].code = blocks(
+        r#"{"color":-7711273,"id":"10","nextBlock":-1,"opCode":"doToast","parameters":["Ours"],"spec":"Toast %s","subStack1":-1,"subStack2":-1,"type":" ","typeName":""}"#,
+    );
+
+    let mut theirs = base.clone();
+    theirs.screens[0].events.clear();
+
+    let result = merge(&base, &ours, &theirs);
+    assert_eq!(result.conflicts.len(), 1);
+
+    let conflict = &result.conflicts[0];
+    assert!(matches!(
+        conflict,
+        Conflict::Event { screen, container, ours: Some(_), theirs: None }
+            if screen == "main" && container == "button1_onClick"
+    ));
+
+    let mut project = result.project.clone();
+    conflict.resolve(&mut project, Side::Theirs).unwrap();
+    assert!(project.screens[0].events.is_empty());
+
+    let mut project = result.project;
+    conflict.resolve(&mut project, Side::Ours).unwrap();
+    assert_eq!(project.screens[0].events, ours.screens[0].events);
+}
+
+#[test]
+fn drawer_changes() {
+    let mut base = base();
+    base.screens[0].set_drawer_enabled(true);
+    base.screens[0].drawer.as_mut().unwrap().layout = vec![linear(
+        "linear1",
+        true,
+        vec![text_view("textview1", "Home")],
+    )];
+
+    // only theirs edits the drawer, ours edits the layout
+    let mut ours = base.clone();
+    set_text(&mut ours.screens[0], "textview1", "Hi");
+    let mut theirs = base.clone();
+    set_text(&mut theirs.screens[0], "_drawer_textview1", "Beranda");
+    theirs.screens[0].drawer.as_mut().unwrap().layout[0]
+        .children
+        .push(button("button1", "Settings"));
+
+    let result = merge(&base, &ours, &theirs);
+    assert!(result.is_clean());
+    let main = &result.project.screens[0];
+    assert_eq!(text_of(main, "textview1"), "Hi");
+    assert_eq!(text_of(main, "_drawer_textview1"), "Beranda");
+    assert_eq!(text_of(main, "_drawer_button1"), "Settings");
+
+    // conflicting edits on the drawer refer to its views with prefixed ids
+    set_text(&mut ours.screens[0], "_drawer_textview1", "Rumah");
+    let mut result = merge(&base, &ours, &theirs);
+    assert!(matches!(
+        &result.conflicts[..],
+        [Conflict::ViewAttribute { id, .. }] if id == "_drawer_textview1"
+    ));
+
+    result.resolve_all(Side::Theirs).unwrap();
+    assert_eq!(
+        text_of(&result.project.screens[0], "_drawer_textview1"),
+        "Beranda"
+    );
+
+    // removing the drawer on one side while it's edited on the other conflicts
+    ours.screens[0].set_drawer_enabled(false);
+    let mut result = merge(&base, &ours, &theirs);
+    assert!(result
+        .conflicts
+        .iter()
+        .any(|conflict| matches!(conflict, Conflict::Drawer { .. })));
+    assert_eq!(result.project.screens[0].drawer, None);
+
+    result.resolve_all(Side::Theirs).unwrap();
+    assert_eq!(result.project.screens[0].drawer, theirs.screens[0].drawer);
+}
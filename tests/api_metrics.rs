@@ -0,0 +1,137 @@
+mod common;
+
+use common::{blocks, button, linear, project, screen, text_view};
+use swrs::api::metrics::{BlocksMetrics, LayoutMetrics, ProjectMetrics};
+use swrs::api::screen::{Event, EventType, Screen};
+use swrs::api::{CustomView, ResourceId};
+use swrs::parser::{ResourceFileWrapper, ResourceType};
+
+const LOGIC: &str = r#"{"color":-1988310,"id":"10","nextBlock":16,"opCode":"if","parameters":["@11"],"spec":"if %b then","subStack1":12,"subStack2":-1,"type":"c","typeName":""}
+{"color":-10701022,"id":"11","nextBlock":-1,"opCode":"true","parameters":[],"spec":"true","subStack1":-1,"subStack2":-1,"type":"b","typeName":""}
+{"color":-1988310,"id":"12","nextBlock":-1,"opCode":"ifElse","parameters":["@13"],"spec":"if %b then","subStack1":14,"subStack2":15,"type":"e","typeName":""}
+{"color":-10701022,"id":"13","nextBlock":-1,"opCode":">","parameters":["1","2"],"spec":"%d > %d","subStack1":-1,"subStack2":-1,"type":"b","typeName":""}
+{"color":-8453889,"id":"14","nextBlock":-1,"opCode":"addSourceDirectly","parameters":["finish();"],"spec":"add source directly %s.inputOnly","subStack1":-1,"subStack2":-1,"type":" ","typeName":""}
+{"color":-10701022,"id":"15","nextBlock":-1,"opCode":"doToast","parameters":["Hi"],"spec":"Toast %s","subStack1":-1,"subStack2":-1,"type":" ","typeName":""}
+{"color":-8453889,"id":"16","nextBlock":-1,"opCode":"addSourceDirectly","parameters":["return;"],"spec":"add source directly %s.inputOnly","subStack1":-1,"subStack2":-1,"type":" ","typeName":""}"#;
+
+const TOAST: &str = r#"{"color":-10701022,"id":"10","nextBlock":-1,"opCode":"doToast","parameters":["Hello"],"spec":"Toast %s","subStack1":-1,"subStack2":-1,"type":" ","typeName":""}"#;
+
+fn main_screen() -> Screen {
+    let mut main = screen("main");
+
+    main.layout = vec![linear(
+        "linear1",
+        true,
+        vec![
+            linear("linear2", false, vec![button("button1", "Click")]),
+            text_view("textview1", "Hello"),
+        ],
+    )];
+
+    main.events.extend([
+        Event {
+            name: "onCreate".to_string(),
+            event_type: EventType::ActivityEvent,
+            code: blocks(LOGIC),
+        },
+        Event {
+            name: "onClick".to_string(),
+            event_type: EventType::ViewEvent {
+                id: "button1".to_string(),
+            },
+            code: blocks(TOAST),
+        },
+    ]);
+
+    main
+}
+
+#[test]
+fn block_metrics() {
+    let metrics = BlocksMetrics::measure("onCreate_initializeLogic".to_string(), &blocks(LOGIC));
+
+    assert_eq!(
+        metrics,
+        BlocksMetrics {
+            container: "onCreate_initializeLogic".to_string(),
+            blocks: 6,
+            max_nesting_depth: 2,
+            cyclomatic_complexity: 3,
+            add_source_directly: 2,
+        }
+    );
+}
+
+#[test]
+fn layout_metrics() {
+    let main = main_screen();
+
+    assert_eq!(
+        LayoutMetrics::measure(&main.layout),
+        LayoutMetrics { views: 4, depth: 3 }
+    );
+    assert_eq!(
+        LayoutMetrics::measure(&[]),
+        LayoutMetrics { views: 0, depth: 0 }
+    );
+}
+
+#[test]
+fn project_metrics() {
+    let mut project = project(vec![main_screen(), screen("about")]);
+    project.custom_views.push(CustomView {
+        res_name: "item".to_string(),
+        layout: vec![text_view("textview1", "Item")],
+    });
+
+    let manifest = concat!(env!("CARGO_MANIFEST_DIR"), "/Cargo.toml");
+    project
+        .resources
+        .put_resource(
+            ResourceId("manifest".to_string()),
+            ResourceFileWrapper::Path(manifest.into()),
+            ResourceType::Image,
+        )
+        .unwrap();
+    project
+        .resources
+        .put_resource(
+            ResourceId("icon".to_string()),
+            ResourceFileWrapper::StringId {
+                id: "icon".to_string(),
+                res_full_name: "icon.png".to_string(),
+                res_type: ResourceType::Image,
+            },
+            ResourceType::Image,
+        )
+        .unwrap();
+
+    let metrics = ProjectMetrics::measure(&project);
+
+    assert_eq!(metrics.blocks, 7);
+    assert_eq!(metrics.max_nesting_depth, 2);
+    assert_eq!(metrics.max_cyclomatic_complexity, 3);
+    assert_eq!(metrics.add_source_directly, 2);
+    assert_eq!(metrics.views, 5);
+
+    assert_eq!(metrics.resources.images, 2);
+    assert_eq!(
+        metrics.resources.bytes,
+        std::fs::metadata(manifest).unwrap().len()
+    );
+    assert_eq!(metrics.resources.unknown_sizes, 1);
+
+    let containers: Vec<_> = metrics.screens[0]
+        .containers
+        .iter()
+        .map(|container| (container.container.as_str(), container.blocks))
+        .collect();
+    assert_eq!(
+        containers,
+        vec![("onCreate_initializeLogic", 6), ("button1_onClick", 1)]
+    );
+
+    let json = serde_json::to_string(&metrics).unwrap();
+    let parsed: ProjectMetrics = serde_json::from_str(&json).unwrap();
+    assert_eq!(parsed, metrics);
+}
@@ -0,0 +1,162 @@
+mod common;
+
+use common::{blocks, event, on_click, project, screen};
+use swrs::api::block::BlockContent;
+use swrs::api::navigation::{NavigationGraph, Target};
+use swrs::api::screen::{EventType, MoreBlock, Screen};
+use swrs::api::SketchwareProject;
+
+fn set_screen(id: u32, next: i32, intent: &str, activity: &str) -> String {
+    format!(
+        r#"{{"color":-11899692,"id":"{}","nextBlock":{},"opCode":"intentSetScreen","parameters":["{}","{}"],"spec":"%m.intent setScreen %m.activity","subStack1":-1,"subStack2":-1,"type":" ","typeName":""}}"#,
+        id, next, intent, activity
+    )
+}
+
+fn start_activity(id: u32, intent: &str) -> String {
+    format!(
+        r#"{{"color":-11899692,"id":"{}","nextBlock":-1,"opCode":"startActivity","parameters":["{}"],"spec":"StartActivity %m.intent","subStack1":-1,"subStack2":-1,"type":" ","typeName":""}}"#,
+        id, intent
+    )
+}
+
+fn main_screen() -> Screen {
+    let mut main = screen("main");
+
+    main.more_blocks.insert(
+        "openSettings".to_string(),
+        MoreBlock {
+            name: "openSettings".to_string(),
+            spec: BlockContent::parse_wo_params("openSettings").unwrap(),
+            code: blocks(&format!(
+                "{}\n{}",
+                set_screen(10, 11, "intent", "SettingsActivity"),
+                start_activity(11, "intent")
+            )),
+        },
+    );
+
+    main.events.extend([
+        // the browser intent is set here but started elsewhere
+        event(
+            "onCreate",
+            EventType::ActivityEvent,
+            &set_screen(10, -1, "browser", "BrowserActivity"),
+        ),
+        on_click(
+            "button1",
+            &format!(
+                "{}\n{}",
+                set_screen(10, 11, "intent", "AboutActivity"),
+                start_activity(11, "intent")
+            ),
+        ),
+        on_click(
+            "button2",
+            r#"{"color":-7711273,"id":"10","nextBlock":-1,"opCode":"definedFunc","parameters":[],"spec":"openSettings","subStack1":-1,"subStack2":-1,"type":" ","typeName":""}"#,
+        ),
+        on_click("button3", &start_activity(10, "browser")),
+    ]);
+
+    main
+}
+
+fn test_project() -> SketchwareProject {
+    project(vec![main_screen(), screen("about"), screen("settings")])
+}
+
+#[test]
+fn resolves_targets() {
+    let graph = NavigationGraph::build(&test_project());
+
+    let navigations: Vec<_> = graph
+        .from_screen("main")
+        .map(|navigation| {
+            (
+                navigation.to.to_owned(),
+                navigation.container.as_str(),
+                navigation.events.to_owned(),
+            )
+        })
+        .collect();
+
+    assert_eq!(
+        navigations,
+        vec![
+            (
+                Target::Screen("about".to_string()),
+                "button1_onClick",
+                vec!["button1_onClick".to_string()]
+            ),
+            (
+                Target::Unknown("BrowserActivity".to_string()),
+                "button3_onClick",
+                vec!["button3_onClick".to_string()]
+            ),
+            (
+                Target::Screen("settings".to_string()),
+                "openSettings_moreBlock",
+                vec!["button2_onClick".to_string()]
+            ),
+        ]
+    );
+    assert!(graph.unreachable_screens().is_empty());
+}
+
+#[test]
+fn unreachable_screens() {
+    // the main screen is where the app starts
+    let graph = NavigationGraph::build(&project(vec![screen("main")]));
+    assert!(graph.unreachable_screens().is_empty());
+
+    let graph = NavigationGraph::build(&project(vec![main_screen(), screen("hidden")]));
+    assert_eq!(graph.unreachable_screens(), vec!["hidden"]);
+}
+
+#[test]
+fn exports() {
+    let graph = NavigationGraph::build(&test_project());
+
+    assert_eq!(
+        graph.to_dot(),
+        r#"digraph navigation {
+    "main" [shape=box];
+    "about" [shape=box];
+    "settings" [shape=box];
+    "BrowserActivity" [shape=box, style=dashed];
+    "main" -> "about" [label="button1_onClick"];
+    "main" -> "BrowserActivity" [label="button3_onClick"];
+    "main" -> "settings" [label="button2_onClick"];
+}
+"#
+    );
+
+    assert_eq!(
+        graph.to_mermaid(),
+        r#"flowchart LR
+    s_main["main"]
+    s_about["about"]
+    s_settings["settings"]
+    u_BrowserActivity[/"BrowserActivity"/]
+    s_main -->|"button1_onClick"| s_about
+    s_main -->|"button3_onClick"| u_BrowserActivity
+    s_main -->|"button2_onClick"| s_settings
+"#
+    );
+}
+
+#[test]
+fn json_round_trip() {
+    let graph = NavigationGraph::build(&test_project());
+    let json = graph.to_json();
+
+    let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(
+        value["navigations"][0]["to"],
+        serde_json::json!({"kind": "screen", "name": "about"})
+    );
+    assert_eq!(value["navigations"][0]["path"], "1");
+
+    let parsed: NavigationGraph = serde_json::from_str(&json).unwrap();
+    assert_eq!(parsed, graph);
+}
@@ -0,0 +1,267 @@
+mod common;
+
+use common::{blocks, button, linear, project, screen, text_view};
+use swrs::api::block::BlockContent;
+use swrs::api::diff::diff;
+use swrs::api::patch::{Operation, Patch, PatchOperationError};
+use swrs::api::screen::{Event, EventType, MoreBlock, Screen};
+use swrs::api::view::{View, ViewType};
+use swrs::api::{ResourceId, SketchwareProject};
+use swrs::parser::logic::variable::{Variable, VariableType};
+use swrs::parser::{ResourceFileWrapper, ResourceType};
+
+const ON_CLICK: &str = r#"{"color":-7711273,"id":"10","nextBlock":11,"opCode":"setText","parameters":["textview1","Clicked"],"spec":"%m.textview setText %s","subStack1":-1,"subStack2":-1,"type":" ","typeName":""}
+{"color":-1147626,"id":"11","nextBlock":-1,"opCode":"increaseInt","parameters":["count"],"spec":"%m.varInt increase 1","subStack1":-1,"subStack2":-1,"type":" ","typeName":""}"#;
+
+fn main_screen() -> Screen {
+    let mut main = screen("main");
+    main.layout = vec![
+        linear(
+            "linear1",
+            true,
+            vec![text_view("textview1", "Hello"), button("button1", "Click")],
+        ),
+        linear("linear2", true, vec![]),
+    ];
+
+    main.variables.insert(
+        "count".to_string(),
+        Variable {
+            name: "count".to_string(),
+            r#type: VariableType::Integer,
+        },
+    );
+
+    main.events.push(Event {
+        name: "onClick".to_string(),
+        event_type: EventType::ViewEvent {
+            id: "button1".to_string(),
+        },
+        code: blocks(ON_CLICK),
+    });
+
+    main
+}
+
+fn set_text(screen: &mut Screen, id: &str, new_text: &str) {
+    if let Ok(ViewType::TextView { text, .. }) = &mut screen.find_view_mut(id).unwrap().view {
+        *text = new_text.to_string();
+    }
+}
+
+fn changed(old: &SketchwareProject) -> SketchwareProject {
+    let mut new = old.clone();
+    new.metadata.version_code = 2;
+
+    new.resources
+        .put_resource(
+            ResourceId("logo".to_string()),
+            ResourceFileWrapper::StringId {
+                id: "logo".to_string(),
+                res_full_name: "logo.png".to_string(),
+                res_type: ResourceType::Image,
+            },
+            ResourceType::Image,
+        )
+        .unwrap();
+
+    let main = &mut new.screens[0];
+    main.toolbar_enabled = false;
+
+    // move button1 into linear2, change the text of textview1 and add a new layout with a view
+    let button1 = main.layout[0].children.remove(1);
+    main.layout[1].children.push(button1);
+    set_text(main, "textview1", "Hi");
+    main.layout.insert(
+        0,
+        linear("linear3", false, vec![text_view("textview2", "New")]),
+    );
+
+    main.variables.clear();
+    main.variables.insert(
+        "name".to_string(),
+        Variable {
+            name: "name".to_string(),
+            r#type: VariableType::String,
+        },
+    );
+
+    main.events[0].code = blocks(
+        r#"{"color":-7711273,"id":"10","nextBlock":12,"opCode":"setText","parameters":["textview1","Clicked!"],"spec":"%m.textview setText %s","subStack1":-1,"subStack2":-1,"type":" ","typeName":""}
+{"color":-1988310,"id":"12","nextBlock":-1,"opCode":"if","parameters":["@13"],"spec":"if %b then","subStack1":14,"subStack2":-1,"type":"c","typeName":""}
+{"color":-10701022,"id":"13","nextBlock":-1,"opCode":"true","parameters":[],"spec":"true","subStack1":-1,"subStack2":-1,"type":"b","typeName":""}
+{"color":-10701022,"id":"14","nextBlock":-1,"opCode":"doToast","parameters":["Hello"],"spec":"Toast %s","subStack1":-1,"subStack2":-1,"type":" ","typeName":""}"#,
+    );
+
+    main.more_blocks.insert(
+        "greet".to_string(),
+        MoreBlock {
+            name: "greet".to_string(),
+            spec: BlockContent::parse_wo_params("greet %s.name").unwrap(),
+            code: blocks(
+                r#"{"color":-10701022,"id":"10","nextBlock":-1,"opCode":"doToast","parameters":["Hi"],"spec":"Toast %s","subStack1":-1,"subStack2":-1,"type":" ","typeName":""}"#,
+            ),
+        },
+    );
+
+    new.screens.push(screen("about"));
+    new
+}
+
+#[test]
+fn round_trip() {
+    let old = project(vec![main_screen()]);
+    let new = changed(&old);
+
+    let patch = Patch::between(&old, &new);
+    let json = serde_json::to_string(&patch).unwrap();
+    let patch: Patch = serde_json::from_str(&json).unwrap();
+
+    let mut patched = old.clone();
+    patch.apply_patch(&mut patched).unwrap();
+
+    assert!(diff(&new, &patched).is_empty(), "{}", diff(&new, &patched));
+    assert_eq!(
+        patched.resources.get_images().keys().collect::<Vec<_>>(),
+        vec![&ResourceId("logo".to_string())]
+    );
+    assert!(Patch::between(&new, &patched).is_empty());
+}
+
+#[test]
+fn drawer_round_trip() {
+    let mut with_drawer = main_screen();
+    with_drawer.set_drawer_enabled(true);
+    with_drawer.drawer.as_mut().unwrap().layout = vec![linear(
+        "linear1",
+        true,
+        vec![text_view("textview1", "Home")],
+    )];
+
+    let old = project(vec![with_drawer]);
+    let mut new = old.clone();
+    let drawer = new.screens[0].drawer.as_mut().unwrap();
+    let textview1 = drawer.layout[0].children.remove(0);
+    drawer.layout.insert(0, textview1);
+    drawer.layout[1]
+        .children
+        .push(button("button1", "Settings"));
+    set_text(&mut new.screens[0], "_drawer_textview1", "Beranda");
+
+    let patch = Patch::between(&old, &new);
+    assert!(!patch.is_empty());
+
+    let mut patched = old.clone();
+    patch.apply_patch(&mut patched).unwrap();
+    assert!(diff(&new, &patched).is_empty(), "{}", diff(&new, &patched));
+    assert_eq!(patched.screens[0].layout, old.screens[0].layout);
+
+    // removing the drawer removes its views along with it
+    let mut removed = new.clone();
+    removed.screens[0].set_drawer_enabled(false);
+
+    let patch = Patch::between(&new, &removed);
+    let mut patched = new.clone();
+    patch.apply_patch(&mut patched).unwrap();
+    assert_eq!(patched.screens[0].drawer, None);
+    assert!(Patch::between(&removed, &patched).is_empty());
+
+    // and adding it back adds its views
+    Patch::between(&removed, &new)
+        .apply_patch(&mut patched)
+        .unwrap();
+    assert!(diff(&new, &patched).is_empty(), "{}", diff(&new, &patched));
+}
+
+#[test]
+fn mismatching_target() {
+    let old = project(vec![main_screen()]);
+    let new = changed(&old);
+    let patch = Patch::between(&old, &new);
+
+    // another project where textview1 has a different text
+    let mut other = old.clone();
+    set_text(&mut other.screens[0], "textview1", "Welcome");
+    let untouched = other.clone();
+
+    let error = patch.apply_patch(&mut other).unwrap_err();
+
+    assert_eq!(error.operation, "set-attribute");
+    assert!(matches!(
+        &patch.operations[error.index],
+        Operation::SetAttribute { id, attribute, .. } if id == "textview1" && attribute == "text.text"
+    ));
+    assert!(matches!(
+        error.source,
+        PatchOperationError::Mismatch { ref target, .. } if target == "textview1.text.text"
+    ));
+
+    // the project is left as it is
+    assert_eq!(other, untouched);
+
+    // a fab that changed since the patch was computed isn't replaced
+    let fab = |id: &str| {
+        View::new(
+            id,
+            ViewType::Fab {
+                image_res_name: "ic_add_white".to_string(),
+            },
+        )
+    };
+
+    let mut new = old.clone();
+    new.screens[0].fab = Some(fab("_fab"));
+    let patch = Patch::between(&old, &new);
+
+    let mut other = old.clone();
+    other.screens[0].fab = Some(fab("fab2"));
+    let untouched = other.clone();
+
+    let error = patch.apply_patch(&mut other).unwrap_err();
+
+    assert_eq!(error.operation, "set-fab");
+    assert!(matches!(
+        error.source,
+        PatchOperationError::Mismatch { ref target, .. } if target == "main.fab"
+    ));
+    assert_eq!(other, untouched);
+}
+
+#[test]
+fn handwritten_patch() {
+    let patch: Patch = serde_json::from_str(
+        r#"{"operations": [
+            {"op": "add-variable", "screen": "main", "name": "loaded", "type": "Boolean"},
+            {"op": "insert-block", "screen": "main", "container": "button1_onClick", "path": "1",
+             "block": [{"color":-10701022,"id":"10","nextBlock":-1,"opCode":"doToast","parameters":["Hi"],"spec":"Toast %s","subStack1":-1,"subStack2":-1,"type":" ","typeName":""}]},
+            {"op": "set-argument", "screen": "main", "container": "button1_onClick", "path": "0",
+             "argument": 1, "old": {"value": "Clicked"}, "new": {"value": "Pressed"}}
+        ]}"#,
+    )
+    .unwrap();
+
+    let mut project = project(vec![main_screen()]);
+    patch.apply_patch(&mut project).unwrap();
+
+    let main = &project.screens[0];
+    assert_eq!(main.variables["loaded"].r#type, VariableType::Boolean);
+    assert_eq!(
+        main.events[0].code,
+        blocks(
+            r#"{"color":-7711273,"id":"10","nextBlock":11,"opCode":"setText","parameters":["textview1","Pressed"],"spec":"%m.textview setText %s","subStack1":-1,"subStack2":-1,"type":" ","typeName":""}
+{"color":-10701022,"id":"11","nextBlock":12,"opCode":"doToast","parameters":["Hi"],"spec":"Toast %s","subStack1":-1,"subStack2":-1,"type":" ","typeName":""}
+{"color":-1147626,"id":"12","nextBlock":-1,"opCode":"increaseInt","parameters":["count"],"spec":"%m.varInt increase 1","subStack1":-1,"subStack2":-1,"type":" ","typeName":""}"#
+        )
+    );
+
+    // applying it twice fails since the variable already exists
+    let error = patch.apply_patch(&mut project).unwrap_err();
+    assert_eq!(error.index, 0);
+    assert!(matches!(
+        error.source,
+        PatchOperationError::AlreadyExists {
+            kind: "variable",
+            ..
+        }
+    ));
+}
@@ -0,0 +1,135 @@
+mod common;
+
+use common::{blocks, linear, project, screen};
+use swrs::api::component::ComponentKind;
+use swrs::api::permissions::{PermissionReport, Reason};
+use swrs::api::screen::{Event, EventType};
+use swrs::api::view::{View, ViewType};
+use swrs::api::CustomView;
+
+const WRITE_FILE: &str = r#"{"color":-11899692,"id":"10","nextBlock":-1,"opCode":"fileutilwrite","parameters":["/sdcard/notes.txt","hello"],"spec":"FileUtil writeFile path %s string %s","subStack1":-1,"subStack2":-1,"type":" ","typeName":""}"#;
+
+#[test]
+fn components_and_views() {
+    let mut main = screen("main");
+    main.layout = vec![linear(
+        "linear1",
+        true,
+        vec![View::new("webview1", ViewType::WebView)],
+    )];
+    main.components
+        .insert("camera".to_string(), ComponentKind::Camera);
+
+    let report = PermissionReport::infer(&project(vec![main]));
+    assert_eq!(
+        report.names(),
+        vec!["android.permission.CAMERA", "android.permission.INTERNET"]
+    );
+
+    assert_eq!(
+        report.get("android.permission.CAMERA").unwrap().reasons,
+        vec![Reason::Component {
+            screen: "main".to_string(),
+            id: "camera".to_string(),
+            kind: "Camera",
+        }]
+    );
+    assert_eq!(
+        report.get("android.permission.INTERNET").unwrap().reasons,
+        vec![Reason::View {
+            screen: "main".to_string(),
+            id: "webview1".to_string(),
+            view_type: "WebView",
+        }]
+    );
+}
+
+#[test]
+fn blocks_and_explanation() {
+    let mut main = screen("main");
+    main.events.push(Event {
+        name: "onCreate".to_string(),
+        event_type: EventType::ActivityEvent,
+        code: blocks(WRITE_FILE),
+    });
+
+    let report = PermissionReport::infer(&project(vec![main]));
+    assert_eq!(
+        report.names(),
+        vec![
+            "android.permission.READ_EXTERNAL_STORAGE",
+            "android.permission.WRITE_EXTERNAL_STORAGE"
+        ]
+    );
+
+    assert_eq!(
+        report.explain(),
+        "android.permission.READ_EXTERNAL_STORAGE\n\
+         \x20 - main, block `fileutilwrite` in onCreate_initializeLogic at 0\n\
+         android.permission.WRITE_EXTERNAL_STORAGE\n\
+         \x20 - main, block `fileutilwrite` in onCreate_initializeLogic at 0\n"
+    );
+}
+
+#[test]
+fn manifest_entries() {
+    let mut main = screen("main");
+    main.layout = vec![
+        View::new("webview1", ViewType::WebView),
+        View::new("webview2", ViewType::WebView),
+    ];
+    main.components
+        .insert("net".to_string(), ComponentKind::RequestNetwork);
+
+    let report = PermissionReport::infer(&project(vec![main]));
+    assert_eq!(
+        report.to_manifest(),
+        "<uses-permission android:name=\"android.permission.INTERNET\" />\n\
+         <uses-permission android:name=\"android.permission.ACCESS_NETWORK_STATE\" />\n"
+    );
+
+    // every cause is kept under a single entry
+    assert_eq!(
+        report
+            .get("android.permission.INTERNET")
+            .unwrap()
+            .reasons
+            .len(),
+        3
+    );
+    assert!(PermissionReport::infer(&project(vec![screen("empty")]))
+        .permissions
+        .is_empty());
+}
+
+#[test]
+fn custom_views() {
+    let mut project = project(vec![screen("main")]);
+    project.custom_views.push(CustomView {
+        res_name: "item".to_string(),
+        layout: vec![linear(
+            "linear1",
+            true,
+            vec![View::new(
+                "adview1",
+                ViewType::AdView {
+                    adview_size: "BANNER".to_string(),
+                },
+            )],
+        )],
+    });
+
+    let report = PermissionReport::infer(&project);
+    assert_eq!(
+        report.to_manifest(),
+        "<uses-permission android:name=\"android.permission.INTERNET\" />\n\
+         <uses-permission android:name=\"android.permission.ACCESS_NETWORK_STATE\" />\n"
+    );
+    assert_eq!(
+        report.explain(),
+        "android.permission.INTERNET\n\
+         \x20 - custom view item, AdView `adview1`\n\
+         android.permission.ACCESS_NETWORK_STATE\n\
+         \x20 - custom view item, AdView `adview1`\n"
+    );
+}
@@ -0,0 +1,180 @@
+mod common;
+
+use common::{blocks, screen, text_view};
+use swrs::api::block::{ArgValue, Argument, Block, BlockContent};
+use swrs::api::component::ComponentKind;
+use swrs::api::screen::{MoreBlock, Screen};
+use swrs::api::snippet::{DependencyKind, Renamed, Snippet};
+use swrs::parser::logic::list_variable::ListVariable;
+use swrs::parser::logic::variable::{Variable, VariableType};
+
+const CODE: &str = r#"{"color":-1147626,"id":"10","nextBlock":11,"opCode":"increaseInt","parameters":["count"],"spec":"%m.varInt increase 1","subStack1":-1,"subStack2":-1,"type":" ","typeName":""}
+{"color":-7711273,"id":"11","nextBlock":12,"opCode":"setText","parameters":["textview1","Hi"],"spec":"%m.textview setText %s","subStack1":-1,"subStack2":-1,"type":" ","typeName":""}
+{"color":-11899692,"id":"12","nextBlock":13,"opCode":"intentSetScreen","parameters":["intent","AboutActivity"],"spec":"%m.intent setScreen %m.activity","subStack1":-1,"subStack2":-1,"type":" ","typeName":""}
+{"color":-7711273,"id":"13","nextBlock":-1,"opCode":"definedFunc","parameters":[],"spec":"refresh","subStack1":-1,"subStack2":-1,"type":" ","typeName":""}"#;
+
+fn variable(name: &str, r#type: VariableType) -> (String, Variable) {
+    (
+        name.to_string(),
+        Variable {
+            name: name.to_string(),
+            r#type,
+        },
+    )
+}
+
+fn menu_value(block: &Block) -> String {
+    match block.content.get_args()[0] {
+        Argument::Menu {
+            value: ArgValue::Value(value),
+            ..
+        } => value.to_owned(),
+        _ => panic!("the first argument of {} isn't a menu", block.op_code),
+    }
+}
+
+fn source() -> Screen {
+    let mut main = screen("main");
+    main.layout = vec![text_view("textview1", "Hello")];
+
+    main.variables.extend([
+        variable("count", VariableType::Integer),
+        variable("unused", VariableType::Boolean),
+    ]);
+    main.list_variables.insert(
+        "names".to_string(),
+        ListVariable {
+            name: "names".to_string(),
+            r#type: VariableType::String,
+        },
+    );
+    main.components
+        .insert("intent".to_string(), ComponentKind::Intent);
+
+    // refresh calls clear, which clears the names list
+    main.more_blocks.insert(
+        "refresh".to_string(),
+        MoreBlock {
+            name: "refresh".to_string(),
+            spec: BlockContent::parse_wo_params("refresh").unwrap(),
+            code: blocks(
+                r#"{"color":-7711273,"id":"10","nextBlock":-1,"opCode":"definedFunc","parameters":[],"spec":"clear","subStack1":-1,"subStack2":-1,"type":" ","typeName":""}"#,
+            ),
+        },
+    );
+    main.more_blocks.insert(
+        "clear".to_string(),
+        MoreBlock {
+            name: "clear".to_string(),
+            spec: BlockContent::parse_wo_params("clear").unwrap(),
+            code: blocks(
+                r#"{"color":-3384542,"id":"10","nextBlock":-1,"opCode":"clearList","parameters":["names"],"spec":"clear %m.list","subStack1":-1,"subStack2":-1,"type":" ","typeName":""}"#,
+            ),
+        },
+    );
+
+    main
+}
+
+#[test]
+fn extracts_dependencies() {
+    let source = source();
+    let snippet = Snippet::extract(&source, &blocks(CODE));
+
+    assert_eq!(snippet.variables.keys().collect::<Vec<_>>(), vec!["count"]);
+    assert_eq!(
+        snippet.list_variables.keys().collect::<Vec<_>>(),
+        vec!["names"]
+    );
+    assert_eq!(
+        snippet.components.keys().collect::<Vec<_>>(),
+        vec!["intent"]
+    );
+    assert_eq!(
+        snippet.more_blocks.keys().collect::<Vec<_>>(),
+        vec!["refresh", "clear"]
+    );
+    assert_eq!(snippet.views, vec!["textview1"]);
+}
+
+#[test]
+fn imports_into_empty_screen() {
+    let snippet = Snippet::extract(&source(), &blocks(CODE));
+
+    let mut target = screen("other");
+    let result = snippet.import(&mut target);
+
+    assert!(result.renamed.is_empty());
+    assert_eq!(result.missing_views, vec!["textview1"]);
+    assert_eq!(result.blocks, blocks(CODE));
+
+    assert_eq!(target.variables["count"].r#type, VariableType::Integer);
+    assert_eq!(target.list_variables["names"].r#type, VariableType::String);
+    assert_eq!(target.components["intent"], ComponentKind::Intent);
+    assert_eq!(target.more_blocks.len(), 2);
+}
+
+#[test]
+fn renames_on_collision() {
+    let snippet = Snippet::extract(&source(), &blocks(CODE));
+
+    let mut target = screen("other");
+    target.layout = vec![text_view("textview1", "Hello")];
+
+    // count has a different type, intent is the same component, and the list's name is taken by
+    // a view
+    target
+        .variables
+        .extend([variable("count", VariableType::String)]);
+    target
+        .components
+        .insert("intent".to_string(), ComponentKind::Intent);
+    target.layout.push(text_view("names", ""));
+    target.more_blocks.insert(
+        "clear".to_string(),
+        MoreBlock {
+            name: "clear".to_string(),
+            spec: BlockContent::parse_wo_params("clear").unwrap(),
+            code: Default::default(),
+        },
+    );
+
+    let result = snippet.import(&mut target);
+
+    assert_eq!(
+        result.renamed,
+        vec![
+            Renamed {
+                kind: DependencyKind::Variable,
+                from: "count".to_string(),
+                to: "count2".to_string(),
+            },
+            Renamed {
+                kind: DependencyKind::ListVariable,
+                from: "names".to_string(),
+                to: "names2".to_string(),
+            },
+            Renamed {
+                kind: DependencyKind::MoreBlock,
+                from: "clear".to_string(),
+                to: "clear2".to_string(),
+            },
+        ]
+    );
+    assert!(result.missing_views.is_empty());
+
+    assert_eq!(target.variables["count"].r#type, VariableType::String);
+    assert_eq!(target.variables["count2"].r#type, VariableType::Integer);
+    assert_eq!(target.components.len(), 1);
+
+    // references are renamed in the blocks and in the copied moreblocks
+    assert_eq!(menu_value(&result.blocks.0[0]), "count2");
+    assert_eq!(
+        target.more_blocks["refresh"].code.0[0].content.to_string(),
+        "clear2"
+    );
+
+    let clear2 = &target.more_blocks["clear2"];
+    assert_eq!(clear2.spec.to_string(), "clear2");
+    assert_eq!(menu_value(&clear2.code.0[0]), "names2");
+}
@@ -0,0 +1,188 @@
+mod common;
+
+use common::{button, linear, on_click, screen, text_view};
+use swrs::api::block::{ArgValue, Argument, BlockPath};
+use swrs::api::screen::{Drawer, Screen};
+use swrs::api::tree::{
+    clone_view, insert_view, move_view, position, remove_view, reorder_view, TreeError,
+    ViewPosition,
+};
+use swrs::api::view::{View, ViewType};
+use swrs::parser::view::models::layout::Orientation;
+
+fn ids(views: &[View]) -> Vec<String> {
+    views.iter().map(|view| view.id.to_owned()).collect()
+}
+
+fn layout() -> Vec<View> {
+    vec![linear(
+        "linear1",
+        true,
+        vec![
+            text_view("textview1", "Title"),
+            linear("linear2", false, vec![button("button1", "Ok")]),
+            button("button2", "Cancel"),
+        ],
+    )]
+}
+
+#[test]
+fn move_and_reorder() {
+    let mut layout = layout();
+    assert_eq!(
+        position(&layout, "button1"),
+        Some(ViewPosition {
+            parent: Some("linear2".to_string()),
+            index: 0
+        })
+    );
+
+    move_view(&mut layout, "button2", Some("linear2"), 0).unwrap();
+    assert_eq!(
+        ids(&layout[0].children[1].children),
+        vec!["button2", "button1"]
+    );
+    assert_eq!(ids(&layout[0].children), vec!["textview1", "linear2"]);
+
+    reorder_view(&mut layout, "textview1", 1).unwrap();
+    assert_eq!(ids(&layout[0].children), vec!["linear2", "textview1"]);
+
+    move_view(&mut layout, "linear2", None, 0).unwrap();
+    assert_eq!(ids(&layout), vec!["linear2", "linear1"]);
+
+    // failures leave the layout as is
+    let before = layout.clone();
+    assert_eq!(
+        move_view(&mut layout, "button1", Some("textview1"), 0),
+        Err(TreeError::NotAContainer {
+            id: "textview1".to_string(),
+            view_type: "TextView".to_string()
+        })
+    );
+    assert_eq!(
+        move_view(&mut layout, "linear2", Some("button2"), 0),
+        Err(TreeError::IntoItself {
+            id: "linear2".to_string()
+        })
+    );
+    assert_eq!(
+        reorder_view(&mut layout, "button1", 2),
+        Err(TreeError::IndexOutOfBounds { index: 2, len: 1 })
+    );
+    assert_eq!(layout, before);
+}
+
+#[test]
+fn insert_and_clone() {
+    let mut layout = layout();
+    let scroll = View::new(
+        "vscroll1",
+        ViewType::ScrollView {
+            orientation: Orientation::Vertical,
+            gravity: Default::default(),
+        },
+    );
+
+    insert_view(&mut layout, Some("linear1"), 3, scroll).unwrap();
+    insert_view(
+        &mut layout,
+        Some("vscroll1"),
+        0,
+        text_view("textview2", "Hi"),
+    )
+    .unwrap();
+    assert_eq!(layout[0].children[3].children[0].id, "textview2");
+
+    assert_eq!(
+        insert_view(&mut layout, None, 0, button("button1", "Again")),
+        Err(TreeError::DuplicateId {
+            id: "button1".to_string()
+        })
+    );
+    assert_eq!(
+        insert_view(&mut layout, Some("button2"), 0, button("button3", "")),
+        Err(TreeError::NotAContainer {
+            id: "button2".to_string(),
+            view_type: "Button".to_string()
+        })
+    );
+
+    let original = layout[0].children[1].clone();
+    let copy = clone_view(&original, &mut |view: &View| format!("{}_b", view.id));
+    assert_eq!(copy.id, "linear2_b");
+    assert_eq!(copy.children[0].id, "button1_b");
+    assert_eq!(copy.children[0].view, original.children[0].view);
+
+    let removed = remove_view(&mut layout, "linear2").unwrap();
+    assert_eq!(removed, original);
+    assert!(position(&layout, "button1").is_none());
+    assert!(remove_view(&mut layout, "linear2").is_none());
+}
+
+fn set_enabled(view: &str) -> String {
+    format!(
+        r#"{{"color":-1988310,"id":"10","nextBlock":-1,"opCode":"setEnable","parameters":["{}","true"],"spec":"%m.view setEnable %b","subStack1":-1,"subStack2":-1,"type":" ","typeName":""}}"#,
+        view
+    )
+}
+
+fn first_menu(screen: &Screen, event: usize) -> String {
+    let block = screen.events[event]
+        .code
+        .get(&"0".parse::<BlockPath>().unwrap())
+        .unwrap();
+
+    match block.content.get_args()[0] {
+        Argument::Menu {
+            value: ArgValue::Value(value),
+            ..
+        } => value.to_owned(),
+        other => panic!("unexpected argument {:?}", other),
+    }
+}
+
+#[test]
+fn screen_delete_and_duplicate() {
+    let mut main = screen("main");
+    main.layout = layout();
+    main.drawer = Some(Drawer {
+        layout: vec![button("button1", "Home")],
+    });
+    main.events.extend([
+        on_click("button2", &set_enabled("_drawer_button1")),
+        on_click("button1", &set_enabled("button2")),
+        on_click("_drawer_button1", &set_enabled("button1")),
+    ]);
+
+    let deleted = main.delete_view("linear2").unwrap();
+    assert_eq!(deleted.children[0].id, "button1");
+    assert_eq!(main.events.len(), 2);
+    // the drawer has its own ids
+    assert_eq!(first_menu(&main, 0), "_drawer_button1");
+    assert_eq!(first_menu(&main, 1), "");
+
+    main.delete_view("_drawer_button1").unwrap();
+    assert_eq!(main.events.len(), 1);
+    assert_eq!(first_menu(&main, 0), "");
+    assert!(main.drawer.as_ref().unwrap().layout.is_empty());
+    assert_eq!(
+        main.delete_view("linear2"),
+        Err(TreeError::ViewNotFound {
+            id: "linear2".to_string()
+        })
+    );
+
+    main.layout[0].children.push(linear(
+        "linear3",
+        true,
+        vec![text_view("textview1_copy", "")],
+    ));
+    let copy = main.duplicate_view("linear3").unwrap();
+    assert_eq!(copy, "linear2");
+    assert_eq!(
+        ids(&main.layout[0].children),
+        vec!["textview1", "button2", "linear3", "linear2"]
+    );
+    assert_eq!(main.layout[0].children[3].children[0].id, "textview2");
+    assert_eq!(main.duplicate_view("linear3").unwrap(), "linear4");
+}
@@ -0,0 +1,129 @@
+mod common;
+
+use common::{blocks, button, linear, screen, text_view};
+use swrs::api::block::{ArgumentBlockReturnType, BlockType};
+use swrs::api::component::ComponentKind;
+use swrs::api::screen::{Event, EventType};
+use swrs::api::typecheck::{check_screen, ArgumentKind, TypeContext, TypeErrorKind};
+use swrs::parser::logic::variable::{Variable, VariableType};
+
+fn main_screen() -> swrs::api::screen::Screen {
+    let mut main = screen("main");
+    main.layout = vec![linear(
+        "linear1",
+        true,
+        vec![text_view("textview1", "Hello"), button("button1", "Click")],
+    )];
+
+    main.variables.insert(
+        "count".to_string(),
+        Variable {
+            name: "count".to_string(),
+            r#type: VariableType::Integer,
+        },
+    );
+
+    main.components
+        .insert("intent".to_string(), ComponentKind::Intent);
+
+    main
+}
+
+#[test]
+fn well_typed_blocks() {
+    let code = blocks(
+        r#"{"color":-7711273,"id":"10","nextBlock":11,"opCode":"setText","parameters":["textview1","@12"],"spec":"%m.textview setText %s","subStack1":-1,"subStack2":-1,"type":" ","typeName":""}
+{"color":-1147626,"id":"12","nextBlock":-1,"opCode":"toString","parameters":["@13"],"spec":"toString %d without decimal","subStack1":-1,"subStack2":-1,"type":"s","typeName":""}
+{"color":-1147626,"id":"13","nextBlock":-1,"opCode":"getVar","parameters":[],"spec":"count","subStack1":-1,"subStack2":-1,"type":"d","typeName":""}
+{"color":-11899692,"id":"11","nextBlock":-1,"opCode":"intentSetScreen","parameters":["intent","SecondActivity"],"spec":"%m.intent setScreen %m.activity","subStack1":-1,"subStack2":-1,"type":" ","typeName":""}"#,
+    );
+
+    let context = TypeContext::from_screen(&main_screen());
+    assert_eq!(context.check(&code), vec![]);
+}
+
+#[test]
+fn mismatched_blocks() {
+    let code = blocks(
+        r#"{"color":-1147626,"id":"10","nextBlock":-1,"opCode":"setVarInt","parameters":["count","@11"],"spec":"set %m.varInt to %d","subStack1":-1,"subStack2":-1,"type":" ","typeName":""}
+{"color":-10701022,"id":"11","nextBlock":-1,"opCode":"getText","parameters":["textview1"],"spec":"%m.textview getText","subStack1":-1,"subStack2":-1,"type":"s","typeName":""}"#,
+    );
+
+    let errors = TypeContext::from_screen(&main_screen()).check(&code);
+
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].op_code, "setVarInt");
+    assert_eq!(errors[0].argument, 1);
+    assert_eq!(errors[0].path.to_string(), "0");
+    assert_eq!(
+        errors[0].kind,
+        TypeErrorKind::MismatchedBlock {
+            expected: ArgumentKind::Number,
+            found: BlockType::Argument(ArgumentBlockReturnType::String),
+        }
+    );
+}
+
+#[test]
+fn mismatched_menu_values() {
+    let code = blocks(
+        r#"{"color":-7711273,"id":"10","nextBlock":11,"opCode":"setText","parameters":["button1","hi"],"spec":"%m.textview setText %s","subStack1":-1,"subStack2":-1,"type":" ","typeName":""}
+{"color":-7711273,"id":"11","nextBlock":12,"opCode":"setText","parameters":["textview9","hi"],"spec":"%m.textview setText %s","subStack1":-1,"subStack2":-1,"type":" ","typeName":""}
+{"color":-1147626,"id":"12","nextBlock":13,"opCode":"setVarInt","parameters":["counter","1"],"spec":"set %m.varInt to %d","subStack1":-1,"subStack2":-1,"type":" ","typeName":""}
+{"color":-11899692,"id":"13","nextBlock":-1,"opCode":"startActivity","parameters":["dialog"],"spec":"StartActivity %m.intent","subStack1":-1,"subStack2":-1,"type":" ","typeName":""}"#,
+    );
+
+    let kinds = TypeContext::from_screen(&main_screen())
+        .check(&code)
+        .into_iter()
+        .map(|error| error.kind)
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        kinds,
+        vec![
+            TypeErrorKind::MismatchedView {
+                id: "button1".to_string(),
+                menu: "textview".to_string(),
+                found: "Button",
+            },
+            TypeErrorKind::UnknownView {
+                id: "textview9".to_string()
+            },
+            TypeErrorKind::UnknownVariable {
+                name: "counter".to_string()
+            },
+            TypeErrorKind::UnknownComponent {
+                id: "dialog".to_string()
+            },
+        ]
+    );
+}
+
+#[test]
+fn screen_errors_are_located() {
+    let mut main = main_screen();
+    main.events.push(Event {
+        name: "onClick".to_string(),
+        event_type: EventType::ViewEvent {
+            id: "button1".to_string(),
+        },
+        code: blocks(
+            r#"{"color":-7711273,"id":"10","nextBlock":-1,"opCode":"setText","parameters":["linear1","hi"],"spec":"%m.textview setText %s","subStack1":-1,"subStack2":-1,"type":" ","typeName":""}"#,
+        ),
+    });
+
+    let errors = check_screen(&main, &[]);
+
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].screen, "main");
+    assert_eq!(errors[0].container, "button1_onClick");
+    assert_eq!(
+        errors[0].error.kind,
+        TypeErrorKind::MismatchedView {
+            id: "linear1".to_string(),
+            menu: "textview".to_string(),
+            found: "LinearLayout",
+        }
+    );
+}
@@ -0,0 +1,201 @@
+mod common;
+
+use common::{blocks, button, event, project, screen, text_view};
+use swrs::api::block::BlockContent;
+use swrs::api::component::ComponentKind;
+use swrs::api::screen::{EventType, MoreBlock, Screen};
+use swrs::api::unused::{find_orphan_block_containers, find_unused, prune, Unused};
+use swrs::parser::logic::event::{Event as ParserEvent, EventPool};
+use swrs::parser::logic::list_variable::ListVariable;
+use swrs::parser::logic::more_block::{MoreBlock as ParserMoreBlock, MoreBlockPool};
+use swrs::parser::logic::variable::{Variable, VariableType};
+use swrs::parser::logic::{BlockContainer, ScreenLogic};
+use swrs::LinkedHashMap;
+
+fn variable(name: &str) -> (String, Variable) {
+    (
+        name.to_string(),
+        Variable {
+            name: name.to_string(),
+            r#type: VariableType::Integer,
+        },
+    )
+}
+
+fn more_block(name: &str, code: &str) -> (String, MoreBlock) {
+    (
+        name.to_string(),
+        MoreBlock {
+            name: name.to_string(),
+            spec: BlockContent::parse_wo_params(name).unwrap(),
+            code: blocks(code),
+        },
+    )
+}
+
+fn main_screen() -> Screen {
+    let mut main = screen("main");
+    main.layout = vec![text_view("textview1", "Hello"), button("button1", "Click")];
+
+    main.variables.extend([
+        variable("count"),
+        variable("unused"),
+        variable("stale"),
+        variable("ghost"),
+    ]);
+    main.list_variables.insert(
+        "names".to_string(),
+        ListVariable {
+            name: "names".to_string(),
+            r#type: VariableType::String,
+        },
+    );
+    main.components.extend([
+        ("intent".to_string(), ComponentKind::Intent),
+        ("timer".to_string(), ComponentKind::Timer),
+        ("dialog".to_string(), ComponentKind::Dialog),
+    ]);
+
+    // onCreate calls refresh, which calls clear, old is never called and uses stale
+    main.more_blocks.extend([
+        more_block(
+            "refresh",
+            r#"{"color":-7711273,"id":"10","nextBlock":-1,"opCode":"definedFunc","parameters":[],"spec":"clear","subStack1":-1,"subStack2":-1,"type":" ","typeName":""}"#,
+        ),
+        more_block(
+            "clear",
+            r#"{"color":-3384542,"id":"10","nextBlock":-1,"opCode":"clearList","parameters":["names"],"spec":"clear %m.list","subStack1":-1,"subStack2":-1,"type":" ","typeName":""}"#,
+        ),
+        more_block(
+            "old",
+            r#"{"color":-1147626,"id":"10","nextBlock":-1,"opCode":"increaseInt","parameters":["stale"],"spec":"%m.varInt increase 1","subStack1":-1,"subStack2":-1,"type":" ","typeName":""}"#,
+        ),
+    ]);
+
+    main.events.extend([
+        event(
+            "onCreate",
+            EventType::ActivityEvent,
+            r#"{"color":-7711273,"id":"10","nextBlock":-1,"opCode":"definedFunc","parameters":[],"spec":"refresh","subStack1":-1,"subStack2":-1,"type":" ","typeName":""}"#,
+        ),
+        event(
+            "onClick",
+            EventType::ViewEvent {
+                id: "button1".to_string(),
+            },
+            r#"{"color":-1147626,"id":"10","nextBlock":11,"opCode":"increaseInt","parameters":["count"],"spec":"%m.varInt increase 1","subStack1":-1,"subStack2":-1,"type":" ","typeName":""}
+{"color":-11899692,"id":"11","nextBlock":-1,"opCode":"startActivity","parameters":["intent"],"spec":"StartActivity %m.intent","subStack1":-1,"subStack2":-1,"type":" ","typeName":""}"#,
+        ),
+        // timer is only used by its own event
+        event(
+            "onTimerTask",
+            EventType::ComponentEvent {
+                id: "timer".to_string(),
+                component_type: 5,
+            },
+            "",
+        ),
+        // button2 got removed from the layout, so ghost is only used by dead code
+        event(
+            "onClick",
+            EventType::ViewEvent {
+                id: "button2".to_string(),
+            },
+            r#"{"color":-1147626,"id":"10","nextBlock":-1,"opCode":"increaseInt","parameters":["ghost"],"spec":"%m.varInt increase 1","subStack1":-1,"subStack2":-1,"type":" ","typeName":""}"#,
+        ),
+    ]);
+
+    main
+}
+
+fn unused(project: &swrs::api::SketchwareProject) -> Vec<String> {
+    find_unused(project)
+        .iter()
+        .map(ToString::to_string)
+        .collect()
+}
+
+#[test]
+fn finds_unused() {
+    let project = project(vec![main_screen()]);
+
+    assert_eq!(
+        unused(&project),
+        vec![
+            "main: variable `unused` is never used",
+            "main: variable `stale` is never used",
+            "main: variable `ghost` is never used",
+            "main: component `dialog` is never used",
+            "main: moreblock `old` is never called",
+            "main: event `button2_onClick` belongs to something that doesn't exist",
+        ]
+    );
+}
+
+#[test]
+fn prunes_unused() {
+    let mut project = project(vec![main_screen()]);
+    let removed = prune(&mut project);
+
+    assert_eq!(removed.len(), 6);
+    assert!(find_unused(&project).is_empty());
+
+    let main = &project.screens[0];
+    assert_eq!(main.variables.keys().collect::<Vec<_>>(), vec!["count"]);
+    assert_eq!(
+        main.components.keys().collect::<Vec<_>>(),
+        vec!["intent", "timer"]
+    );
+    assert_eq!(
+        main.more_blocks.keys().collect::<Vec<_>>(),
+        vec!["refresh", "clear"]
+    );
+    assert_eq!(main.events.len(), 3);
+}
+
+#[test]
+fn finds_orphan_block_containers() {
+    let mut logic = ScreenLogic::new_empty("MainActivity.java".to_string());
+    logic.events = Some(EventPool(vec![ParserEvent {
+        event_name: "onClick".to_string(),
+        event_type: 1,
+        target_id: "button1".to_string(),
+        target_type: 1,
+    }]));
+
+    let mut more_blocks = LinkedHashMap::new();
+    more_blocks.insert(
+        "refresh".to_string(),
+        ParserMoreBlock {
+            id: "refresh".to_string(),
+            spec: "refresh".to_string(),
+        },
+    );
+    logic.more_blocks = Some(MoreBlockPool(more_blocks));
+
+    for container in [
+        "onCreate_initializeLogic",
+        "button1_onClick",
+        "button2_onClick",
+        "refresh_moreBlock",
+        "old_moreBlock",
+    ] {
+        logic
+            .block_containers
+            .insert(container.to_string(), BlockContainer(vec![]));
+    }
+
+    assert_eq!(
+        find_orphan_block_containers(&logic),
+        vec![
+            Unused::OrphanBlockContainer {
+                screen: "MainActivity.java".to_string(),
+                container: "button2_onClick".to_string(),
+            },
+            Unused::OrphanBlockContainer {
+                screen: "MainActivity.java".to_string(),
+                container: "old_moreBlock".to_string(),
+            },
+        ]
+    );
+}
@@ -0,0 +1,37 @@
+use swrs::api::view::ViewType;
+use swrs::parser::view::models::layout::Orientation;
+use swrs::parser::view::models::AndroidView;
+
+#[test]
+fn type_id_round_trip() {
+    // every stock view type, 1 isn't used by sketchware
+    for type_id in (0..=18).filter(|&id| id != 1) {
+        let mut raw = AndroidView::new_empty("view1", type_id, "root", 0);
+        raw.image.res_name = Some("icon".to_string());
+        let view_type = ViewType::from_view(&raw).unwrap();
+
+        assert_eq!(view_type.get_type_id(), type_id, "{:?}", view_type);
+
+        let mut rebuilt = AndroidView::new_empty("view1", view_type.get_type_id(), "root", 0);
+        view_type.clone().apply_values_to_view(&mut rebuilt);
+        assert_eq!(ViewType::from_view(&rebuilt).unwrap(), view_type);
+    }
+
+    let scroll =
+        |id| ViewType::from_view(&AndroidView::new_empty("scroll1", id, "root", 0)).unwrap();
+
+    assert!(matches!(
+        scroll(2),
+        ViewType::ScrollView {
+            orientation: Orientation::Horizontal,
+            ..
+        }
+    ));
+    assert!(matches!(
+        scroll(12),
+        ViewType::ScrollView {
+            orientation: Orientation::Vertical,
+            ..
+        }
+    ));
+}
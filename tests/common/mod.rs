@@ -0,0 +1,117 @@
+// Helpers shared across the api integration tests to build projects without needing real
+// sketchware project files
+#![allow(dead_code)]
+
+use swrs::api::block::Blocks;
+use swrs::api::lifecycle::java_name;
+use swrs::api::screen::{Event, EventType, Screen};
+use swrs::api::view::{View, ViewType};
+use swrs::api::{Colors, Libraries, Metadata, Resources, SketchwareProject};
+use swrs::color::Color;
+use swrs::parser::logic::BlockContainer;
+use swrs::parser::view::models::layout::gravity::Gravity;
+use swrs::parser::view::models::layout::Orientation;
+use swrs::parser::view::models::text::TextType;
+use swrs::parser::Parsable;
+
+/// Creates a linear layout with the given children
+pub fn linear(id: &str, vertical: bool, children: Vec<View>) -> View {
+    let mut view = View::new(
+        id,
+        ViewType::LinearLayout {
+            orientation: if vertical {
+                Orientation::Vertical
+            } else {
+                Orientation::Horizontal
+            },
+            gravity: Gravity::default(),
+        },
+    );
+
+    view.children = children;
+    view
+}
+
+pub fn text_view(id: &str, text: &str) -> View {
+    View::new(
+        id,
+        ViewType::TextView {
+            text: text.to_string(),
+            text_color: Color::from_rgb(0, 0, 0),
+            text_size: 14,
+            single_line: false,
+            text_font: "default_font".to_string(),
+            text_style: TextType::Normal,
+            lines: 0,
+        },
+    )
+}
+
+pub fn button(id: &str, text: &str) -> View {
+    View::new(
+        id,
+        ViewType::Button {
+            text: text.to_string(),
+            text_color: Color::from_rgb(0, 0, 0),
+            text_size: 14,
+            text_style: TextType::Normal,
+        },
+    )
+}
+
+/// Parses raw block container lines into [`Blocks`]
+pub fn blocks(raw: &str) -> Blocks {
+    Blocks::try_from(BlockContainer::parse(raw).expect("invalid block container"))
+        .expect("invalid blocks")
+}
+
+/// Creates an event with the given code as raw block container lines
+pub fn event(name: &str, event_type: EventType, code: &str) -> Event {
+    Event {
+        name: name.to_string(),
+        event_type,
+        code: blocks(code),
+    }
+}
+
+/// Creates an `onClick` event of a view
+pub fn on_click(id: &str, code: &str) -> Event {
+    event("onClick", EventType::ViewEvent { id: id.to_string() }, code)
+}
+
+/// Creates an empty screen with the given layout name
+pub fn screen(layout_name: &str) -> Screen {
+    Screen::new_empty(layout_name.to_string(), java_name(layout_name))
+}
+
+/// Creates a project with the given screens and default everything else
+pub fn project(screens: Vec<Screen>) -> SketchwareProject {
+    SketchwareProject::new(
+        Metadata {
+            local_id: 601,
+            name: "Test App".to_string(),
+            workspace_name: "TestApp".to_string(),
+            package_name: "com.my.testapp".to_string(),
+            time_created: 1640995200,
+            sketchware_version: 150,
+            version_name: "1.0".to_string(),
+            version_code: 1,
+        },
+        Colors {
+            color_primary: Color::from(0xff008dcd),
+            color_primary_dark: Color::from(0xff0084c2),
+            color_accent: Color::from(0xff008dcd),
+            color_control_normal: Color::from(0xff57beee),
+            color_control_highlight: Color::from(0x20008dcd),
+        },
+        screens,
+        vec![],
+        Libraries {
+            app_compat_enabled: true,
+            firebase: None,
+            ad_mob: None,
+            google_map: None,
+        },
+        Resources::new_empty(),
+    )
+}
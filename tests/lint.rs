@@ -0,0 +1,165 @@
+mod common;
+
+use common::{button, linear, on_click, project, screen, text_view};
+use swrs::api::component::ComponentKind;
+use swrs::api::screen::Screen;
+use swrs::api::view::{View, ViewType};
+use swrs::api::SketchwareProject;
+use swrs::lint::{lint, Finding, LintConfig, LintRule, Linter, Location, Severity};
+use swrs::parser::view::models::image::ImageScaleType;
+
+fn add_source(code: &str) -> String {
+    format!(
+        r#"{{"color":-10701022,"id":"10","nextBlock":-1,"opCode":"addSourceDirectly","parameters":[{}],"spec":"add source directly %s.inputOnly","subStack1":-1,"subStack2":-1,"type":" ","typeName":""}}"#,
+        serde_json::to_string(code).unwrap()
+    )
+}
+
+fn main_screen() -> Screen {
+    let mut main = screen("main");
+    main.layout = vec![linear(
+        "linear1",
+        true,
+        vec![
+            text_view("textview1", "Hello"),
+            button("textview1", "Click"),
+            View::new(
+                "listview1",
+                ViewType::ListView {
+                    divider_height: 1,
+                    custom_view: "item".to_string(),
+                },
+            ),
+            View::new(
+                "imageview1",
+                ViewType::ImageView {
+                    image_res_name: "logo".to_string(),
+                    image_scale_type: ImageScaleType::Center,
+                },
+            ),
+            View::new(
+                "imageview2",
+                ViewType::ImageView {
+                    image_res_name: "default_image".to_string(),
+                    image_scale_type: ImageScaleType::Center,
+                },
+            ),
+        ],
+    )];
+
+    main.components.extend([
+        ("intent".to_string(), ComponentKind::Intent),
+        ("auth".to_string(), ComponentKind::FirebaseAuth),
+    ]);
+
+    main.events.extend([
+        on_click("imageview1", ""),
+        // braces inside of strings and comments don't count
+        on_click(
+            "textview1",
+            &add_source("if (a) { b(\"}\"); } // {\n/* } */ char c = '{';"),
+        ),
+        on_click("imageview2", &add_source("if (a) {")),
+    ]);
+
+    main
+}
+
+fn messages(project: &SketchwareProject, linter: &Linter) -> Vec<String> {
+    linter
+        .run(project)
+        .iter()
+        .map(ToString::to_string)
+        .collect()
+}
+
+#[test]
+fn builtin_rules() {
+    let project = project(vec![main_screen()]);
+
+    assert_eq!(
+        lint(&project)
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>(),
+        vec![
+            "error[duplicate-view-id] main, view `textview1`: the id `textview1` is used by more than one view",
+            "warning[empty-event] main, imageview1_onClick: the event `onClick` has no blocks",
+            "warning[unbalanced-braces] main, imageview2_onClick at 0: 1 `{` is never closed",
+            "error[missing-custom-view] main, view `listview1`: the custom view `item` doesn't exist",
+            "error[missing-image] main, view `imageview1`: the image `logo` doesn't exist",
+            "error[missing-library] main, component `auth`: the FirebaseAuth component `auth` needs Firebase to be enabled",
+        ]
+    );
+}
+
+#[test]
+fn configured_severities() {
+    let project = project(vec![main_screen()]);
+
+    let config: LintConfig = serde_json::from_str(
+        r#"{"disabled": ["duplicate-view-id", "missing-image", "missing-library"],
+            "severities": {"empty-event": "info"}}"#,
+    )
+    .unwrap();
+    let linter = Linter::new(config.severity("unbalanced-braces", Severity::Error));
+
+    assert_eq!(
+        messages(&project, &linter),
+        vec![
+            "info[empty-event] main, imageview1_onClick: the event `onClick` has no blocks",
+            "error[unbalanced-braces] main, imageview2_onClick at 0: 1 `{` is never closed",
+            "error[missing-custom-view] main, view `listview1`: the custom view `item` doesn't exist",
+        ]
+    );
+}
+
+/// Reports screens that don't have any view
+struct EmptyLayout;
+
+impl LintRule for EmptyLayout {
+    fn name(&self) -> &'static str {
+        "empty-layout"
+    }
+
+    fn description(&self) -> &'static str {
+        "screens should have at least one view"
+    }
+
+    fn default_severity(&self) -> Severity {
+        Severity::Info
+    }
+
+    fn check(&self, project: &SketchwareProject) -> Vec<Finding> {
+        project
+            .screens
+            .iter()
+            .filter(|screen| screen.layout.is_empty())
+            .map(|screen| {
+                Finding::new(
+                    Location::Screen {
+                        screen: screen.layout_name.to_owned(),
+                    },
+                    "this screen is empty",
+                )
+            })
+            .collect()
+    }
+}
+
+#[test]
+fn custom_rule() {
+    let project = project(vec![main_screen(), screen("about")]);
+
+    let mut linter = Linter::empty(LintConfig::default());
+    linter.add_rule(EmptyLayout);
+
+    assert_eq!(
+        linter.rules().map(|rule| rule.name()).collect::<Vec<_>>(),
+        vec!["empty-layout"]
+    );
+    assert_eq!(
+        messages(&project, &linter),
+        vec!["info[empty-layout] about: this screen is empty"]
+    );
+}
@@ -0,0 +1,63 @@
+mod common;
+
+use common::blocks;
+use swrs::api::screen::{Event, EventType};
+use swrs::render::block::{render_blocks, render_event, BlockRenderOptions};
+
+const LOGIC: &str = r#"{"color":-1988310,"id":"14","nextBlock":15,"opCode":"ifElse","parameters":["@19"],"spec":"if %b then","subStack1":40,"subStack2":-1,"type":"e","typeName":""}
+{"color":-10701022,"id":"19","nextBlock":-1,"opCode":">","parameters":["@20","0"],"spec":"%d > %d","subStack1":-1,"subStack2":-1,"type":"b","typeName":""}
+{"color":-3384542,"id":"20","nextBlock":-1,"opCode":"lengthList","parameters":["webviews"],"spec":"length of %m.list","subStack1":-1,"subStack2":-1,"type":"d","typeName":""}
+{"color":-11899692,"id":"40","nextBlock":-1,"opCode":"setVisible","parameters":["linear_notab","GONE"],"spec":"%m.view setVisible %m.visible","subStack1":-1,"subStack2":-1,"type":" ","typeName":""}
+{"color":-10701022,"id":"15","nextBlock":-1,"opCode":"addSourceDirectly","parameters":["((WebView)webviews.get((int)currentWV).get(\"webview\")).reload();"],"spec":"add source directly %s.inputOnly","subStack1":-1,"subStack2":-1,"type":" ","typeName":""}"#;
+
+#[test]
+fn render_block_shapes() {
+    let svg = render_blocks(&blocks(LOGIC), &BlockRenderOptions::default());
+
+    assert!(svg.starts_with("<svg"));
+    assert!(svg.trim_end().ends_with("</svg>"));
+
+    // every block is drawn, including the nested argument blocks
+    for op_code in [
+        "ifElse",
+        "&gt;",
+        "lengthList",
+        "setVisible",
+        "addSourceDirectly",
+    ] {
+        assert!(
+            svg.contains(&format!(r#"data-opcode="{}""#, op_code)),
+            "block {} is not rendered",
+            op_code
+        );
+    }
+
+    assert!(svg.contains(">else</text>"));
+    assert!(svg.contains(">webviews</text>"));
+    assert!(svg.contains(">GONE</text>"));
+
+    // long arguments are cut
+    assert!(svg.contains("…</text>"));
+
+    // block colors
+    assert!(svg.contains(r##"fill="#e1a92a""##));
+    assert!(svg.contains(r##"fill="#4a6cd4""##));
+}
+
+#[test]
+fn render_event_hat() {
+    let event = Event {
+        name: "onClick".to_string(),
+        event_type: EventType::ViewEvent {
+            id: "button1".to_string(),
+        },
+        code: blocks(LOGIC),
+    };
+
+    let svg = render_event(&event, &BlockRenderOptions::default());
+
+    assert!(svg.contains(r#"class="hat""#));
+    assert!(svg.contains(">button1</text>"));
+    assert!(svg.contains(">onClick</text>"));
+    assert!(svg.contains(r#"data-opcode="setVisible""#));
+}
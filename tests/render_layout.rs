@@ -0,0 +1,99 @@
+mod common;
+
+use common::{button, linear, screen, text_view};
+use swrs::api::view::{View, ViewType};
+use swrs::parser::view::models::image::ImageScaleType;
+use swrs::parser::view::models::layout::gravity::Gravity;
+use swrs::parser::view::models::layout::Orientation;
+use swrs::render::layout::{render_screen, render_views, LayoutRenderOptions};
+
+#[test]
+fn render_simple_screen() {
+    let mut main = screen("main");
+    main.layout = vec![linear(
+        "linear1",
+        true,
+        vec![
+            text_view("textview1", "Hello <world>"),
+            button("button1", "Click me"),
+            View::new(
+                "imageview1",
+                ViewType::ImageView {
+                    image_res_name: "app_icon".to_string(),
+                    image_scale_type: ImageScaleType::CenterCrop,
+                },
+            ),
+        ],
+    )];
+
+    let svg = render_screen(&main, &LayoutRenderOptions::default());
+
+    assert!(svg.starts_with("<svg"));
+    assert!(svg.trim_end().ends_with("</svg>"));
+    assert!(svg.contains(r#"class="toolbar""#));
+    assert!(svg.contains(">main</text>"));
+    assert!(svg.contains("Hello &lt;world&gt;"));
+    assert!(svg.contains(">Click me</text>"));
+    assert!(svg.contains(">app_icon</text>"));
+    assert!(svg.contains(r#"data-id="textview1""#));
+    assert!(!svg.contains(r#"class="fab""#));
+}
+
+#[test]
+fn render_screen_decorations() {
+    let mut main = screen("main");
+    main.toolbar_enabled = false;
+    main.fab_enabled = true;
+    main.fab = Some(View::new(
+        "_fab",
+        ViewType::Fab {
+            image_res_name: "ic_add_white".to_string(),
+        },
+    ));
+
+    let svg = render_screen(&main, &LayoutRenderOptions::default());
+
+    assert!(!svg.contains(r#"class="toolbar""#));
+    assert!(svg.contains(r#"class="fab""#));
+    assert!(svg.contains(">ic_add_white</text>"));
+}
+
+#[test]
+fn render_custom_view() {
+    let svg = render_views(
+        &[text_view("textview1", "item")],
+        &LayoutRenderOptions::default(),
+    );
+
+    assert!(svg.contains(">item</text>"));
+    assert!(!svg.contains(r#"class="toolbar""#));
+}
+
+#[test]
+fn render_unique_clip_ids() {
+    let scroll = || {
+        let mut scroll = View::new(
+            "vscroll1",
+            ViewType::ScrollView {
+                orientation: Orientation::Vertical,
+                gravity: Gravity::default(),
+            },
+        );
+        scroll.children = vec![text_view("textview1", "item")];
+        scroll
+    };
+
+    let mut main = screen("main");
+    main.layout = vec![scroll()];
+    let mut second = screen("second");
+    second.layout = vec![scroll()];
+
+    let options = LayoutRenderOptions::default();
+    let main = render_screen(&main, &options);
+    let second = render_screen(&second, &options);
+
+    assert!(main.contains(r#"<clipPath id="main-content">"#));
+    assert!(main.contains(r#"<clipPath id="main-clip-vscroll1">"#));
+    assert!(second.contains(r#"<clipPath id="second-content">"#));
+    assert!(second.contains(r#"<g clip-path="url(#second-clip-vscroll1)">"#));
+}